@@ -0,0 +1,361 @@
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Method;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use super::bookmarks::ReadingStatus;
+use super::config::MangaDexAccount;
+use super::mangadex::{self, ApiError, Manga, BASE_URL};
+
+const AUTH_URL: &str = "https://auth.mangadex.org/realms/mangadex/protocol/openid-connect/token";
+/// Reading status given to manga bookmarked here that have none, here or on the account
+const BOOKMARK_STATUS: ReadingStatus = ReadingStatus::Reading;
+/// Refresh tokens this long before they expire, so a request doesn't race the expiry
+const TOKEN_MARGIN: Duration = Duration::from_secs(30);
+
+struct Session {
+    access_token: String,
+    refresh_token: String,
+    expires_at: Instant,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: String,
+    expires_in: u64,
+}
+
+static SESSION: Mutex<Option<Session>> = Mutex::const_new(None);
+
+async fn request_token(form: &[(&str, &str)]) -> Result<TokenResponse, ApiError> {
+    let body: Vec<String> = form
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, urlencoding::encode(value)))
+        .collect();
+    let request = mangadex::http_client()
+        .post(AUTH_URL)
+        .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(body.join("&"));
+
+    let _permit = mangadex::acquire_fetch_permit().await;
+    let response = mangadex::send_request("POST", AUTH_URL, request).await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(ApiError::Status(status));
+    }
+    // Not `parse_json`, which would log the tokens on failure
+    serde_json::from_str(&response.text().await?).map_err(ApiError::Parse)
+}
+
+/// A valid access token, refreshing the session or logging in again as needed.
+async fn access_token(account: &MangaDexAccount) -> Result<String, ApiError> {
+    let mut session = SESSION.lock().await;
+    if let Some(current) = session.as_ref()
+        && Instant::now() + TOKEN_MARGIN < current.expires_at
+    {
+        return Ok(current.access_token.clone());
+    }
+
+    let credentials = [
+        ("client_id", account.client_id.as_str()),
+        ("client_secret", account.client_secret.as_str()),
+    ];
+    let refreshed = match session.as_ref() {
+        Some(current) => {
+            let mut form = vec![("grant_type", "refresh_token"), ("refresh_token", current.refresh_token.as_str())];
+            form.extend(credentials);
+            request_token(&form).await.ok()
+        }
+        None => None,
+    };
+    let token = match refreshed {
+        Some(token) => token,
+        None => {
+            let mut form = vec![
+                ("grant_type", "password"),
+                ("username", account.username.as_str()),
+                ("password", account.password.as_str()),
+            ];
+            form.extend(credentials);
+            request_token(&form).await?
+        }
+    };
+
+    *session = Some(Session {
+        access_token: token.access_token.clone(),
+        refresh_token: token.refresh_token,
+        expires_at: Instant::now() + Duration::from_secs(token.expires_in),
+    });
+    Ok(token.access_token)
+}
+
+/// Sends an authenticated request and returns the response body.
+async fn send_authed(
+    account: &MangaDexAccount,
+    method: Method,
+    url: &str,
+    json_body: Option<String>,
+) -> Result<String, ApiError> {
+    let token = access_token(account).await?;
+    let mut request = mangadex::http_client().request(method.clone(), url).bearer_auth(token);
+    if let Some(body) = json_body {
+        request = request.header(CONTENT_TYPE, "application/json").body(body);
+    }
+
+    let _permit = mangadex::acquire_fetch_permit().await;
+    let response = mangadex::send_request(method.as_str(), url, request).await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(ApiError::Status(status));
+    }
+    let body = response.text().await?;
+    mangadex::record_download(body.len());
+    Ok(body)
+}
+
+#[derive(Deserialize)]
+struct StatusesResponse {
+    /// An object by manga ID, or an empty array when the account has none
+    #[serde(default)]
+    statuses: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct StatusResponse {
+    status: Option<String>,
+}
+
+/// The local status a MangaDex one stands for. MangaDex uses the same names, plus
+/// `on_hold` and `re_reading`, which have no local match.
+fn parse_status(status: &str) -> Option<ReadingStatus> {
+    serde_json::from_value(serde_json::Value::from(status)).ok()
+}
+
+/// Every manga the account has a reading status for, with that status.
+async fn reading_statuses(account: &MangaDexAccount) -> Result<HashMap<String, String>, ApiError> {
+    let body = send_authed(account, Method::GET, &format!("{}/manga/status", BASE_URL), None).await?;
+    let response: StatusesResponse = mangadex::parse_json(&body)?;
+    Ok(response
+        .statuses
+        .as_object()
+        .map(|statuses| {
+            statuses
+                .iter()
+                .filter_map(|(id, status)| Some((id.clone(), status.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// The account's reading status for one manga, if it has one.
+async fn reading_status(account: &MangaDexAccount, manga_id: &str) -> Result<Option<String>, ApiError> {
+    let url = format!("{}/manga/{}/status", BASE_URL, manga_id);
+    let body = send_authed(account, Method::GET, &url, None).await?;
+    let response: StatusResponse = mangadex::parse_json(&body)?;
+    Ok(response.status)
+}
+
+/// Sets the account's reading status for a manga.
+pub async fn push_status(account: &MangaDexAccount, manga_id: &str, status: ReadingStatus) -> Result<(), ApiError> {
+    send_authed(
+        account,
+        Method::POST,
+        &format!("{}/manga/{}/status", BASE_URL, manga_id),
+        Some(serde_json::json!({ "status": status }).to_string()),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Follows a manga and gives it `status`, or unfollows it and clears its status. Without a
+/// local status, one the account already has is kept and only a manga with none is marked
+/// as reading.
+pub async fn push_bookmark(
+    account: &MangaDexAccount,
+    manga_id: &str,
+    bookmarked: bool,
+    status: Option<ReadingStatus>,
+) -> Result<(), ApiError> {
+    let follow_url = format!("{}/manga/{}/follow", BASE_URL, manga_id);
+    if !bookmarked {
+        send_authed(account, Method::DELETE, &follow_url, None).await?;
+        send_authed(
+            account,
+            Method::POST,
+            &format!("{}/manga/{}/status", BASE_URL, manga_id),
+            Some(serde_json::json!({ "status": null }).to_string()),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    send_authed(account, Method::POST, &follow_url, None).await?;
+    let status = match status {
+        Some(status) => Some(status),
+        None => reading_status(account, manga_id).await?.is_none().then_some(BOOKMARK_STATUS),
+    };
+    if let Some(status) = status {
+        push_status(account, manga_id, status).await?;
+    }
+    Ok(())
+}
+
+/// What a sync changes on each side.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SyncPlan {
+    /// On the account only; bookmarked locally
+    pub pull_add: Vec<String>,
+    /// Removed from the account since the last sync; unbookmarked locally
+    pub pull_remove: Vec<String>,
+    /// Local changes sent to the account, as (manga ID, bookmarked)
+    pub push: Vec<(String, bool)>,
+}
+
+/// Reconciles local bookmarks with the account, letting the most recent change win. A
+/// bookmark added or removed here since the last sync (`pending`) is newer than the
+/// account's state and is pushed. A bookmark only one side has that both had at the last
+/// sync (`last_synced`) was removed from the other side since. Anything else only one side
+/// has is new there, and is added to the other.
+pub fn plan_sync(
+    local: &HashSet<String>,
+    remote: &HashSet<String>,
+    pending: &HashMap<String, bool>,
+    last_synced: &HashSet<String>,
+) -> SyncPlan {
+    let mut plan = SyncPlan::default();
+
+    for id in remote.difference(local) {
+        let removed_here = match pending.get(id) {
+            Some(bookmarked) => !bookmarked,
+            None => last_synced.contains(id),
+        };
+        if removed_here {
+            plan.push.push((id.clone(), false));
+        } else {
+            plan.pull_add.push(id.clone());
+        }
+    }
+    for id in local.difference(remote) {
+        if !pending.contains_key(id) && last_synced.contains(id) {
+            plan.pull_remove.push(id.clone());
+        } else {
+            plan.push.push((id.clone(), true));
+        }
+    }
+
+    plan.pull_add.sort();
+    plan.pull_remove.sort();
+    plan.push.sort();
+    plan
+}
+
+pub struct SyncResult {
+    pub added: Vec<Manga>,
+    pub removed: Vec<String>,
+    pub pushed: usize,
+    /// Pushes that failed, as (manga ID, bookmarked), to retry at the next sync
+    pub failed: Vec<(String, bool)>,
+    /// The account's reading statuses that have a local match
+    pub statuses: HashMap<String, ReadingStatus>,
+}
+
+/// Fetches the account's statuses, pushes local changes and gathers what to pull.
+/// `local_statuses` are sent along with the bookmarks pushed.
+pub async fn sync_bookmarks(
+    account: &MangaDexAccount,
+    local: &HashSet<String>,
+    local_statuses: &HashMap<String, ReadingStatus>,
+    pending: &HashMap<String, bool>,
+    last_synced: &HashSet<String>,
+) -> Result<SyncResult, ApiError> {
+    let remote_statuses = reading_statuses(account).await?;
+    let remote: HashSet<String> = remote_statuses.keys().cloned().collect();
+    let plan = plan_sync(local, &remote, pending, last_synced);
+
+    let mut failed = Vec::new();
+    for (id, bookmarked) in &plan.push {
+        // Anything pushed as bookmarked isn't on the account, so has no status there
+        let status = local_statuses.get(id).copied().unwrap_or(BOOKMARK_STATUS);
+        if let Err(e) = push_bookmark(account, id, *bookmarked, Some(status)).await {
+            log::warn!("Failed to push bookmark {} to MangaDex: {}", id, e);
+            failed.push((id.clone(), *bookmarked));
+        }
+    }
+
+    let mut added = Vec::new();
+    for batch in plan.pull_add.chunks(100) {
+        added.extend(mangadex::get_manga_by_ids(batch).await?);
+    }
+
+    Ok(SyncResult {
+        added,
+        removed: plan.pull_remove,
+        pushed: plan.push.len() - failed.len(),
+        failed,
+        statuses: remote_statuses
+            .iter()
+            .filter_map(|(id, status)| Some((id.clone(), parse_status(status)?)))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(list: &[&str]) -> HashSet<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_plan_sync_prefers_latest_change() {
+        let local = ids(&["both", "added-here", "removed-there"]);
+        let remote = ids(&["both", "added-there", "removed-here"]);
+        let last_synced = ids(&["both", "removed-there", "removed-here"]);
+        let pending = HashMap::from([("added-here".to_string(), true), ("removed-here".to_string(), false)]);
+
+        let plan = plan_sync(&local, &remote, &pending, &last_synced);
+        assert_eq!(plan.pull_add, ["added-there"]);
+        assert_eq!(plan.pull_remove, ["removed-there"]);
+        assert_eq!(
+            plan.push,
+            [("added-here".to_string(), true), ("removed-here".to_string(), false)]
+        );
+
+        // The first sync merges instead of treating missing entries as removals
+        let plan = plan_sync(&local, &remote, &HashMap::new(), &HashSet::new());
+        assert!(plan.pull_remove.is_empty());
+        assert_eq!(plan.push.len(), 2);
+        assert_eq!(plan.pull_add.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_status_matches_local_statuses() {
+        assert_eq!(parse_status("plan_to_read"), Some(ReadingStatus::PlanToRead));
+        assert_eq!(parse_status("completed"), Some(ReadingStatus::Completed));
+        assert_eq!(parse_status("on_hold"), None);
+        assert_eq!(
+            serde_json::json!({ "status": ReadingStatus::PlanToRead }).to_string(),
+            r#"{"status":"plan_to_read"}"#
+        );
+    }
+
+    #[test]
+    fn test_plan_sync_after_bookmarking_with_sync_off() {
+        // Synced once, then "offline" and "dropped-offline" changed while syncing was off
+        let last_synced = ids(&["kept", "dropped-offline"]);
+        let local = ids(&["kept", "offline"]);
+        let remote = ids(&["kept", "dropped-offline"]);
+
+        let plan = plan_sync(&local, &remote, &HashMap::new(), &last_synced);
+        assert!(plan.pull_remove.is_empty());
+        assert!(plan.pull_add.is_empty());
+        assert_eq!(
+            plan.push,
+            [("dropped-offline".to_string(), false), ("offline".to_string(), true)]
+        );
+    }
+}
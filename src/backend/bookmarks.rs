@@ -1,25 +1,135 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use super::mangadex::Manga;
+use super::config::{app_data_dir, BookmarkSort};
+use super::local;
+use super::mangadex::{Manga, Tag};
+use super::recency::Recency;
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Bookmarks {
+    /// File format version; files written before versioning read as 0
+    #[serde(default)]
+    pub version: u32,
     pub manga_ids: HashSet<String>,
     #[serde(default)]
     pub manga_cache: Vec<BookmarkedManga>,
+    /// Unix time of the last new-chapter check
+    #[serde(default)]
+    pub last_checked: Option<u64>,
+    /// Bookmarked manga with chapters released since they were last opened
+    #[serde(default)]
+    pub updated_ids: HashSet<String>,
+    /// Favorites listed ahead of the other bookmarks
+    #[serde(default)]
+    pub pinned: HashSet<String>,
+    /// Bookmarks added (true) or removed (false) here and not yet pushed to MangaDex
+    #[serde(default)]
+    pub sync_pending: HashMap<String, bool>,
+    /// Username of the MangaDex account bookmarks were last synced with
+    #[serde(default)]
+    pub synced_account: Option<String>,
+    /// Bookmarks both sides had after that sync, to tell which side removed one since
+    #[serde(default)]
+    pub synced_ids: HashSet<String>,
+    /// Reading statuses set from the cards, for bookmarked manga or not
+    #[serde(default)]
+    pub statuses: HashMap<String, ReadingStatus>,
+    /// Order `statuses` were last changed in, for dropping the oldest
+    #[serde(default)]
+    pub status_order: Recency,
+    /// Record changes in `sync_pending`; set at startup when syncing is on
+    #[serde(skip)]
+    pub track_sync: bool,
+}
+
+impl Default for Bookmarks {
+    fn default() -> Self {
+        Bookmarks {
+            version: BOOKMARKS_VERSION,
+            manga_ids: HashSet::new(),
+            manga_cache: Vec::new(),
+            last_checked: None,
+            updated_ids: HashSet::new(),
+            pinned: HashSet::new(),
+            sync_pending: HashMap::new(),
+            synced_account: None,
+            synced_ids: HashSet::new(),
+            statuses: HashMap::new(),
+            status_order: Recency::default(),
+            track_sync: false,
+        }
+    }
+}
+
+/// A quick triage status for a manga, cycled from its card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadingStatus {
+    Reading,
+    PlanToRead,
+    Completed,
+    Dropped,
+}
+
+impl ReadingStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            ReadingStatus::Reading => "Reading",
+            ReadingStatus::PlanToRead => "Plan to Read",
+            ReadingStatus::Completed => "Completed",
+            ReadingStatus::Dropped => "Dropped",
+        }
+    }
+
+    /// The status that follows `current`, ending with none before starting over.
+    pub fn cycle(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(ReadingStatus::Reading),
+            Some(ReadingStatus::Reading) => Some(ReadingStatus::PlanToRead),
+            Some(ReadingStatus::PlanToRead) => Some(ReadingStatus::Completed),
+            Some(ReadingStatus::Completed) => Some(ReadingStatus::Dropped),
+            Some(ReadingStatus::Dropped) => None,
+        }
+    }
+}
+
+/// Current `bookmarks.json` format. Bump it and add a step to `migrate` when the format changes.
+const BOOKMARKS_VERSION: u32 = 1;
+
+/// Reading statuses kept for manga that aren't bookmarked; bookmarked ones keep theirs
+const MAX_UNBOOKMARKED_STATUSES: usize = 500;
+
+/// Minimum time between new-chapter checks on launch
+const UPDATE_CHECK_INTERVAL_SECS: u64 = 15 * 60;
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BookmarkedManga {
     pub id: String,
     pub title: String,
+    #[serde(default)]
+    pub alt_titles: Vec<String>,
     pub author: String,
     pub status: String,
     pub description: String,
     pub cover_url: String,
+    /// Latest uploaded chapter as of the last check
+    #[serde(default)]
+    pub latest_chapter_id: Option<String>,
+    #[serde(default)]
+    pub content_rating: String,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
 }
 
 impl From<&Manga> for BookmarkedManga {
@@ -27,10 +137,14 @@ impl From<&Manga> for BookmarkedManga {
         BookmarkedManga {
             id: manga.id.clone(),
             title: manga.title.clone(),
+            alt_titles: manga.alt_titles.clone(),
             author: manga.author.clone(),
             status: manga.status.clone(),
             description: manga.description.clone(),
             cover_url: manga.cover_url.clone(),
+            latest_chapter_id: manga.latest_chapter_id.clone(),
+            content_rating: manga.content_rating.clone(),
+            tags: manga.tags.clone(),
         }
     }
 }
@@ -40,37 +154,106 @@ impl From<&BookmarkedManga> for Manga {
         Manga {
             id: bm.id.clone(),
             title: bm.title.clone(),
+            alt_titles: bm.alt_titles.clone(),
             author: bm.author.clone(),
             artist: String::new(),
             status: bm.status.clone(),
             description: bm.description.clone(),
             cover_url: bm.cover_url.clone(),
+            latest_chapter_id: bm.latest_chapter_id.clone(),
+            content_rating: bm.content_rating.clone(),
+            tags: bm.tags.clone(),
         }
     }
 }
 
+/// Upgrades a parsed bookmarks file to `BOOKMARKS_VERSION`, one version at a time.
+fn migrate(mut value: serde_json::Value) -> Result<Bookmarks, String> {
+    let mut version = file_version(&value);
+    while version < BOOKMARKS_VERSION {
+        match version {
+            // Version 1 only added the version field itself
+            0 => {}
+            _ => unreachable!("no migration from bookmarks version {}", version),
+        }
+        version += 1;
+    }
+
+    let mut bookmarks: Bookmarks = serde_json::from_value(value.take()).map_err(|e| e.to_string())?;
+    bookmarks.version = BOOKMARKS_VERSION;
+    Ok(bookmarks)
+}
+
+fn file_version(value: &serde_json::Value) -> u32 {
+    value.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32
+}
+
+/// Copies `path` to `bookmarks.json.bak`, for a file the next save would lose data from.
+fn back_up(path: &Path, reason: &str) {
+    let backup = path.with_extension("json.bak");
+    match fs::copy(path, &backup) {
+        Ok(_) => log::warn!("Backed up {} to {}: {}", path.display(), backup.display(), reason),
+        Err(e) => log::warn!("Couldn't back up {} ({}): {}", path.display(), reason, e),
+    }
+}
+
 fn get_bookmarks_path() -> PathBuf {
-    let config_dir = dirs::config_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("tachiyomi-tui");
-    
-    fs::create_dir_all(&config_dir).ok();
-    config_dir.join("bookmarks.json")
+    app_data_dir().join("bookmarks.json")
 }
 
 impl Bookmarks {
     pub fn load() -> Self {
-        let path = get_bookmarks_path();
-        
-        if path.exists() {
-            if let Ok(content) = fs::read_to_string(&path) {
-                if let Ok(bookmarks) = serde_json::from_str(&content) {
-                    return bookmarks;
-                }
+        let mut bookmarks = Self::load_from(&get_bookmarks_path());
+        let pruned = bookmarks.prune();
+        if pruned > 0 {
+            log::info!("Pruned {} orphaned entries from bookmarks.json", pruned);
+            bookmarks.save();
+        }
+        bookmarks
+    }
+
+    /// Drops cached manga, update flags and pins for IDs that are no longer bookmarked, as
+    /// happens when `manga_ids` is edited by hand, along with duplicate cache entries.
+    /// Returns how many entries were removed.
+    fn prune(&mut self) -> usize {
+        let before = self.manga_cache.len() + self.updated_ids.len() + self.pinned.len();
+
+        let mut seen = HashSet::new();
+        let ids = &self.manga_ids;
+        self.manga_cache.retain(|m| ids.contains(&m.id) && seen.insert(m.id.clone()));
+        self.updated_ids.retain(|id| ids.contains(id));
+        self.pinned.retain(|id| ids.contains(id));
+
+        before - (self.manga_cache.len() + self.updated_ids.len() + self.pinned.len())
+    }
+
+    /// Reads and migrates a bookmarks file. A file that can't be parsed, or was written by
+    /// a newer version whose fields would be dropped on the next save, is first copied to
+    /// `bookmarks.json.bak` so the next save doesn't destroy the only copy.
+    fn load_from(path: &Path) -> Self {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Bookmarks::default();
+        };
+
+        let value = serde_json::from_str::<serde_json::Value>(&content).map_err(|e| e.to_string());
+        if let Ok(value) = &value
+            && file_version(value) > BOOKMARKS_VERSION
+        {
+            let reason = format!(
+                "version {} is newer than this build understands ({})",
+                file_version(value),
+                BOOKMARKS_VERSION
+            );
+            back_up(path, &reason);
+        }
+
+        match value.and_then(migrate) {
+            Ok(bookmarks) => bookmarks,
+            Err(err) => {
+                back_up(path, &format!("couldn't read it ({})", err));
+                Bookmarks::default()
             }
         }
-        
-        Bookmarks::default()
     }
 
     pub fn save(&self) {
@@ -82,6 +265,7 @@ impl Bookmarks {
 
     pub fn add(&mut self, manga: &Manga) {
         self.manga_ids.insert(manga.id.clone());
+        self.record_sync_change(&manga.id, true);
         
         // Update cache if not already present
         if !self.manga_cache.iter().any(|m| m.id == manga.id) {
@@ -93,7 +277,89 @@ impl Bookmarks {
 
     pub fn remove(&mut self, manga_id: &str) {
         self.manga_ids.remove(manga_id);
+        self.record_sync_change(manga_id, false);
         self.manga_cache.retain(|m| m.id != manga_id);
+        self.updated_ids.remove(manga_id);
+        self.pinned.remove(manga_id);
+        self.save();
+    }
+
+    fn record_sync_change(&mut self, manga_id: &str, bookmarked: bool) {
+        if self.track_sync && !local::is_local(manga_id) {
+            self.sync_pending.insert(manga_id.to_string(), bookmarked);
+        }
+    }
+
+    /// Bookmarked MangaDex IDs, leaving out the local library's.
+    pub fn syncable_ids(&self) -> HashSet<String> {
+        self.manga_ids.iter().filter(|id| !local::is_local(id)).cloned().collect()
+    }
+
+    /// What to sync against `username`'s account: the pending changes and the bookmarks as of
+    /// the last sync. Both are empty for an account not synced with before, so it merges.
+    pub fn sync_state(&self, username: &str) -> (HashMap<String, bool>, HashSet<String>) {
+        if self.synced_account.as_deref() == Some(username) {
+            (self.sync_pending.clone(), self.synced_ids.clone())
+        } else {
+            (HashMap::new(), HashSet::new())
+        }
+    }
+
+    /// Records that `username`'s account has a change, forgetting it as pending unless it
+    /// was changed again since.
+    pub fn confirm_push(&mut self, username: &str, manga_id: &str, bookmarked: bool) {
+        if self.synced_account.as_deref() == Some(username) {
+            if bookmarked {
+                self.synced_ids.insert(manga_id.to_string());
+            } else {
+                self.synced_ids.remove(manga_id);
+            }
+        }
+        if self.sync_pending.get(manga_id) == Some(&bookmarked) {
+            self.sync_pending.remove(manga_id);
+        }
+        self.save();
+    }
+
+    /// Applies a finished sync with `username`'s account: bookmarks pulled from it are added
+    /// or removed without being queued to push back, the changes in `sent` are cleared, and
+    /// pushes that `failed` stay pending for the next sync. The account's `statuses` fill in
+    /// for manga with none set here.
+    pub fn apply_sync(
+        &mut self,
+        username: &str,
+        added: &[Manga],
+        removed: &[String],
+        sent: &HashMap<String, bool>,
+        failed: &[(String, bool)],
+        statuses: &HashMap<String, ReadingStatus>,
+    ) {
+        for manga in added {
+            if self.manga_ids.insert(manga.id.clone()) {
+                self.manga_cache.push(BookmarkedManga::from(manga));
+            }
+        }
+        for id in removed {
+            self.manga_ids.remove(id);
+        }
+        self.prune();
+
+        for (id, bookmarked) in sent {
+            if self.sync_pending.get(id) == Some(bookmarked) {
+                self.sync_pending.remove(id);
+            }
+        }
+        for (id, bookmarked) in failed {
+            self.sync_pending.entry(id.clone()).or_insert(*bookmarked);
+        }
+        for (id, status) in statuses {
+            if !self.statuses.contains_key(id) {
+                self.statuses.insert(id.clone(), *status);
+                self.status_order.touch(id);
+            }
+        }
+        self.synced_account = Some(username.to_string());
+        self.synced_ids = self.syncable_ids();
         self.save();
     }
 
@@ -111,7 +377,259 @@ impl Bookmarks {
         }
     }
 
-    pub fn get_bookmarked_manga(&self) -> Vec<Manga> {
-        self.manga_cache.iter().map(Manga::from).collect()
+    /// Updates cached metadata from freshly fetched manga. Returns the IDs whose cover changed.
+    pub fn refresh_cached(&mut self, fresh: &[Manga]) -> Vec<String> {
+        let mut changed = false;
+        let mut cover_changed = Vec::new();
+
+        for manga in fresh {
+            let Some(cached) = self.manga_cache.iter_mut().find(|m| m.id == manga.id) else {
+                continue;
+            };
+            let updated = BookmarkedManga::from(manga);
+
+            if cached.cover_url != updated.cover_url && !updated.cover_url.is_empty() {
+                cover_changed.push(manga.id.clone());
+                cached.cover_url = updated.cover_url;
+                changed = true;
+            }
+            if cached.title != updated.title
+                || cached.status != updated.status
+                || cached.author != updated.author
+                || cached.description != updated.description
+                || cached.alt_titles != updated.alt_titles
+                || cached.content_rating != updated.content_rating
+                || cached.tags != updated.tags
+            {
+                cached.title = updated.title;
+                cached.status = updated.status;
+                cached.author = updated.author;
+                cached.description = updated.description;
+                cached.alt_titles = updated.alt_titles;
+                cached.content_rating = updated.content_rating;
+                cached.tags = updated.tags;
+                changed = true;
+            }
+        }
+
+        if changed {
+            self.save();
+        }
+        cover_changed
+    }
+
+    pub fn update_check_due(&self) -> bool {
+        self.last_checked
+            .is_none_or(|t| unix_now().saturating_sub(t) >= UPDATE_CHECK_INTERVAL_SECS)
+    }
+
+    /// Compares fresh latest-chapter IDs with the stored ones, flagging manga that got new
+    /// chapters. Returns how many were newly flagged.
+    pub fn record_latest_chapters(&mut self, fresh: &[Manga]) -> usize {
+        let mut newly_updated = 0;
+
+        for manga in fresh {
+            let Some(latest) = &manga.latest_chapter_id else {
+                continue;
+            };
+            let Some(cached) = self.manga_cache.iter_mut().find(|m| m.id == manga.id) else {
+                continue;
+            };
+
+            // The first check only records a baseline
+            if cached.latest_chapter_id.as_ref().is_some_and(|seen| seen != latest)
+                && self.updated_ids.insert(manga.id.clone())
+            {
+                newly_updated += 1;
+            }
+            cached.latest_chapter_id = Some(latest.clone());
+        }
+
+        self.last_checked = Some(unix_now());
+        self.save();
+        newly_updated
+    }
+
+    pub fn has_updates(&self, manga_id: &str) -> bool {
+        self.updated_ids.contains(manga_id)
+    }
+
+    pub fn clear_update(&mut self, manga_id: &str) {
+        if self.updated_ids.remove(manga_id) {
+            self.save();
+        }
+    }
+
+    pub fn is_pinned(&self, manga_id: &str) -> bool {
+        self.pinned.contains(manga_id)
+    }
+
+    /// Pins or unpins a bookmarked manga. Returns whether it is now pinned.
+    pub fn toggle_pin(&mut self, manga_id: &str) -> bool {
+        let pinned = if self.pinned.remove(manga_id) {
+            false
+        } else if self.is_bookmarked(manga_id) {
+            self.pinned.insert(manga_id.to_string())
+        } else {
+            return false;
+        };
+        self.save();
+        pinned
+    }
+
+    pub fn status(&self, manga_id: &str) -> Option<ReadingStatus> {
+        self.statuses.get(manga_id).copied()
+    }
+
+    /// Moves a manga to its next reading status. Returns the new status.
+    pub fn cycle_status(&mut self, manga_id: &str) -> Option<ReadingStatus> {
+        let next = ReadingStatus::cycle(self.status(manga_id));
+        match next {
+            Some(status) => self.statuses.insert(manga_id.to_string(), status),
+            None => self.statuses.remove(manga_id),
+        };
+        self.status_order.touch(manga_id);
+        let bookmarked = &self.manga_ids;
+        let cap = MAX_UNBOOKMARKED_STATUSES + self.statuses.keys().filter(|id| bookmarked.contains(*id)).count();
+        self.status_order.evict(&mut self.statuses, cap, |id| bookmarked.contains(id));
+        self.save();
+        next
+    }
+
+    /// Bookmarked manga with pinned ones first, each group in `sort` order.
+    pub fn get_bookmarked_manga(&self, sort: BookmarkSort) -> Vec<Manga> {
+        let mut manga: Vec<Manga> = self.manga_cache.iter().map(Manga::from).collect();
+        match sort {
+            BookmarkSort::Added => manga.sort_by_key(|m| !self.is_pinned(&m.id)),
+            BookmarkSort::Title => {
+                manga.sort_by_cached_key(|m| (!self.is_pinned(&m.id), m.title.to_lowercase()))
+            }
+        }
+        manga
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tachiyomi-tui-test-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bookmarks.json");
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_corrupt_file_is_backed_up() {
+        let path = temp_file("corrupt", "{\"manga_ids\": [\"abc\"");
+
+        let bookmarks = Bookmarks::load_from(&path);
+        assert!(bookmarks.manga_ids.is_empty());
+
+        let backup = fs::read_to_string(path.with_extension("json.bak")).unwrap();
+        assert_eq!(backup, "{\"manga_ids\": [\"abc\"");
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_unversioned_file_is_migrated() {
+        let path = temp_file("v0", r#"{"manga_ids": ["abc"], "manga_cache": []}"#);
+
+        let bookmarks = Bookmarks::load_from(&path);
+        assert_eq!(bookmarks.version, BOOKMARKS_VERSION);
+        assert!(bookmarks.is_bookmarked("abc"));
+        assert!(!path.with_extension("json.bak").exists());
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_newer_file_is_backed_up() {
+        let content = r#"{"version": 99, "manga_ids": ["abc"], "shelves": {"abc": "later"}}"#;
+        let path = temp_file("newer", content);
+
+        let bookmarks = Bookmarks::load_from(&path);
+        assert!(bookmarks.is_bookmarked("abc"));
+        assert_eq!(fs::read_to_string(path.with_extension("json.bak")).unwrap(), content);
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_prune_drops_orphaned_cache_entries() {
+        let entry = |id: &str| {
+            format!(
+                r#"{{"id": "{}", "title": "", "author": "", "status": "", "description": "", "cover_url": ""}}"#,
+                id
+            )
+        };
+        let content = format!(
+            r#"{{"manga_ids": ["a", "b"], "manga_cache": [{}, {}, {}], "pinned": ["b"]}}"#,
+            entry("a"),
+            entry("b"),
+            entry("a")
+        );
+        let mut bookmarks: Bookmarks = serde_json::from_str(&content).unwrap();
+        assert_eq!(bookmarks.prune(), 1);
+
+        // Removing "b" from the IDs behind the app's back, as a hand edit would
+        bookmarks.manga_ids.remove("b");
+        assert_eq!(bookmarks.prune(), 2);
+
+        let cached: Vec<&str> = bookmarks.manga_cache.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(cached, ["a"]);
+        assert!(bookmarks.pinned.is_empty());
+        assert_eq!(bookmarks.prune(), 0);
+    }
+
+    #[test]
+    fn test_title_sort_keeps_pinned_first() {
+        let entry = |id: &str, title: &str| {
+            format!(
+                r#"{{"id": "{}", "title": "{}", "author": "", "status": "", "description": "", "cover_url": ""}}"#,
+                id, title
+            )
+        };
+        let content = format!(
+            r#"{{"manga_ids": ["a", "b", "c", "d"], "manga_cache": [{}, {}, {}, {}], "pinned": ["d"]}}"#,
+            entry("a", "Vagabond"),
+            entry("b", "berserk"),
+            entry("c", "Akira"),
+            entry("d", "Monster")
+        );
+        let bookmarks: Bookmarks = serde_json::from_str(&content).unwrap();
+        let ids = |sort| -> Vec<String> {
+            bookmarks.get_bookmarked_manga(sort).into_iter().map(|m| m.id).collect()
+        };
+
+        assert_eq!(ids(BookmarkSort::Added), ["d", "a", "b", "c"]);
+        assert_eq!(ids(BookmarkSort::Title), ["d", "c", "b", "a"]);
+    }
+
+    #[test]
+    fn test_reading_status_cycles_back_to_none() {
+        let mut status = None;
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            status = ReadingStatus::cycle(status);
+            seen.push(status);
+        }
+        assert_eq!(
+            seen,
+            [
+                Some(ReadingStatus::Reading),
+                Some(ReadingStatus::PlanToRead),
+                Some(ReadingStatus::Completed),
+                Some(ReadingStatus::Dropped),
+                None
+            ]
+        );
+
+        let parsed: Bookmarks =
+            serde_json::from_str(r#"{"manga_ids": [], "statuses": {"abc": "plan_to_read"}}"#).unwrap();
+        assert_eq!(parsed.status("abc"), Some(ReadingStatus::PlanToRead));
     }
 }
@@ -1,116 +1,276 @@
+use image::codecs::jpeg::JpegEncoder;
 use image::DynamicImage;
 use std::collections::HashMap;
 use std::fs;
-use std::io::Cursor;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::future::Future;
+use std::io::{self, Cursor};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
-const MAX_MEMORY_PAGES: usize = 50;
+use super::config::{app_cache_dir, check_writable, PageCacheFormat};
+use super::local;
+use super::mangadex::FetchedPage;
+
+pub const MAX_MEMORY_PAGES: usize = 50;
 const MAX_DISK_CACHE_MB: u64 = 500;
+/// How long a chapter's page list is reused. MangaDex@Home URLs stop working after about
+/// 15 minutes, so lists are looked up again a while before that.
+const CHAPTER_URLS_TTL: Duration = Duration::from_secs(10 * 60);
 
 #[derive(Clone)]
 pub struct PageCache {
     inner: Arc<RwLock<PageCacheInner>>,
+    /// `None` when the cache directory can't be written, in which case pages are only
+    /// kept in memory
+    disk: Option<Arc<DiskCache>>,
+    /// Why the disk cache is unavailable, reported once at startup
+    disk_error: Option<String>,
+    /// Pages written to disk since launch
+    saved_this_session: Arc<AtomicUsize>,
+    /// A lock per chapter whose page list is being looked up, so callers asking for the
+    /// same chapter at once share one request
+    url_lookups: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
 }
 
+/// In-memory state; only decoded images live here.
 struct PageCacheInner {
     pages: HashMap<String, DynamicImage>,
     access_order: Vec<String>,
-    chapter_urls: HashMap<String, Vec<String>>,
-    cache_dir: PathBuf,
+    /// Page lists by chapter ID, with when they were fetched
+    chapter_urls: HashMap<String, (Instant, Vec<String>)>,
+}
+
+/// On-disk store of the downloaded page files, keyed by a hash of the URL.
+/// Reads, decodes and writes run on the blocking pool so they never hold the memory lock.
+struct DiskCache {
+    dir: PathBuf,
+    format: PageCacheFormat,
+    jpeg_quality: u8,
 }
 
 impl PageCache {
-    pub fn new() -> Self {
-        let cache_dir = dirs::cache_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("tachiyomi-tui")
-            .join("pages");
-
-        if let Err(e) = fs::create_dir_all(&cache_dir) {
-            eprintln!("Failed to create cache directory: {}", e);
-        }
+    pub fn new(format: PageCacheFormat, jpeg_quality: u8) -> Self {
+        Self::with_dir(app_cache_dir().join("pages"), format, jpeg_quality)
+    }
+
+    fn with_dir(dir: PathBuf, format: PageCacheFormat, jpeg_quality: u8) -> Self {
+        let (disk, disk_error) = match DiskCache::open(dir.clone(), format, jpeg_quality) {
+            Ok(disk) => (Some(Arc::new(disk)), None),
+            Err(e) => {
+                log::warn!("Page cache directory {} is unusable, caching in memory only: {}", dir.display(), e);
+                (None, Some(format!("Can't write to {} ({}); pages won't be cached on disk", dir.display(), e)))
+            }
+        };
 
         Self {
             inner: Arc::new(RwLock::new(PageCacheInner {
                 pages: HashMap::new(),
                 access_order: Vec::new(),
                 chapter_urls: HashMap::new(),
-                cache_dir,
             })),
+            disk,
+            disk_error,
+            saved_this_session: Arc::new(AtomicUsize::new(0)),
+            url_lookups: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
-    pub async fn get_page(&self, url: &str) -> Option<DynamicImage> {
-        let mut inner = self.inner.write().await;
+    /// Why pages are only cached in memory, if the disk cache couldn't be set up.
+    pub fn disk_error(&self) -> Option<&str> {
+        self.disk_error.as_deref()
+    }
+
+    pub fn pages_saved_this_session(&self) -> usize {
+        self.saved_this_session.load(Ordering::Relaxed)
+    }
 
-        if inner.pages.contains_key(url) {
-            let image = inner.pages.get(url).cloned();
-            inner.access_order.retain(|k| k != url);
-            inner.access_order.push(url.to_string());
-            return image;
+    pub async fn get_page(&self, url: &str) -> Option<DynamicImage> {
+        {
+            let mut inner = self.inner.write().await;
+            if let Some(image) = inner.pages.get(url).cloned() {
+                inner.access_order.retain(|k| k != url);
+                inner.access_order.push(url.to_string());
+                return Some(image);
+            }
         }
 
-        if let Some(image) = inner.load_from_disk(url) {
-            inner.insert_memory(url.to_string(), image.clone());
-            return Some(image);
+        let disk = self.disk.clone();
+        let url_owned = url.to_string();
+        let image = tokio::task::spawn_blocking(move || {
+            // Library pages are already on disk, so read them in place
+            if local::is_local(&url_owned) {
+                local::load_image(&url_owned)
+            } else {
+                let disk = disk?;
+                disk.load(&disk.path_for(&url_owned))
+            }
+        })
+        .await
+        .ok()
+        .flatten()?;
+
+        self.inner
+            .write()
+            .await
+            .insert_memory(url.to_string(), image.clone());
+        Some(image)
+    }
+
+    pub async fn insert_page(&self, url: String, page: FetchedPage) {
+        let disk = match &self.disk {
+            Some(disk) if !local::is_local(&url) => disk.clone(),
+            _ => {
+                self.inner.write().await.insert_memory(url, page.image);
+                return;
+            }
+        };
+        let path = disk.path_for(&url);
+        let (saved, image) = match tokio::task::spawn_blocking(move || {
+            (disk.save(&path, &page), page.image)
+        })
+        .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                log::warn!("Failed to write page to disk cache: {}", e);
+                return;
+            }
+        };
+
+        if saved {
+            self.saved_this_session.fetch_add(1, Ordering::Relaxed);
         }
+        self.inner.write().await.insert_memory(url, image);
+    }
 
-        None
+    /// A cached page's file as stored: the original download, or its JPEG re-encode
+    /// with the `jpeg` cache format.
+    pub async fn page_bytes(&self, url: &str) -> Option<Vec<u8>> {
+        let path = self.disk.as_ref()?.path_for(url);
+        tokio::task::spawn_blocking(move || fs::read(path).ok())
+            .await
+            .ok()
+            .flatten()
     }
 
-    pub async fn insert_page(&self, url: String, image: DynamicImage) {
-        let mut inner = self.inner.write().await;
-        inner.save_to_disk(&url, &image);
-        inner.insert_memory(url, image);
+    /// Drops every cached page, from memory and from disk. Chapter page lists are kept,
+    /// since they're small and still valid. Returns the bytes freed on disk.
+    pub async fn clear(&self) -> u64 {
+        {
+            let mut inner = self.inner.write().await;
+            inner.pages.clear();
+            inner.access_order.clear();
+        }
+        let Some(disk) = self.disk.clone() else {
+            return 0;
+        };
+        tokio::task::spawn_blocking(move || disk.clear()).await.unwrap_or(0)
     }
 
+    /// A chapter's page list, while it's recent enough that its URLs still work.
     pub async fn get_chapter_urls(&self, chapter_id: &str) -> Option<Vec<String>> {
         let inner = self.inner.read().await;
-        inner.chapter_urls.get(chapter_id).cloned()
+        inner
+            .chapter_urls
+            .get(chapter_id)
+            .filter(|(fetched, _)| fetched.elapsed() < CHAPTER_URLS_TTL)
+            .map(|(_, urls)| urls.clone())
     }
 
     pub async fn insert_chapter_urls(&self, chapter_id: String, urls: Vec<String>) {
         let mut inner = self.inner.write().await;
-        inner.chapter_urls.insert(chapter_id, urls);
+        inner.chapter_urls.retain(|_, (fetched, _)| fetched.elapsed() < CHAPTER_URLS_TTL);
+        inner.chapter_urls.insert(chapter_id, (Instant::now(), urls));
+    }
+
+    /// A chapter's page list from the cache, or from `fetch` when it isn't there or has
+    /// expired. Lookups of a chapter already being fetched wait for that fetch instead of
+    /// sending their own. Empty lists are returned but not cached.
+    pub async fn chapter_urls(
+        &self,
+        chapter_id: &str,
+        fetch: impl Future<Output = Option<Vec<String>>>,
+    ) -> Option<Vec<String>> {
+        let lookup = self
+            .url_lookups
+            .lock()
+            .unwrap()
+            .entry(chapter_id.to_string())
+            .or_default()
+            .clone();
+        let urls = {
+            let _running = lookup.lock().await;
+            match self.get_chapter_urls(chapter_id).await {
+                Some(urls) => Some(urls),
+                None => {
+                    let urls = fetch.await;
+                    if let Some(urls) = urls.as_ref().filter(|urls| !urls.is_empty()) {
+                        self.insert_chapter_urls(chapter_id.to_string(), urls.clone()).await;
+                    }
+                    urls
+                }
+            }
+        };
+
+        // Forget the lock once no other lookup is holding or waiting on it
+        let mut lookups = self.url_lookups.lock().unwrap();
+        if Arc::strong_count(&lookup) <= 2 {
+            lookups.remove(chapter_id);
+        }
+        urls
     }
 
     pub async fn has_page(&self, url: &str) -> bool {
-        let inner = self.inner.read().await;
-        if inner.pages.contains_key(url) {
+        if self.inner.read().await.pages.contains_key(url) {
             return true;
         }
-        inner.disk_cache_exists(url)
+        local::is_local(url)
+            || self
+                .disk
+                .as_ref()
+                .is_some_and(|disk| disk.path_for(url).exists())
     }
 }
 
 impl PageCacheInner {
     fn insert_memory(&mut self, url: String, image: DynamicImage) {
-        if self.pages.len() >= MAX_MEMORY_PAGES {
-            if let Some(oldest) = self.access_order.first().cloned() {
-                self.pages.remove(&oldest);
-                self.access_order.remove(0);
-            }
+        if self.pages.len() >= MAX_MEMORY_PAGES
+            && !self.pages.contains_key(&url)
+            && let Some(oldest) = self.access_order.first().cloned()
+        {
+            self.pages.remove(&oldest);
+            self.access_order.remove(0);
         }
 
         self.access_order.retain(|k| k != &url);
         self.access_order.push(url.clone());
         self.pages.insert(url, image);
     }
+}
 
-    fn url_to_filename(&self, url: &str) -> PathBuf {
-        let hash = format!("{:x}", md5_hash(url));
-        self.cache_dir.join(hash)
+impl DiskCache {
+    /// Creates the cache directory and checks that files can be written to it.
+    fn open(dir: PathBuf, format: PageCacheFormat, jpeg_quality: u8) -> io::Result<Self> {
+        check_writable(&dir)?;
+        remove_partial_files(&dir);
+
+        Ok(Self {
+            dir,
+            format,
+            jpeg_quality: jpeg_quality.clamp(1, 100),
+        })
     }
 
-    fn disk_cache_exists(&self, url: &str) -> bool {
-        self.url_to_filename(url).exists()
+    fn path_for(&self, url: &str) -> PathBuf {
+        let hash = format!("{:x}", md5_hash(url));
+        self.dir.join(hash)
     }
 
-    fn load_from_disk(&self, url: &str) -> Option<DynamicImage> {
-        let path = self.url_to_filename(url);
-        let bytes = fs::read(&path).ok()?;
+    fn load(&self, path: &Path) -> Option<DynamicImage> {
+        let bytes = fs::read(path).ok()?;
         image::ImageReader::new(Cursor::new(bytes))
             .with_guessed_format()
             .ok()?
@@ -118,19 +278,52 @@ impl PageCacheInner {
             .ok()
     }
 
-    fn save_to_disk(&self, url: &str, image: &DynamicImage) {
+    /// Writes the page under a temporary name and moves it into place once complete, so
+    /// an interrupted write never leaves a truncated file that `has_page` would trust.
+    /// Returns whether the page is now on disk.
+    fn save(&self, path: &Path, page: &FetchedPage) -> bool {
         self.cleanup_old_cache();
 
-        let path = self.url_to_filename(url);
-        if let Ok(mut file) = fs::File::create(&path) {
-            let _ = image.write_to(&mut file, image::ImageFormat::Jpeg);
+        let partial = path.with_extension(PARTIAL_EXTENSION);
+        let written = match self.format {
+            PageCacheFormat::Original => fs::write(&partial, &page.bytes).is_ok(),
+            PageCacheFormat::Jpeg => match fs::File::create(&partial) {
+                Ok(mut file) => {
+                    // JPEG has no alpha channel, so flatten to RGB first
+                    let rgb = DynamicImage::ImageRgb8(page.image.to_rgb8());
+                    let encoder = JpegEncoder::new_with_quality(&mut file, self.jpeg_quality);
+                    rgb.write_with_encoder(encoder).is_ok()
+                }
+                Err(_) => false,
+            },
+        };
+
+        let saved = written && fs::rename(&partial, path).is_ok();
+        if !saved {
+            let _ = fs::remove_file(&partial);
         }
+        saved
+    }
+
+    /// Removes every page file. Returns the bytes freed.
+    fn clear(&self) -> u64 {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return 0;
+        };
+        let mut freed = 0;
+        for entry in entries.filter_map(|e| e.ok()) {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            if fs::remove_file(entry.path()).is_ok() {
+                freed += size;
+            }
+        }
+        freed
     }
 
     fn cleanup_old_cache(&self) {
         let max_bytes = MAX_DISK_CACHE_MB * 1024 * 1024;
 
-        let entries: Vec<_> = fs::read_dir(&self.cache_dir)
+        let entries: Vec<_> = fs::read_dir(&self.dir)
             .ok()
             .map(|rd| {
                 rd.filter_map(|e| e.ok())
@@ -162,7 +355,21 @@ impl PageCacheInner {
     }
 }
 
-fn md5_hash(s: &str) -> u128 {
+/// Page files still being written, left behind if the app stopped mid-write.
+const PARTIAL_EXTENSION: &str = "part";
+
+fn remove_partial_files(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if path.extension().is_some_and(|ext| ext == PARTIAL_EXTENSION) {
+            let _ = fs::remove_file(&path);
+        }
+    }
+}
+
+pub(super) fn md5_hash(s: &str) -> u128 {
     let mut hash: u128 = 0;
     for (i, byte) in s.bytes().enumerate() {
         hash = hash.wrapping_add((byte as u128).wrapping_mul(31u128.wrapping_pow(i as u32)));
@@ -172,6 +379,104 @@ fn md5_hash(s: &str) -> u128 {
 
 impl Default for PageCache {
     fn default() -> Self {
-        Self::new()
+        Self::new(PageCacheFormat::default(), 90)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_falls_back_to_memory_when_dir_cannot_be_created() {
+        // A directory can't be created underneath a regular file
+        let blocker = std::env::temp_dir().join(format!("tachiyomi-tui-cache-{}", std::process::id()));
+        fs::write(&blocker, b"").unwrap();
+        let cache = PageCache::with_dir(blocker.join("pages"), PageCacheFormat::Original, 90);
+
+        assert!(cache.disk_error().is_some());
+
+        let url = "https://example.org/page.png".to_string();
+        let image = DynamicImage::new_rgb8(2, 2);
+        let page = FetchedPage { bytes: Vec::new(), image: image.clone() };
+        cache.insert_page(url.clone(), page).await;
+
+        assert!(cache.has_page(&url).await);
+        assert_eq!(cache.get_page(&url).await, Some(image));
+        assert_eq!(cache.pages_saved_this_session(), 0);
+
+        fs::remove_file(&blocker).ok();
+    }
+
+    #[tokio::test]
+    async fn test_only_pages_written_to_disk_are_counted() {
+        let dir = std::env::temp_dir().join(format!("tachiyomi-tui-saved-{}", std::process::id()));
+        let cache = PageCache::with_dir(dir.clone(), PageCacheFormat::Original, 90);
+        let page = || FetchedPage { bytes: b"page".to_vec(), image: DynamicImage::new_rgb8(2, 2) };
+
+        cache.insert_page("https://example.org/1.png".to_string(), page()).await;
+        assert_eq!(cache.pages_saved_this_session(), 1);
+
+        // The folder disappearing mid-session makes the next write fail
+        fs::remove_dir_all(&dir).unwrap();
+        cache.insert_page("https://example.org/2.png".to_string(), page()).await;
+        assert_eq!(cache.pages_saved_this_session(), 1);
+        assert!(cache.has_page("https://example.org/2.png").await);
+    }
+
+    #[tokio::test]
+    async fn test_chapter_urls_are_fetched_once_until_they_expire() {
+        let blocker = std::env::temp_dir().join(format!("tachiyomi-tui-urls-{}", std::process::id()));
+        fs::write(&blocker, b"").unwrap();
+        let cache = PageCache::with_dir(blocker.join("pages"), PageCacheFormat::Original, 90);
+        let fetches = AtomicUsize::new(0);
+        let fetch = || async {
+            fetches.fetch_add(1, Ordering::Relaxed);
+            tokio::task::yield_now().await;
+            Some(vec!["https://example.org/1.png".to_string()])
+        };
+
+        // A thumbnail preload and a page-list prefetch asking at the same time
+        let (a, b) = tokio::join!(cache.chapter_urls("c1", fetch()), cache.chapter_urls("c1", fetch()));
+        assert_eq!(a, b);
+        assert_eq!(fetches.load(Ordering::Relaxed), 1);
+        assert!(cache.url_lookups.lock().unwrap().is_empty());
+
+        // The at-home URLs have expired by now, so the list is looked up again
+        let stale = Instant::now().checked_sub(CHAPTER_URLS_TTL).unwrap();
+        cache.inner.write().await.chapter_urls.get_mut("c1").unwrap().0 = stale;
+        assert_eq!(cache.get_chapter_urls("c1").await, None);
+        cache.chapter_urls("c1", fetch()).await;
+        assert_eq!(fetches.load(Ordering::Relaxed), 2);
+
+        fs::remove_file(&blocker).ok();
+    }
+
+    #[tokio::test]
+    async fn test_pages_are_moved_into_place_and_partial_files_dropped() {
+        let dir = std::env::temp_dir().join(format!("tachiyomi-tui-partial-{}", std::process::id()));
+        let cache = PageCache::with_dir(dir.clone(), PageCacheFormat::Original, 90);
+
+        let url = "https://example.org/page.png".to_string();
+        let page = FetchedPage { bytes: b"page".to_vec(), image: DynamicImage::new_rgb8(2, 2) };
+        cache.insert_page(url.clone(), page).await;
+
+        let files: Vec<_> = fs::read_dir(&dir).unwrap().filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].extension(), None);
+
+        // A write cut short by a crash is cleaned up when the cache opens again
+        let leftover = files[0].with_extension(PARTIAL_EXTENSION);
+        fs::write(&leftover, b"pa").unwrap();
+        let _ = PageCache::with_dir(dir.clone(), PageCacheFormat::Original, 90);
+        assert!(!leftover.exists());
+        assert!(files[0].exists());
+
+        // Clearing empties the directory and the memory copy alike
+        assert!(cache.clear().await >= 4);
+        assert!(!cache.has_page(&url).await);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        fs::remove_dir_all(&dir).ok();
     }
 }
@@ -0,0 +1,166 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Zip entry date for 1980-01-01, the earliest the format can hold. Pages carry no
+/// meaningful timestamp, and a fixed one keeps archives of the same chapter identical.
+const DOS_DATE: u16 = (1 << 5) | 1;
+/// Entry names are UTF-8
+const UTF8_FLAG: u16 = 1 << 11;
+const ZIP_VERSION: u16 = 20;
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &b| CRC_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8))
+}
+
+/// Replaces characters that can't appear in a file name on common filesystems.
+pub fn safe_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    cleaned.trim().trim_matches('.').to_string()
+}
+
+/// File extension for a page image, from its contents.
+pub fn page_extension(bytes: &[u8]) -> &'static str {
+    image::guess_format(bytes)
+        .ok()
+        .and_then(|format| format.extensions_str().first().copied())
+        .unwrap_or("jpg")
+}
+
+fn too_large() -> io::Error {
+    io::Error::other("chapter is too large for a zip archive")
+}
+
+/// Writes `pages` (entry name and contents, in reading order) as a CBZ at `path`. Pages
+/// are stored rather than compressed, since images don't shrink further. The archive is
+/// written under a temporary name and moved into place, so a cut-short write never
+/// looks like a finished download.
+pub fn write_cbz(path: &Path, pages: &[(String, Vec<u8>)]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let partial = path.with_extension("cbz.part");
+    let result = write_archive(&partial, pages).and_then(|()| fs::rename(&partial, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    result
+}
+
+fn write_archive(path: &Path, pages: &[(String, Vec<u8>)]) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let mut central = Vec::new();
+    let mut offset: u32 = 0;
+
+    for (name, data) in pages {
+        let crc = crc32(data);
+        let size = u32::try_from(data.len()).map_err(|_| too_large())?;
+        let name_len = u16::try_from(name.len()).map_err(|_| too_large())?;
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        header.extend_from_slice(&ZIP_VERSION.to_le_bytes());
+        header.extend_from_slice(&UTF8_FLAG.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // stored
+        header.extend_from_slice(&0u16.to_le_bytes()); // time
+        header.extend_from_slice(&DOS_DATE.to_le_bytes());
+        header.extend_from_slice(&crc.to_le_bytes());
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&name_len.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // extra field
+        header.extend_from_slice(name.as_bytes());
+        out.write_all(&header)?;
+        out.write_all(data)?;
+
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&ZIP_VERSION.to_le_bytes()); // made by
+        central.extend_from_slice(&ZIP_VERSION.to_le_bytes()); // needed
+        central.extend_from_slice(&UTF8_FLAG.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&DOS_DATE.to_le_bytes());
+        central.extend_from_slice(&crc.to_le_bytes());
+        central.extend_from_slice(&size.to_le_bytes());
+        central.extend_from_slice(&size.to_le_bytes());
+        central.extend_from_slice(&name_len.to_le_bytes());
+        central.extend_from_slice(&[0; 12]); // extra, comment, disk, attributes
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+
+        offset = (header.len() as u32)
+            .checked_add(size)
+            .and_then(|entry| offset.checked_add(entry))
+            .ok_or_else(too_large)?;
+    }
+
+    let entries = u16::try_from(pages.len()).map_err(|_| too_large())?;
+    let central_len = u32::try_from(central.len()).map_err(|_| too_large())?;
+    out.write_all(&central)?;
+    out.write_all(&0x0605_4b50u32.to_le_bytes())?;
+    out.write_all(&[0; 4])?; // disk numbers
+    out.write_all(&entries.to_le_bytes())?;
+    out.write_all(&entries.to_le_bytes())?;
+    out.write_all(&central_len.to_le_bytes())?;
+    out.write_all(&offset.to_le_bytes())?;
+    out.write_all(&0u16.to_le_bytes())?; // comment
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_cbz_layout() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(safe_file_name("Vol. 1: Ch/2?"), "Vol. 1_ Ch_2_");
+
+        let dir = std::env::temp_dir().join(format!("tachiyomi-tui-cbz-{}", std::process::id()));
+        let path = dir.join("chapter.cbz");
+        let pages = vec![
+            ("001.png".to_string(), b"first".to_vec()),
+            ("002.png".to_string(), b"second".to_vec()),
+        ];
+        write_cbz(&path, &pages).unwrap();
+
+        let bytes = fs::read(&path).unwrap();
+        assert!(bytes.starts_with(b"PK\x03\x04"));
+        assert!(!dir.join("chapter.cbz.part").exists());
+        // Local headers and data, then a central entry per page, then the end record
+        let local_len: usize = pages.iter().map(|(name, data)| 30 + name.len() + data.len()).sum();
+        let central_len: usize = pages.iter().map(|(name, _)| 46 + name.len()).sum();
+        assert_eq!(bytes.len(), local_len + central_len + 22);
+        let end = &bytes[bytes.len() - 22..];
+        assert!(end.starts_with(b"PK\x05\x06"));
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 2);
+        assert_eq!(u32::from_le_bytes([end[16], end[17], end[18], end[19]]) as usize, local_len);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
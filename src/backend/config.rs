@@ -0,0 +1,418 @@
+use image::imageops::FilterType;
+use ratatui_image::picker::ProtocolType;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use super::mangadex::{
+    FeedOptions, RetryPolicy, DEFAULT_BROWSE_LIMIT, DEFAULT_CONNECT_TIMEOUT_SECS,
+    DEFAULT_MAX_CONCURRENT_FETCHES, DEFAULT_PAGE_FETCH_RETRIES, DEFAULT_PAGE_RETRY_BACKOFF_MS,
+    DEFAULT_REQUEST_TIMEOUT_SECS,
+};
+
+/// Filter used when scaling covers and pages to the terminal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResizeFilter {
+    /// Let ratatui-image pick (nearest-neighbor)
+    #[default]
+    Default,
+    Nearest,
+    Triangle,
+    CatmullRom,
+    Gaussian,
+    Lanczos3,
+}
+
+impl ResizeFilter {
+    pub fn filter_type(self) -> Option<FilterType> {
+        match self {
+            ResizeFilter::Default => None,
+            ResizeFilter::Nearest => Some(FilterType::Nearest),
+            ResizeFilter::Triangle => Some(FilterType::Triangle),
+            ResizeFilter::CatmullRom => Some(FilterType::CatmullRom),
+            ResizeFilter::Gaussian => Some(FilterType::Gaussian),
+            ResizeFilter::Lanczos3 => Some(FilterType::Lanczos3),
+        }
+    }
+}
+
+/// Extra processing for pages before they're displayed, for low-resolution data-saver scans.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageEnhance {
+    #[default]
+    Off,
+    /// Unsharp mask, to make compressed text crisper
+    Sharpen,
+    /// Double the size of small pages, then sharpen
+    Upscale,
+}
+
+/// Terminal graphics protocol used for covers and pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageProtocol {
+    /// Detect from the terminal
+    #[default]
+    Auto,
+    Kitty,
+    Sixel,
+    Iterm2,
+    Halfblocks,
+}
+
+impl ImageProtocol {
+    pub fn protocol_type(self) -> Option<ProtocolType> {
+        match self {
+            ImageProtocol::Auto => None,
+            ImageProtocol::Kitty => Some(ProtocolType::Kitty),
+            ImageProtocol::Sixel => Some(ProtocolType::Sixel),
+            ImageProtocol::Iterm2 => Some(ProtocolType::Iterm2),
+            ImageProtocol::Halfblocks => Some(ProtocolType::Halfblocks),
+        }
+    }
+}
+
+/// Where the app lands once the browse lists have loaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StartupView {
+    #[default]
+    Home,
+    Bookmarks,
+    /// Reopen the last-read chapter at the saved page
+    Resume,
+}
+
+/// Order of the Bookmarks tab, after the pinned bookmarks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookmarkSort {
+    /// The order they were bookmarked in
+    #[default]
+    Added,
+    /// Alphabetically by displayed title
+    Title,
+}
+
+/// How pages are written to the disk cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageCacheFormat {
+    /// Store the downloaded bytes untouched
+    #[default]
+    Original,
+    /// Re-encode as JPEG at `page_cache_jpeg_quality`
+    Jpeg,
+}
+
+/// Login for a MangaDex personal API client, created in the account's API settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MangaDexAccount {
+    pub username: String,
+    pub password: String,
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Chapter languages in priority order, e.g. `["en", "es", "ja"]`
+    pub languages: Vec<String>,
+    /// List chapters missing from `languages` greyed out, in whatever language they exist
+    pub show_other_languages: bool,
+    /// Language of the title shown for each manga, e.g. `ja-ro`, when it has one
+    pub display_title_language: Option<String>,
+    pub resize_filter: ResizeFilter,
+    pub page_enhance: PageEnhance,
+    /// Fixed cover height in terminal rows, for terminals that misreport their font size
+    pub cover_rows: Option<u16>,
+    /// Upper bound on simultaneous network requests across all background tasks
+    pub max_concurrent_fetches: usize,
+    /// Fraction of a chapter's pages that must be reached before it counts as read
+    pub auto_mark_read_ratio: f32,
+    /// Log every request at info level and show a request counter overlay
+    pub verbose_network: bool,
+    /// Ask before quitting even when no downloads are running
+    pub confirm_quit: bool,
+    /// Write logs to `tachiyomi.log` in the data directory instead of stderr
+    pub log_to_file: bool,
+    pub startup_view: StartupView,
+    pub bookmark_sort: BookmarkSort,
+    pub page_cache_format: PageCacheFormat,
+    /// JPEG quality (1-100) used when `page_cache_format` is `jpeg`
+    pub page_cache_jpeg_quality: u8,
+    /// Fetch and show first-page thumbnails on chapter cards
+    pub show_chapter_thumbnails: bool,
+    /// Fetch the page lists of the selected chapter and its neighbours while browsing the grid
+    pub prefetch_chapter_urls: bool,
+    /// Pause between chapter thumbnail fetches in milliseconds
+    pub thumbnail_preload_delay_ms: u64,
+    /// Share of the detail view's width, in percent, given to the info panel instead of 40 columns
+    pub info_panel_percent: Option<u16>,
+    /// Seconds a page takes to read, for the estimate on chapter cards; 0 hides it
+    pub reading_secs_per_page: u64,
+    /// Group the detail view's chapters under collapsible volume headers
+    pub group_by_volume: bool,
+    /// Overrides the detected graphics protocol, for terminals that misreport support
+    pub image_protocol: ImageProtocol,
+    /// Milliseconds to wait for the terminal to answer the graphics query at startup
+    pub terminal_query_timeout_ms: u64,
+    /// Pages advanced per Left/Right press in the reader
+    pub page_step: usize,
+    /// Pages to step back from a saved page when resuming a chapter, for context
+    pub resume_rewind: usize,
+    /// Show which of the next few pages are cached under the reader header
+    pub show_page_cache_status: bool,
+    /// Right on the last page opens the next chapter, Left on the first the previous one
+    pub auto_advance_chapter: bool,
+    /// Open the reader with the chapter context sidebar showing
+    pub reader_sidebar: bool,
+    /// Last pages of a chapter on which the reader footer says the chapter is ending; 0 hides it
+    pub chapter_end_warning_pages: usize,
+    /// Seconds without a key press before the reader preloads further ahead; 0 turns it off
+    pub idle_preload_secs: u64,
+    /// Pages preloaded ahead of the current one while idle
+    pub idle_preload_pages: usize,
+    /// Seconds each page stays up in the reader's auto-scroll mode
+    pub auto_scroll_secs: u64,
+    /// Auto-scroll carries on into the next chapter instead of stopping at the last page
+    pub auto_scroll_next_chapter: bool,
+    /// Show one entry per chapter number when several groups uploaded the same chapter
+    pub dedupe_chapters: bool,
+    /// Scanlation group names or UUIDs whose uploads win when several groups have a chapter
+    pub preferred_groups: Vec<String>,
+    /// Seconds before any request is abandoned, including reading the body
+    pub request_timeout_secs: u64,
+    /// Seconds to wait for a connection to be established
+    pub connect_timeout_secs: u64,
+    /// Times a failed page download is retried before giving up
+    pub page_fetch_retries: u32,
+    /// Delay before the first page retry in milliseconds, doubled for each retry after
+    pub page_retry_backoff_ms: u64,
+    /// Left/Right past the end of a card row or the chapter grid wraps to the other end
+    pub wrap_navigation: bool,
+    /// Cards from the selected one onward whose covers are preloaded when a row scrolls
+    pub cover_preload_ahead: usize,
+    /// Blur covers of manga rated above `safe` until revealed with `v`
+    pub blur_covers: bool,
+    /// Manga fetched per browse list on the home screen, capped at 100 by MangaDex
+    pub browse_limit: usize,
+    /// Fetch every cover in the browse rows and search results up front, not just the first few
+    pub eager_covers: bool,
+    /// Where downloaded pages are cached, instead of the platform cache directory
+    pub cache_dir: Option<PathBuf>,
+    /// Where bookmarks and reading progress are stored, instead of the config directory
+    pub data_dir: Option<PathBuf>,
+    /// Folder of manga to read from disk: one subfolder per manga, one per chapter below that
+    pub local_library: Option<PathBuf>,
+    /// Where downloaded chapters are saved as CBZ files, instead of `downloads` in the data directory
+    pub download_dir: Option<PathBuf>,
+    /// Mirror bookmarks to the MangaDex account's follows and reading statuses
+    pub sync_with_mangadex: bool,
+    pub mangadex_account: Option<MangaDexAccount>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            languages: vec!["en".to_string()],
+            show_other_languages: false,
+            display_title_language: None,
+            resize_filter: ResizeFilter::default(),
+            page_enhance: PageEnhance::default(),
+            cover_rows: None,
+            max_concurrent_fetches: DEFAULT_MAX_CONCURRENT_FETCHES,
+            auto_mark_read_ratio: 1.0,
+            verbose_network: false,
+            confirm_quit: false,
+            log_to_file: false,
+            startup_view: StartupView::default(),
+            bookmark_sort: BookmarkSort::default(),
+            page_cache_format: PageCacheFormat::default(),
+            page_cache_jpeg_quality: 90,
+            show_chapter_thumbnails: true,
+            prefetch_chapter_urls: true,
+            thumbnail_preload_delay_ms: 200,
+            info_panel_percent: None,
+            reading_secs_per_page: 30,
+            group_by_volume: false,
+            image_protocol: ImageProtocol::default(),
+            terminal_query_timeout_ms: 2000,
+            page_step: 1,
+            resume_rewind: 0,
+            show_page_cache_status: false,
+            auto_advance_chapter: false,
+            reader_sidebar: false,
+            chapter_end_warning_pages: 2,
+            idle_preload_secs: 5,
+            idle_preload_pages: 15,
+            auto_scroll_secs: 8,
+            auto_scroll_next_chapter: false,
+            dedupe_chapters: false,
+            preferred_groups: Vec::new(),
+            request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+            page_fetch_retries: DEFAULT_PAGE_FETCH_RETRIES,
+            page_retry_backoff_ms: DEFAULT_PAGE_RETRY_BACKOFF_MS,
+            wrap_navigation: false,
+            cover_preload_ahead: 8,
+            blur_covers: false,
+            browse_limit: DEFAULT_BROWSE_LIMIT,
+            eager_covers: false,
+            cache_dir: None,
+            data_dir: None,
+            local_library: None,
+            download_dir: None,
+            sync_with_mangadex: false,
+            mangadex_account: None,
+        }
+    }
+}
+
+/// Directory holding the config file and the other persisted JSON stores.
+pub fn app_config_dir() -> PathBuf {
+    let config_dir = dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("tachiyomi-tui");
+
+    fs::create_dir_all(&config_dir).ok();
+    config_dir
+}
+
+/// Covers fetched per row when it first loads with `eager_covers` off
+const INITIAL_COVERS: usize = 6;
+
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+static CACHE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Directory holding bookmarks and reading progress.
+pub fn app_data_dir() -> PathBuf {
+    DATA_DIR.get().cloned().unwrap_or_else(app_config_dir)
+}
+
+/// Root of the on-disk page cache.
+pub fn app_cache_dir() -> PathBuf {
+    CACHE_DIR.get().cloned().unwrap_or_else(default_cache_dir)
+}
+
+fn default_cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("tachiyomi-tui")
+}
+
+/// Expands a leading `~` to the home directory.
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Creates `dir` if needed and checks that files can be written to it.
+pub fn check_writable(dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let probe = dir.join(".write-test");
+    fs::write(&probe, b"")?;
+    fs::remove_file(probe)
+}
+
+pub fn get_config_path() -> PathBuf {
+    app_config_dir().join("config.json")
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let path = get_config_path();
+
+        if path.exists()
+            && let Ok(content) = fs::read_to_string(&path)
+        {
+            match serde_json::from_str(&content) {
+                Ok(config) => return config,
+                Err(e) => log::warn!("Ignoring invalid config file {}: {}", path.display(), e),
+            }
+        }
+
+        Config::default()
+    }
+
+    /// Applies the `data_dir` and `cache_dir` overrides. Must be called before bookmarks,
+    /// progress or the page cache are loaded. Returns a warning for each override that
+    /// isn't writable and was ignored.
+    pub fn init_dirs(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let overrides = [
+            ("data_dir", &self.data_dir, &DATA_DIR),
+            ("cache_dir", &self.cache_dir, &CACHE_DIR),
+        ];
+
+        for (name, dir, slot) in overrides {
+            let Some(dir) = dir else {
+                continue;
+            };
+            let dir = expand_home(dir);
+            match check_writable(&dir) {
+                Ok(()) => {
+                    slot.set(dir).ok();
+                }
+                Err(e) => {
+                    log::warn!("{} {} isn't writable ({}); using the default", name, dir.display(), e);
+                    warnings.push(format!("{} {} isn't writable, using the default", name, dir.display()));
+                }
+            }
+        }
+
+        warnings
+    }
+
+    /// Covers to fetch when a row of manga first loads; the rest load as it scrolls.
+    pub fn initial_covers(&self) -> usize {
+        if self.eager_covers { usize::MAX } else { INITIAL_COVERS }
+    }
+
+    /// The account to sync bookmarks with, if syncing is turned on.
+    pub fn sync_account(&self) -> Option<&MangaDexAccount> {
+        self.mangadex_account.as_ref().filter(|_| self.sync_with_mangadex)
+    }
+
+    pub fn local_library_dir(&self) -> Option<PathBuf> {
+        self.local_library.as_deref().map(expand_home)
+    }
+
+    /// Root of the CBZ downloads, with one folder per manga.
+    pub fn downloads_dir(&self) -> PathBuf {
+        match &self.download_dir {
+            Some(dir) => expand_home(dir),
+            None => app_data_dir().join("downloads"),
+        }
+    }
+
+    pub fn languages(&self) -> Vec<String> {
+        if self.languages.is_empty() {
+            Config::default().languages
+        } else {
+            self.languages.clone()
+        }
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            retries: self.page_fetch_retries,
+            base_delay_ms: self.page_retry_backoff_ms,
+        }
+    }
+
+    pub fn feed_options(&self) -> FeedOptions {
+        FeedOptions {
+            languages: self.languages(),
+            dedupe: self.dedupe_chapters,
+            preferred_groups: self.preferred_groups.clone(),
+            other_languages: self.show_other_languages,
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::cbz::{page_extension, safe_file_name, write_cbz};
+use super::config::app_data_dir;
+use super::mangadex::Chapter;
+
+/// Characters of the chapter ID put in its file name
+const CHAPTER_ID_CHARS: usize = 8;
+
+/// A chapter to download and the CBZ file it's saved as.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadChapter {
+    pub id: String,
+    pub path: PathBuf,
+}
+
+impl DownloadChapter {
+    /// Where `chapter` of `manga_title` goes under `root`: a folder per manga, and a file
+    /// per chapter named after its number, group and language. The start of the chapter ID
+    /// tells apart uploads that share all three, such as two oneshots by one group.
+    pub fn new(root: &Path, manga_title: &str, chapter: &Chapter) -> Self {
+        let mut name = format!("{} ({})", chapter.long_label(), chapter.group_names());
+        if !chapter.language.is_empty() {
+            name.push_str(&format!(" [{}]", chapter.language));
+        }
+        let short_id: String = chapter.id.chars().take(CHAPTER_ID_CHARS).collect();
+        name.push_str(&format!(" {}", short_id));
+        Self {
+            id: chapter.id.clone(),
+            path: root
+                .join(safe_file_name(manga_title))
+                .join(format!("{}.cbz", safe_file_name(&name))),
+        }
+    }
+
+    /// Folder holding the pages fetched so far, zipped into the CBZ once all are in. A
+    /// resumed download skips the pages already here.
+    pub fn staging_dir(&self) -> PathBuf {
+        self.path.with_extension("partial")
+    }
+
+    /// Where page `index` (from 0) is kept until the chapter is zipped.
+    pub fn staged_page(&self, index: usize) -> PathBuf {
+        self.staging_dir().join(format!("{:03}", index + 1))
+    }
+
+    /// Zips the `pages` staged pages into the CBZ and removes the staging folder.
+    pub fn finish(&self, pages: usize) -> io::Result<()> {
+        let mut entries = Vec::with_capacity(pages);
+        for index in 0..pages {
+            let bytes = fs::read(self.staged_page(index))?;
+            entries.push((format!("{:03}.{}", index + 1, page_extension(&bytes)), bytes));
+        }
+        write_cbz(&self.path, &entries)?;
+        fs::remove_dir_all(self.staging_dir())
+    }
+}
+
+/// A batch download that hasn't finished yet, kept on disk so it can carry on after
+/// a quit or crash. Pages already staged for a chapter are skipped when it resumes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PendingDownload {
+    pub chapters: Vec<DownloadChapter>,
+    /// Chapters at the front of `chapters` that are finished
+    #[serde(default)]
+    pub done: usize,
+    /// How many of the finished chapters couldn't be fetched
+    #[serde(default)]
+    pub failed: usize,
+}
+
+fn get_pending_download_path() -> PathBuf {
+    app_data_dir().join("pending-download.json")
+}
+
+impl PendingDownload {
+    pub fn new(chapters: Vec<DownloadChapter>) -> Self {
+        Self {
+            chapters,
+            done: 0,
+            failed: 0,
+        }
+    }
+
+    /// The download left unfinished by an earlier run, if any.
+    pub fn load() -> Option<Self> {
+        let content = fs::read_to_string(get_pending_download_path()).ok()?;
+        let pending = serde_json::from_str::<PendingDownload>(&content).ok()?;
+        if pending.is_finished() {
+            Self::clear();
+            return None;
+        }
+        Some(pending)
+    }
+
+    pub fn save(&self) {
+        if let Ok(content) = serde_json::to_string_pretty(self) {
+            fs::write(get_pending_download_path(), content).ok();
+        }
+    }
+
+    /// Forgets the pending download, once it has finished or been cancelled.
+    pub fn clear() {
+        fs::remove_file(get_pending_download_path()).ok();
+    }
+
+    /// Drops the download along with the pages of the chapters it didn't finish.
+    pub fn discard(&self) {
+        for chapter in &self.chapters[self.done.min(self.total())..] {
+            fs::remove_dir_all(chapter.staging_dir()).ok();
+        }
+        Self::clear();
+    }
+
+    pub fn total(&self) -> usize {
+        self.chapters.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.done >= self.total()
+    }
+}
+
+/// A pending download owned by the task running it. When the task stops after the
+/// download was cancelled, the download is discarded; when it stops because the app is
+/// quitting, it's left on disk to resume at the next launch. Discarding on drop rather
+/// than from the cancelling side means a save the task was in the middle of can't bring
+/// the download back.
+pub struct ActiveDownload {
+    pub pending: PendingDownload,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ActiveDownload {
+    pub fn new(pending: PendingDownload, cancelled: Arc<AtomicBool>) -> Self {
+        Self { pending, cancelled }
+    }
+}
+
+impl Drop for ActiveDownload {
+    fn drop(&mut self) {
+        if self.cancelled.load(Ordering::Relaxed) {
+            self.pending.discard();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resumed_download_keeps_its_place() {
+        let pending: PendingDownload = serde_json::from_str(
+            r#"{"chapters": [{"id": "a", "path": "a.cbz"}, {"id": "b", "path": "b.cbz"}, {"id": "c", "path": "c.cbz"}], "done": 2}"#,
+        )
+        .unwrap();
+        assert_eq!(pending.total(), 3);
+        assert_eq!(pending.failed, 0);
+        assert!(!pending.is_finished());
+
+        let chapter = DownloadChapter {
+            id: "a".to_string(),
+            path: PathBuf::from("a.cbz"),
+        };
+        let mut pending = PendingDownload::new(vec![chapter]);
+        assert!(!pending.is_finished());
+        pending.done = 1;
+        assert!(pending.is_finished());
+
+        // Staged pages end up in the CBZ, and the staging folder goes
+        let dir = std::env::temp_dir().join(format!("tachiyomi-tui-download-{}", std::process::id()));
+        let chapter = DownloadChapter {
+            id: "a".to_string(),
+            path: dir.join("Chapter 1.5 (Group).cbz"),
+        };
+        assert_eq!(chapter.staging_dir(), dir.join("Chapter 1.5 (Group).partial"));
+        fs::create_dir_all(chapter.staging_dir()).unwrap();
+        fs::write(chapter.staged_page(0), b"one").unwrap();
+        fs::write(chapter.staged_page(1), b"two").unwrap();
+        chapter.finish(2).unwrap();
+        assert!(chapter.path.exists());
+        assert!(!chapter.staging_dir().exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_chapters_sharing_a_label_get_their_own_files() {
+        let chapter = |id: &str, language: &str| Chapter {
+            id: id.to_string(),
+            chapter: String::new(),
+            title: String::new(),
+            volume: None,
+            pages: 10,
+            external_url: None,
+            groups: Vec::new(),
+            language: language.to_string(),
+            other_language: false,
+        };
+        let path = |c: &Chapter| DownloadChapter::new(Path::new("root"), "Manga", c).path;
+
+        let oneshots = [chapter("1f0c2d3e-aaaa", "en"), chapter("7b9e4a10-bbbb", "en")];
+        assert_ne!(path(&oneshots[0]), path(&oneshots[1]));
+        let translations = [chapter("1f0c2d3e-aaaa", "en"), chapter("1f0c2d3e-cccc", "es")];
+        assert_ne!(path(&translations[0]), path(&translations[1]));
+        assert_ne!(
+            DownloadChapter::new(Path::new("root"), "Manga", &translations[0]).staging_dir(),
+            DownloadChapter::new(Path::new("root"), "Manga", &translations[1]).staging_dir()
+        );
+        assert!(path(&oneshots[0]).to_string_lossy().ends_with("[en] 1f0c2d3e.cbz"));
+    }
+}
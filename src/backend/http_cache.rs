@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::cache::md5_hash;
+use super::config::app_config_dir;
+
+/// A stored API response and the validators needed to revalidate it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    /// Kept so a hash collision can't hand back another URL's body
+    pub url: String,
+    pub body: String,
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    /// Unix time until which the body may be reused without asking the server
+    #[serde(default)]
+    pub fresh_until: u64,
+}
+
+impl CachedResponse {
+    pub fn is_fresh(&self) -> bool {
+        now_secs() < self.fresh_until
+    }
+
+    /// Whether the server can answer a conditional request for this entry with a 304.
+    pub fn can_revalidate(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+}
+
+/// The parts of a `Cache-Control` header that matter to a private client cache.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CacheControl {
+    pub no_store: bool,
+    pub no_cache: bool,
+    pub max_age: Option<u64>,
+}
+
+impl CacheControl {
+    pub fn parse(header: Option<&str>) -> Self {
+        let mut control = CacheControl::default();
+        for directive in header.unwrap_or_default().split(',') {
+            let directive = directive.trim().to_ascii_lowercase();
+            match directive.as_str() {
+                "no-store" => control.no_store = true,
+                "no-cache" => control.no_cache = true,
+                _ => {
+                    if let Some(secs) = directive.strip_prefix("max-age=") {
+                        control.max_age = secs.trim_matches('"').parse().ok();
+                    }
+                }
+            }
+        }
+        control
+    }
+
+    /// Unix time until which a response with this header is fresh. Without `max-age` the
+    /// response is stale straight away and is only reused after a 304.
+    pub fn fresh_until(&self) -> u64 {
+        match self.max_age {
+            Some(secs) if !self.no_cache => now_secs().saturating_add(secs),
+            _ => 0,
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Responses kept on disk; past this the least recently written are removed
+const MAX_RESPONSES: usize = 300;
+
+fn cache_dir() -> PathBuf {
+    app_config_dir().join("responses")
+}
+
+fn path_for(url: &str) -> PathBuf {
+    cache_dir().join(format!("{:x}.json", md5_hash(url)))
+}
+
+pub async fn get(url: &str) -> Option<CachedResponse> {
+    let content = fs::read_to_string(path_for(url)).await.ok()?;
+    serde_json::from_str::<CachedResponse>(&content)
+        .ok()
+        .filter(|entry| entry.url == url)
+}
+
+/// Stores a 200 response that can be reused, either while it's fresh or after a 304. Any
+/// other response, or one the server asked not to store, replaces what was stored before.
+pub async fn put(url: &str, body: String, etag: Option<String>, last_modified: Option<String>, control: &CacheControl) {
+    let path = path_for(url);
+    let entry = CachedResponse {
+        url: url.to_string(),
+        body,
+        etag,
+        last_modified,
+        fresh_until: control.fresh_until(),
+    };
+    if control.no_store || !(entry.can_revalidate() || entry.is_fresh()) {
+        fs::remove_file(path).await.ok();
+        return;
+    }
+
+    if fs::create_dir_all(cache_dir()).await.is_err() {
+        return;
+    }
+    if let Ok(content) = serde_json::to_string(&entry) {
+        fs::write(path, content).await.ok();
+    }
+    remove_oldest(&cache_dir(), MAX_RESPONSES).await;
+}
+
+/// Removes the least recently written files in `dir` until at most `keep` are left.
+async fn remove_oldest(dir: &Path, keep: usize) {
+    let Ok(mut entries) = fs::read_dir(dir).await else {
+        return;
+    };
+    let mut files = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if let Ok(modified) = entry.metadata().await.and_then(|m| m.modified()) {
+            files.push((modified, entry.path()));
+        }
+    }
+    if files.len() <= keep {
+        return;
+    }
+
+    files.sort();
+    for (_, path) in &files[..files.len() - keep] {
+        fs::remove_file(path).await.ok();
+    }
+}
+
+/// Removes every stored response. Returns the bytes freed.
+pub async fn clear() -> u64 {
+    let Ok(mut entries) = fs::read_dir(cache_dir()).await else {
+        return 0;
+    };
+    let mut freed = 0;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let size = entry.metadata().await.map(|m| m.len()).unwrap_or(0);
+        if fs::remove_file(entry.path()).await.is_ok() {
+            freed += size;
+        }
+    }
+    freed
+}
+
+/// Extends a cached entry's lifetime after the server confirmed it with a 304.
+pub async fn refresh(mut entry: CachedResponse, control: &CacheControl) -> String {
+    let fresh_until = control.fresh_until();
+    if fresh_until != entry.fresh_until {
+        entry.fresh_until = fresh_until;
+        if let Ok(content) = serde_json::to_string(&entry) {
+            fs::write(path_for(&entry.url), content).await.ok();
+        }
+    }
+    entry.body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cache_control() {
+        let control = CacheControl::parse(Some("public, Max-Age=300"));
+        assert_eq!(control.max_age, Some(300));
+        assert!(control.fresh_until() > now_secs());
+
+        let control = CacheControl::parse(Some("no-cache, max-age=300"));
+        assert!(control.no_cache);
+        assert_eq!(control.fresh_until(), 0);
+
+        assert!(CacheControl::parse(Some("no-store")).no_store);
+        assert_eq!(CacheControl::parse(None), CacheControl::default());
+    }
+
+    #[tokio::test]
+    async fn test_remove_oldest_keeps_the_newest() {
+        let dir = std::env::temp_dir().join(format!("tachiyomi-tui-test-responses-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let base = SystemTime::now();
+        for (i, name) in ["a", "b", "c", "d"].iter().enumerate() {
+            let file = std::fs::File::create(dir.join(name)).unwrap();
+            file.set_modified(base - std::time::Duration::from_secs(100 - i as u64)).unwrap();
+        }
+
+        remove_oldest(&dir, 2).await;
+        let mut left: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        assert_eq!(left, ["c", "d"]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,375 @@
+use futures::future::BoxFuture;
+use image::DynamicImage;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use super::mangadex::{ApiError, Chapter, FeedOptions, Manga, SearchOrder};
+use super::source::Source;
+
+/// Prefix on manga, chapter and page IDs that refer to files in the local library.
+/// The rest of the ID is the absolute path.
+pub const LOCAL_PREFIX: &str = "local:";
+
+const IMAGE_EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "webp", "gif", "bmp"];
+
+/// A manga folder: each subfolder is a chapter, or the folder itself is the only
+/// chapter when it holds images directly.
+#[derive(Debug, Clone)]
+pub struct LocalManga {
+    pub path: PathBuf,
+    pub title: String,
+    pub cover: Option<PathBuf>,
+    pub chapters: Vec<LocalChapter>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LocalChapter {
+    pub path: PathBuf,
+    pub name: String,
+    /// Image files in reading order
+    pub pages: Vec<PathBuf>,
+}
+
+pub fn is_local(id: &str) -> bool {
+    id.starts_with(LOCAL_PREFIX)
+}
+
+fn to_id(path: &Path) -> String {
+    format!("{}{}", LOCAL_PREFIX, path.display())
+}
+
+fn from_id(id: &str) -> Option<PathBuf> {
+    id.strip_prefix(LOCAL_PREFIX).map(PathBuf::from)
+}
+
+/// Compares names so that "2" sorts before "10", as file managers do.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take_number = |it: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+                    while let Some(c) = it.peek().copied().filter(char::is_ascii_digit) {
+                        digits.push(c);
+                        it.next();
+                    }
+                    digits
+                };
+                let (na, nb) = (take_number(&mut a), take_number(&mut b));
+                let (ta, tb) = (na.trim_start_matches('0'), nb.trim_start_matches('0'));
+                let ord = ta.len().cmp(&tb.len()).then_with(|| ta.cmp(tb));
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ord = x.to_ascii_lowercase().cmp(&y.to_ascii_lowercase());
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// Entries of `dir` matching `keep`, sorted naturally by name. Hidden files are skipped.
+fn sorted_entries(dir: &Path, keep: impl Fn(&Path) -> bool) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|rd| {
+            rd.filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| !file_name(p).starts_with('.') && keep(p))
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort_by(|a, b| natural_cmp(&file_name(a), &file_name(b)));
+    entries
+}
+
+fn image_files(dir: &Path) -> Vec<PathBuf> {
+    sorted_entries(dir, |p| p.is_file() && is_image(p))
+}
+
+fn scan_chapter(dir: &Path) -> Option<LocalChapter> {
+    let pages = image_files(dir);
+    (!pages.is_empty()).then(|| LocalChapter {
+        path: dir.to_path_buf(),
+        name: file_name(dir),
+        pages,
+    })
+}
+
+/// Reads one manga folder, or `None` if it has no images at all.
+pub fn scan_manga(dir: &Path) -> Option<LocalManga> {
+    let mut chapters: Vec<LocalChapter> = sorted_entries(dir, Path::is_dir)
+        .iter()
+        .filter_map(|d| scan_chapter(d))
+        .collect();
+    if chapters.is_empty() {
+        chapters.extend(scan_chapter(dir));
+    }
+    if chapters.is_empty() {
+        return None;
+    }
+
+    let cover = image_files(dir)
+        .into_iter()
+        .find(|p| {
+            p.file_stem()
+                .is_some_and(|s| s.to_string_lossy().eq_ignore_ascii_case("cover"))
+        })
+        .or_else(|| chapters[0].pages.first().cloned());
+
+    Some(LocalManga {
+        path: dir.to_path_buf(),
+        title: file_name(dir),
+        cover,
+        chapters,
+    })
+}
+
+/// Every manga folder directly under `root`.
+pub fn scan_library(root: &Path) -> Vec<LocalManga> {
+    sorted_entries(root, Path::is_dir)
+        .iter()
+        .filter_map(|d| scan_manga(d))
+        .collect()
+}
+
+impl From<&LocalManga> for Manga {
+    fn from(local: &LocalManga) -> Self {
+        Manga {
+            id: to_id(&local.path),
+            title: local.title.clone(),
+            alt_titles: Vec::new(),
+            author: "Local".to_string(),
+            artist: String::new(),
+            status: "local".to_string(),
+            description: format!(
+                "{} chapters in `{}`",
+                local.chapters.len(),
+                local.path.display()
+            ),
+            cover_url: local.cover.as_deref().map(to_id).unwrap_or_default(),
+            latest_chapter_id: None,
+            content_rating: "safe".to_string(),
+            tags: Vec::new(),
+        }
+    }
+}
+
+/// The first number in a folder name ("Chapter 012.5 - Title" -> "12.5"), or the whole
+/// name when there isn't one.
+fn chapter_number(name: &str) -> String {
+    let start = name.find(|c: char| c.is_ascii_digit());
+    let Some(start) = start else {
+        return name.to_string();
+    };
+    let number: String = name[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let number = number.trim_end_matches('.');
+    let trimmed = number.trim_start_matches('0');
+    if trimmed.is_empty() || trimmed.starts_with('.') {
+        format!("0{}", trimmed)
+    } else {
+        trimmed.to_string()
+    }
+}
+
+impl From<&LocalChapter> for Chapter {
+    fn from(local: &LocalChapter) -> Self {
+        Chapter {
+            id: to_id(&local.path),
+            chapter: chapter_number(&local.name),
+            title: local.name.clone(),
+            volume: None,
+            pages: local.pages.len(),
+            external_url: None,
+            groups: Vec::new(),
+            language: String::new(),
+            other_language: false,
+        }
+    }
+}
+
+/// Chapters of a local manga, by manga ID.
+pub fn chapters(manga_id: &str) -> Vec<Chapter> {
+    from_id(manga_id)
+        .and_then(|dir| scan_manga(&dir))
+        .map(|m| m.chapters.iter().map(Chapter::from).collect())
+        .unwrap_or_default()
+}
+
+/// Page IDs of a local chapter, in reading order.
+pub fn chapter_pages(chapter_id: &str) -> Vec<String> {
+    from_id(chapter_id)
+        .map(|dir| image_files(&dir).iter().map(|p| to_id(p)).collect())
+        .unwrap_or_default()
+}
+
+/// Decodes a local page or cover image.
+pub fn load_image(id: &str) -> Option<DynamicImage> {
+    let path = from_id(id)?;
+    match image::open(&path) {
+        Ok(image) => Some(image),
+        Err(e) => {
+            log::warn!("Failed to read {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// The local library as a [`Source`]. Files are read on the blocking thread pool.
+pub struct LocalSource {
+    root: OnceLock<PathBuf>,
+}
+
+impl LocalSource {
+    pub const fn new() -> Self {
+        Self { root: OnceLock::new() }
+    }
+
+    /// Sets the library folder that `popular`, `recent` and `search` list. Chapters and
+    /// pages are found from their IDs, so they work without it.
+    pub fn init_root(&self, root: PathBuf) {
+        let _ = self.root.set(root);
+    }
+
+    async fn library(&self) -> Vec<Manga> {
+        let Some(root) = self.root.get().cloned() else {
+            return Vec::new();
+        };
+        tokio::task::spawn_blocking(move || scan_library(&root).iter().map(Manga::from).collect())
+            .await
+            .unwrap_or_default()
+    }
+}
+
+impl Source for LocalSource {
+    fn name(&self) -> &'static str {
+        "Local"
+    }
+
+    fn popular(&self, _limit: usize) -> BoxFuture<'_, Result<Vec<Manga>, ApiError>> {
+        Box::pin(async move { Ok(self.library().await) })
+    }
+
+    fn recent(&self, _limit: usize) -> BoxFuture<'_, Result<Vec<Manga>, ApiError>> {
+        Box::pin(async move { Ok(self.library().await) })
+    }
+
+    /// Folders are already listed by name, and there's no follow count or upload date to
+    /// sort by, so `order` is ignored. Local manga have no tags, so a tag matches nothing.
+    fn search<'a>(
+        &'a self,
+        query: &'a str,
+        _order: SearchOrder,
+        tag_id: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Vec<Manga>, ApiError>> {
+        Box::pin(async move {
+            if tag_id.is_some() {
+                return Ok(Vec::new());
+            }
+            let query = query.to_lowercase();
+            let mut mangas = self.library().await;
+            mangas.retain(|m| m.title.to_lowercase().contains(&query));
+            Ok(mangas)
+        })
+    }
+
+    fn chapters<'a>(
+        &'a self,
+        manga_id: &'a str,
+        _options: &'a FeedOptions,
+    ) -> BoxFuture<'a, Result<Vec<Chapter>, ApiError>> {
+        let id = manga_id.to_string();
+        Box::pin(async move {
+            Ok(tokio::task::spawn_blocking(move || chapters(&id))
+                .await
+                .unwrap_or_default())
+        })
+    }
+
+    fn chapter_pages<'a>(&'a self, chapter_id: &'a str) -> BoxFuture<'a, Option<Vec<String>>> {
+        let id = chapter_id.to_string();
+        Box::pin(async move { tokio::task::spawn_blocking(move || chapter_pages(&id)).await.ok() })
+    }
+
+    fn cover<'a>(&'a self, cover_url: &'a str) -> BoxFuture<'a, Option<DynamicImage>> {
+        let id = cover_url.to_string();
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || load_image(&id))
+                .await
+                .ok()
+                .flatten()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_library() {
+        let root = std::env::temp_dir().join(format!("tachiyomi-tui-local-{}", std::process::id()));
+        let touch = |rel: &str| {
+            let path = root.join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"").unwrap();
+        };
+        touch("Series A/Chapter 10/01.png");
+        touch("Series A/Chapter 2/10.jpg");
+        touch("Series A/Chapter 2/2.jpg");
+        touch("Series A/Chapter 2/notes.txt");
+        touch("Series A/cover.jpg");
+        touch("Oneshot B/page1.webp");
+        fs::create_dir_all(root.join("Empty C")).unwrap();
+
+        let library = scan_library(&root);
+        let titles: Vec<&str> = library.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, vec!["Oneshot B", "Series A"]);
+
+        let series = &library[1];
+        let chapters: Vec<&str> = series.chapters.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(chapters, vec!["Chapter 2", "Chapter 10"]);
+        let pages: Vec<String> = series.chapters[0].pages.iter().map(|p| file_name(p)).collect();
+        assert_eq!(pages, vec!["2.jpg", "10.jpg"]);
+        assert_eq!(series.cover.as_deref().map(file_name).as_deref(), Some("cover.jpg"));
+        assert_eq!(Chapter::from(&series.chapters[1]).chapter, "10");
+
+        // A folder of loose images is a single chapter
+        assert_eq!(library[0].chapters.len(), 1);
+
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_chapter_number() {
+        assert_eq!(chapter_number("Chapter 012.5 - The End"), "12.5");
+        assert_eq!(chapter_number("Ch 0"), "0");
+        assert_eq!(chapter_number("Prologue"), "Prologue");
+    }
+}
@@ -0,0 +1,102 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::config::app_data_dir;
+
+/// Size at which the log moves to `tachiyomi.log.1`, replacing the previous one
+pub const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+pub fn log_path() -> PathBuf {
+    app_data_dir().join("tachiyomi.log")
+}
+
+/// An append-only log file that keeps at most one older file next to it, so the
+/// two together never take much more than twice `max_bytes`.
+pub struct RotatingLog {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+}
+
+impl RotatingLog {
+    pub fn open(path: &Path, max_bytes: u64) -> io::Result<Self> {
+        let mut log = RotatingLog {
+            path: path.to_path_buf(),
+            file: open_append(path)?,
+            written: fs::metadata(path).map(|m| m.len()).unwrap_or_default(),
+            max_bytes,
+        };
+        if log.written >= max_bytes {
+            log.rotate()?;
+        }
+        Ok(log)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let mut old = self.path.clone().into_os_string();
+        old.push(".1");
+        fs::rename(&self.path, old)?;
+        self.file = open_append(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+impl Write for RotatingLog {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let n = self.file.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// The last `count` lines of the log file, oldest first. Empty when nothing is logged to a file.
+pub fn recent_lines(path: &Path, count: usize) -> Vec<String> {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let lines: Vec<&str> = content.lines().collect();
+    lines[lines.len().saturating_sub(count)..].iter().map(|l| l.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_rotates_past_size_cap() {
+        let dir = std::env::temp_dir().join(format!("tachiyomi-log-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.log");
+
+        let mut log = RotatingLog::open(&path, 16).unwrap();
+        log.write_all(b"first line\n").unwrap();
+        log.write_all(b"second line\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second line\n");
+        assert_eq!(fs::read_to_string(dir.join("test.log.1")).unwrap(), "first line\n");
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_recent_lines_keeps_the_tail() {
+        let path = std::env::temp_dir().join(format!("tachiyomi-log-tail-{}.log", std::process::id()));
+        fs::write(&path, "one\ntwo\nthree\n").unwrap();
+
+        assert_eq!(recent_lines(&path, 2), ["two", "three"]);
+        assert_eq!(recent_lines(&path, 10).len(), 3);
+        assert!(recent_lines(&path.with_extension("missing"), 5).is_empty());
+        fs::remove_file(path).ok();
+    }
+}
@@ -1,10 +1,45 @@
+use futures::future::BoxFuture;
 use image::DynamicImage;
-use reqwest::Error;
-use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Semaphore, SemaphorePermit};
 
-const BASE_URL: &str = "https://api.mangadex.org";
+use super::http_cache;
+use super::source::Source;
+
+pub(super) const BASE_URL: &str = "https://api.mangadex.org";
+const WEB_URL: &str = "https://mangadex.org";
+
+#[derive(Debug)]
+pub enum ApiError {
+    Network(reqwest::Error),
+    Status(reqwest::StatusCode),
+    Parse(serde_json::Error),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Network(e) => write!(f, "network error: {}", e),
+            ApiError::Status(status) => write!(f, "unexpected status: {}", status),
+            ApiError::Parse(e) => write!(f, "failed to parse response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        ApiError::Network(e)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Chapter {
@@ -14,17 +49,115 @@ pub struct Chapter {
     pub volume: Option<String>,
     pub pages: usize,
     pub external_url: Option<String>,
+    /// Scanlation groups credited for this upload
+    pub groups: Vec<ScanlationGroup>,
+    /// Translation language code, e.g. "en"; empty for local chapters
+    pub language: String,
+    /// Not in any configured language; only listed with `show_other_languages`
+    pub other_language: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScanlationGroup {
+    pub id: String,
+    pub name: String,
+}
+
+/// Position of the first entry in `preferred` naming one of `groups`, by UUID or by
+/// case-insensitive name.
+fn group_rank(groups: &[ScanlationGroup], preferred: &[String]) -> Option<usize> {
+    preferred.iter().position(|p| {
+        groups
+            .iter()
+            .any(|g| g.id == *p || g.name.eq_ignore_ascii_case(p.trim()))
+    })
+}
+
+impl Chapter {
+    /// Whether a `preferred_groups` list is set and none of this upload's groups are on it.
+    pub fn is_from_other_group(&self, preferred: &[String]) -> bool {
+        !preferred.is_empty() && group_rank(&self.groups, preferred).is_none()
+    }
+
+    /// Group names joined for display, e.g. "Group A & Group B".
+    pub fn group_names(&self) -> String {
+        let names: Vec<&str> = self.groups.iter().map(|g| g.name.as_str()).collect();
+        if names.is_empty() {
+            "No group".to_string()
+        } else {
+            names.join(" & ")
+        }
+    }
+
+    /// Numeric chapter number for ordering ("10.5" -> 10.5), `None` if it isn't a number.
+    pub fn number(&self) -> Option<f64> {
+        self.chapter.trim().parse::<f64>().ok()
+    }
+
+    /// Oneshots come from the feed with no chapter number at all.
+    pub fn is_oneshot(&self) -> bool {
+        self.chapter.trim().is_empty()
+    }
+
+    /// Position used when ordering chapters: oneshots first, unnumbered extras last.
+    pub fn sort_key(&self) -> f64 {
+        if self.is_oneshot() {
+            0.0
+        } else {
+            self.number().unwrap_or(f64::MAX)
+        }
+    }
+
+    /// Compact label for cards, e.g. "Ch.12" or "Oneshot".
+    pub fn short_label(&self) -> String {
+        if self.is_oneshot() {
+            "Oneshot".to_string()
+        } else {
+            format!("Ch.{}", self.chapter)
+        }
+    }
+
+    /// Full label for headers, e.g. "Chapter 12" or "Oneshot".
+    pub fn long_label(&self) -> String {
+        if self.is_oneshot() {
+            "Oneshot".to_string()
+        } else {
+            format!("Chapter {}", self.chapter)
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct ChapterResponse {
     data: Vec<ChapterData>,
+    /// Entries in the whole feed, across every page of it
+    #[serde(default)]
+    total: usize,
 }
 
 #[derive(Debug, Deserialize)]
 struct ChapterData {
     id: String,
     attributes: ChapterAttributes,
+    #[serde(default)]
+    relationships: Vec<Relationship>,
+}
+
+impl ChapterData {
+    fn groups(&self) -> Vec<ScanlationGroup> {
+        self.relationships
+            .iter()
+            .filter(|r| r.rel_type == "scanlation_group")
+            .map(|r| ScanlationGroup {
+                id: r.id.clone(),
+                name: r
+                    .attributes
+                    .as_ref()
+                    .and_then(|a| a.name.clone())
+                    .unwrap_or_else(|| "Unknown group".to_string()),
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -34,7 +167,7 @@ struct ChapterAttributes {
     volume: Option<String>,
     pages: usize,
     #[serde(rename = "translatedLanguage")]
-    _translated_language: String,
+    translated_language: String,
     #[serde(rename = "externalUrl")]
     external_url: Option<String>,
 }
@@ -49,8 +182,7 @@ struct AtHomeResponse {
 #[derive(Debug, Deserialize)]
 struct AtHomeChapter {
     hash: String,
-    #[serde(rename = "data")]
-    _data: Vec<String>,
+    data: Vec<String>,
     #[serde(rename = "dataSaver")]
     data_saver: Vec<String>,
 }
@@ -59,12 +191,38 @@ struct AtHomeChapter {
 pub struct Manga {
     pub id: String,
     pub title: String,
+    /// A few alternative titles (romanized, abbreviations...), most useful first
+    pub alt_titles: Vec<String>,
     pub author: String,
     #[allow(dead_code)]
     pub artist: String,
     pub status: String,
     pub description: String,
     pub cover_url: String,
+    /// Most recently uploaded chapter, used to notice new releases of bookmarked series
+    pub latest_chapter_id: Option<String>,
+    /// `safe`, `suggestive`, `erotica` or `pornographic`
+    pub content_rating: String,
+    /// Genres, themes and formats, in the order MangaDex lists them
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+}
+
+impl Manga {
+    /// Anything rated above `safe`, whose cover gets blurred when `blur_covers` is on.
+    pub fn is_explicit(&self) -> bool {
+        matches!(self.content_rating.as_str(), "suggestive" | "erotica" | "pornographic")
+    }
+
+    /// Rated `pornographic`, which needs the session's opt-in before it's shown.
+    pub fn is_pornographic(&self) -> bool {
+        self.content_rating == "pornographic"
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -82,12 +240,33 @@ struct MangaData {
 #[derive(Debug, Deserialize)]
 struct MangaAttributes {
     title: HashMap<String, String>,
+    #[serde(rename = "altTitles", default)]
+    alt_titles: Vec<HashMap<String, String>>,
     status: Option<String>,
     description: Option<HashMap<String, String>>,
+    #[serde(rename = "latestUploadedChapter", default)]
+    latest_uploaded_chapter: Option<String>,
+    #[serde(rename = "contentRating", default)]
+    content_rating: Option<String>,
+    #[serde(default)]
+    tags: Vec<TagData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagData {
+    id: String,
+    attributes: TagAttributes,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagAttributes {
+    name: HashMap<String, String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct Relationship {
+    #[serde(default)]
+    id: String,
     #[serde(rename = "type")]
     rel_type: String,
     attributes: Option<RelationshipAttributes>,
@@ -100,6 +279,60 @@ struct RelationshipAttributes {
     file_name: Option<String>,
 }
 
+const MAX_ALT_TITLES: usize = 5;
+
+static TITLE_LANGUAGE: OnceLock<String> = OnceLock::new();
+
+/// Sets the language, such as `ja-ro`, whose title is shown for each manga when it has one.
+/// Must be called before the first manga list is parsed.
+pub fn init_title_language(language: Option<String>) {
+    if let Some(language) = language
+        && TITLE_LANGUAGE.set(language).is_err()
+    {
+        log::warn!("Title language already initialized; ignoring new language");
+    }
+}
+
+/// The display title: the `preferred` language from the main title or the alternatives,
+/// else the English title, else whatever the main title is.
+fn pick_display_title(
+    title: &HashMap<String, String>,
+    alt_titles: &[HashMap<String, String>],
+    preferred: Option<&str>,
+) -> String {
+    let from_alts = |lang: &str| alt_titles.iter().find_map(|m| m.get(lang));
+    preferred
+        .and_then(|lang| title.get(lang).or_else(|| from_alts(lang)))
+        .or_else(|| title.get("en"))
+        .or_else(|| title.values().next())
+        .cloned()
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// Flattens MangaDex's list of single-entry `{lang: title}` maps, preferring English
+/// then romanized Japanese, and skipping duplicates of the display title.
+fn pick_alt_titles(alt_titles: &[HashMap<String, String>], title: &str) -> Vec<String> {
+    let rank = |lang: &str| match lang {
+        "en" => 0,
+        "ja-ro" => 1,
+        _ => 2,
+    };
+
+    let mut entries: Vec<(&String, &String)> = alt_titles.iter().flat_map(|m| m.iter()).collect();
+    entries.sort_by_key(|(lang, _)| rank(lang));
+
+    let mut picked: Vec<String> = Vec::new();
+    for (_, alt) in entries {
+        if alt != title && !picked.contains(alt) {
+            picked.push(alt.clone());
+        }
+        if picked.len() >= MAX_ALT_TITLES {
+            break;
+        }
+    }
+    picked
+}
+
 fn parse_manga_list(response: MangaResponse) -> Vec<Manga> {
     response
         .data
@@ -139,11 +372,11 @@ fn parse_manga_list(response: MangaResponse) -> Vec<Manga> {
                 String::new()
             };
 
-            let title = m.attributes.title
-                .get("en")
-                .or_else(|| m.attributes.title.values().next())
-                .cloned()
-                .unwrap_or_else(|| "Unknown".to_string());
+            let title = pick_display_title(
+                &m.attributes.title,
+                &m.attributes.alt_titles,
+                TITLE_LANGUAGE.get().map(String::as_str),
+            );
 
             let description = m.attributes.description
                 .as_ref()
@@ -151,29 +384,349 @@ fn parse_manga_list(response: MangaResponse) -> Vec<Manga> {
                 .cloned()
                 .unwrap_or_default();
 
+            // The main title is listed too, in case a preferred language replaced it
+            let mut alt_maps = m.attributes.alt_titles.clone();
+            alt_maps.push(m.attributes.title.clone());
+            let alt_titles = pick_alt_titles(&alt_maps, &title);
+
+            let tags = m.attributes.tags
+                .iter()
+                .filter_map(|t| {
+                    let name = t.attributes.name.get("en").or_else(|| t.attributes.name.values().next())?;
+                    Some(Tag { id: t.id.clone(), name: name.clone() })
+                })
+                .collect();
+
             Manga {
                 id: m.id,
                 title,
+                alt_titles,
                 author,
                 artist,
                 status: m.attributes.status.unwrap_or_else(|| "Unknown".to_string()),
                 description,
                 cover_url,
+                latest_chapter_id: m.attributes.latest_uploaded_chapter,
+                content_rating: m.attributes.content_rating.unwrap_or_else(|| "safe".to_string()),
+                tags,
             }
         })
         .collect()
 }
 
-fn build_client() -> reqwest::Client {
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+static HTTP_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+fn build_client(timeout: Duration, connect_timeout: Duration) -> reqwest::Client {
     reqwest::Client::builder()
         .user_agent("Tachiyomi-TUI/0.1.0")
-        .timeout(std::time::Duration::from_secs(30))
-        .connect_timeout(std::time::Duration::from_secs(10))
+        .timeout(timeout)
+        .connect_timeout(connect_timeout)
         .build()
         .expect("Failed to build HTTP client")
 }
 
+/// Sets the whole-request and connect timeouts. Must be called before the first fetch.
+pub fn init_http_client(timeout_secs: u64, connect_timeout_secs: u64) {
+    let client = build_client(
+        Duration::from_secs(timeout_secs.max(1)),
+        Duration::from_secs(connect_timeout_secs.max(1)),
+    );
+    if HTTP_CLIENT.set(client).is_err() {
+        log::warn!("HTTP client already initialized; ignoring new timeouts");
+    }
+}
+
+pub(super) fn http_client() -> &'static reqwest::Client {
+    HTTP_CLIENT.get_or_init(|| {
+        build_client(
+            Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECS),
+            Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS),
+        )
+    })
+}
+
+pub const DEFAULT_PAGE_FETCH_RETRIES: u32 = 2;
+pub const DEFAULT_PAGE_RETRY_BACKOFF_MS: u64 = 500;
+
+/// How often, and how patiently, a failed page download is retried.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub retries: u32,
+    /// Delay before the first retry; doubles on each one after
+    pub base_delay_ms: u64,
+}
+
+impl RetryPolicy {
+    /// Exponential backoff with up to 50% random jitter, so parallel loaders that failed
+    /// together don't all hit the rate limiter again at the same instant.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let base = self.base_delay_ms.saturating_mul(1 << retry.saturating_sub(1).min(16));
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos() as u64)
+            .unwrap_or_default();
+        let jitter = if base > 1 { nanos % (base / 2).max(1) } else { 0 };
+        Duration::from_millis(base + jitter)
+    }
+}
+
+pub const DEFAULT_MAX_CONCURRENT_FETCHES: usize = 6;
+
+/// `max_concurrent_fetches`, with one of its slots kept for the focused card's cover.
+/// Ordinary requests queue on `queued`, which has a permit fewer than `in_flight`, so a
+/// priority request only ever waits for ones already being sent, never for the queue.
+struct FetchLimit {
+    queued: Semaphore,
+    in_flight: Semaphore,
+}
+
+impl FetchLimit {
+    fn new(max_concurrent: usize) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            queued: Semaphore::new((max_concurrent - 1).max(1)),
+            in_flight: Semaphore::new(max_concurrent),
+        }
+    }
+}
+
+static FETCH_LIMIT: OnceLock<FetchLimit> = OnceLock::new();
+
+fn fetch_limit() -> &'static FetchLimit {
+    FETCH_LIMIT.get_or_init(|| FetchLimit::new(DEFAULT_MAX_CONCURRENT_FETCHES))
+}
+
+/// Sets how many requests may be in flight at once. Must be called before the first fetch.
+pub fn init_fetch_limit(max_concurrent: usize) {
+    if FETCH_LIMIT.set(FetchLimit::new(max_concurrent)).is_err() {
+        log::warn!("Fetch limit already initialized; ignoring new limit");
+    }
+}
+
+/// A slot under the fetch limit, held while a request is sent.
+pub(super) struct FetchPermit {
+    _queued: Option<SemaphorePermit<'static>>,
+    _in_flight: SemaphorePermit<'static>,
+}
+
+pub(super) async fn acquire_fetch_permit() -> FetchPermit {
+    let limit = fetch_limit();
+    let queued = limit.queued.acquire().await.expect("fetch semaphore is never closed");
+    let in_flight = limit.in_flight.acquire().await.expect("fetch semaphore is never closed");
+    FetchPermit { _queued: Some(queued), _in_flight: in_flight }
+}
+
+/// Like [`acquire_fetch_permit`], but skips the queue of ordinary requests.
+async fn acquire_priority_fetch_permit() -> FetchPermit {
+    let in_flight = fetch_limit().in_flight.acquire().await.expect("fetch semaphore is never closed");
+    FetchPermit { _queued: None, _in_flight: in_flight }
+}
+
+/// Wait used after a 429 that doesn't say how long to back off
+const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(5);
+/// Longest wait honored from a 429, in case a header is garbled
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(120);
+
+/// Every request waits until this passes, once the server asked us to back off
+static RATE_LIMITED_UNTIL: Mutex<Option<Instant>> = Mutex::new(None);
+static RATE_LIMIT_HITS: AtomicUsize = AtomicUsize::new(0);
+
+/// How long a 429 asks to wait. `Retry-After` gives seconds; MangaDex's
+/// `X-RateLimit-Retry-After` gives the Unix time the limit resets.
+fn rate_limit_wait(retry_after: Option<&str>, reset_at: Option<&str>, now_unix: u64) -> Duration {
+    let from_retry_after = retry_after.and_then(|v| v.trim().parse::<u64>().ok());
+    let from_reset = reset_at
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(|reset| reset.saturating_sub(now_unix));
+    from_retry_after
+        .or(from_reset)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_RATE_LIMIT_WAIT)
+        .min(MAX_RATE_LIMIT_WAIT)
+}
+
+/// Time left before requests resume after a 429, if they're paused.
+pub fn rate_limit_pause() -> Option<Duration> {
+    let until = (*RATE_LIMITED_UNTIL.lock().unwrap_or_else(|e| e.into_inner()))?;
+    until.checked_duration_since(Instant::now()).filter(|d| !d.is_zero())
+}
+
+/// Holds off requests after a 429 until the server's limit has reset.
+fn note_rate_limited(response: &reqwest::Response) {
+    let header = |name: &str| response.headers().get(name).and_then(|v| v.to_str().ok());
+    let now_unix = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+    let wait = rate_limit_wait(header("retry-after"), header("x-ratelimit-retry-after"), now_unix);
+
+    let until = Instant::now() + wait;
+    let mut limited = RATE_LIMITED_UNTIL.lock().unwrap_or_else(|e| e.into_inner());
+    if limited.is_none_or(|current| current < until) {
+        *limited = Some(until);
+    }
+    RATE_LIMIT_HITS.fetch_add(1, Ordering::Relaxed);
+    log::warn!("Rate limited by {}; pausing requests for {}s", response.url().host_str().unwrap_or("server"), wait.as_secs());
+}
+
+/// Number of 429 responses this session, so the UI can tell when a new one arrives.
+pub fn rate_limit_hits() -> usize {
+    RATE_LIMIT_HITS.load(Ordering::Relaxed)
+}
+
+static VERBOSE_NETWORK: AtomicBool = AtomicBool::new(false);
+static REQUEST_COUNT: AtomicUsize = AtomicUsize::new(0);
+static BYTES_DOWNLOADED: AtomicU64 = AtomicU64::new(0);
+
+/// Logs every request's URL, status and timing at info level instead of debug.
+pub fn set_verbose_network(verbose: bool) {
+    VERBOSE_NETWORK.store(verbose, Ordering::Relaxed);
+}
+
+pub fn is_verbose_network() -> bool {
+    VERBOSE_NETWORK.load(Ordering::Relaxed)
+}
+
+/// Number of requests sent this session.
+pub fn request_count() -> usize {
+    REQUEST_COUNT.load(Ordering::Relaxed)
+}
+
+/// Total response body bytes received this session.
+pub fn bytes_downloaded() -> u64 {
+    BYTES_DOWNLOADED.load(Ordering::Relaxed)
+}
+
+pub(super) fn record_download(len: usize) {
+    BYTES_DOWNLOADED.fetch_add(len as u64, Ordering::Relaxed);
+}
+
+/// Sends a GET request, logging it consistently for every API and image fetch.
+async fn send_get(url: &str) -> Result<reqwest::Response, reqwest::Error> {
+    send_get_with(url, reqwest::header::HeaderMap::new()).await
+}
+
+async fn send_get_with(url: &str, headers: reqwest::header::HeaderMap) -> Result<reqwest::Response, reqwest::Error> {
+    send_request("GET", url, http_client().get(url).headers(headers)).await
+}
+
+/// Sends a prepared request for `url`, logging it the same way as the GETs.
+pub(super) async fn send_request(
+    method: &str,
+    url: &str,
+    request: reqwest::RequestBuilder,
+) -> Result<reqwest::Response, reqwest::Error> {
+    let level = if is_verbose_network() {
+        log::Level::Info
+    } else {
+        log::Level::Debug
+    };
+
+    // Callers hold a fetch permit, so this pauses the whole pool rather than just one request
+    if let Some(wait) = rate_limit_pause() {
+        log::log!(level, "{} {} waiting {}ms for the rate limit", method, url, wait.as_millis());
+        tokio::time::sleep(wait).await;
+    }
+
+    let request_no = REQUEST_COUNT.fetch_add(1, Ordering::Relaxed) + 1;
+    let started = Instant::now();
+    let result = request.send().await;
+    let elapsed = started.elapsed().as_millis();
+
+    match &result {
+        Ok(response) => {
+            log::log!(level, "#{} {} {} -> {} ({}ms)", request_no, method, url, response.status(), elapsed);
+            if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                note_rate_limited(response);
+            }
+        }
+        Err(e) => log::log!(level, "#{} {} {} -> error: {} ({}ms)", request_no, method, url, e, elapsed),
+    }
+    result
+}
+
+pub(super) fn parse_json<T: DeserializeOwned>(body: &str) -> Result<T, ApiError> {
+    serde_json::from_str(body).map_err(|e| {
+        log::debug!("Failed to deserialize response ({}); raw body: {}", e, body);
+        ApiError::Parse(e)
+    })
+}
+
+async fn fetch_json<T: DeserializeOwned>(url: &str) -> Result<T, ApiError> {
+    let _permit = acquire_fetch_permit().await;
+    let response = send_get(url).await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(ApiError::Status(status));
+    }
+
+    let body = response.text().await?;
+    record_download(body.len());
+    parse_json(&body)
+}
+
+fn header_string(response: &reqwest::Response, name: reqwest::header::HeaderName) -> Option<String> {
+    response.headers().get(name)?.to_str().ok().map(str::to_string)
+}
+
+/// Like `fetch_json`, but keeps the body on disk and revalidates it with `If-None-Match` /
+/// `If-Modified-Since` next time. A 304 reuses the stored body, and one still fresh under
+/// its `Cache-Control: max-age` is used without a request at all.
+async fn fetch_json_cached<T: DeserializeOwned>(url: &str) -> Result<T, ApiError> {
+    use reqwest::header::{CACHE_CONTROL, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+
+    let cached = http_cache::get(url).await;
+    if let Some(entry) = cached.as_ref().filter(|e| e.is_fresh()) {
+        log::debug!("GET {} -> fresh in response cache", url);
+        return parse_json(&entry.body);
+    }
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Some(entry) = cached.as_ref().filter(|e| e.can_revalidate()) {
+        let validators = [(IF_NONE_MATCH, &entry.etag), (IF_MODIFIED_SINCE, &entry.last_modified)];
+        for (name, value) in validators {
+            if let Some(value) = value.as_deref().and_then(|v| v.parse().ok()) {
+                headers.insert(name, value);
+            }
+        }
+    }
+
+    let _permit = acquire_fetch_permit().await;
+    let response = send_get_with(url, headers).await?;
+    let status = response.status();
+    let control = http_cache::CacheControl::parse(header_string(&response, CACHE_CONTROL).as_deref());
+
+    if status == reqwest::StatusCode::NOT_MODIFIED
+        && let Some(entry) = cached
+    {
+        return parse_json(&http_cache::refresh(entry, &control).await);
+    }
+    if !status.is_success() {
+        return Err(ApiError::Status(status));
+    }
+
+    let etag = header_string(&response, ETAG);
+    let last_modified = header_string(&response, LAST_MODIFIED);
+    let body = response.text().await?;
+    record_download(body.len());
+    let parsed = parse_json(&body)?;
+    http_cache::put(url, body, etag, last_modified, &control).await;
+    Ok(parsed)
+}
+
 pub async fn fetch_cover_image(cover_url: &str) -> Option<DynamicImage> {
+    fetch_cover(cover_url, false).await
+}
+
+/// Like [`fetch_cover_image`], but ahead of requests still waiting for the fetch limit,
+/// so the cover the user is looking at arrives first.
+pub async fn fetch_cover_image_now(cover_url: &str) -> Option<DynamicImage> {
+    fetch_cover(cover_url, true).await
+}
+
+async fn fetch_cover(cover_url: &str, priority: bool) -> Option<DynamicImage> {
     if cover_url.is_empty() {
         return None;
     }
@@ -181,9 +734,14 @@ pub async fn fetch_cover_image(cover_url: &str) -> Option<DynamicImage> {
     // Use thumbnail size (256px) for faster loading
     let thumb_url = format!("{}.256.jpg", cover_url);
     
-    let client = build_client();
-    let response = client.get(&thumb_url).send().await.ok()?;
+    let _permit = if priority {
+        acquire_priority_fetch_permit().await
+    } else {
+        acquire_fetch_permit().await
+    };
+    let response = send_get(&thumb_url).await.ok()?;
     let bytes = response.bytes().await.ok()?;
+    record_download(bytes.len());
     
     image::ImageReader::new(Cursor::new(bytes))
         .with_guessed_format()
@@ -192,146 +750,408 @@ pub async fn fetch_cover_image(cover_url: &str) -> Option<DynamicImage> {
         .ok()
 }
 
-pub async fn get_recently_updated() -> Result<Vec<Manga>, Error> {
-    let url = format!(
-        "{}/manga?includes[]=author&includes[]=artist&includes[]=cover_art&order[latestUploadedChapter]=desc&limit=20",
-        BASE_URL
-    );
+pub const DEFAULT_BROWSE_LIMIT: usize = 20;
+/// Largest page size MangaDex accepts for list endpoints
+const MAX_BROWSE_LIMIT: usize = 100;
 
-    let client = build_client();
-    let response: MangaResponse = client.get(&url).send().await?.json().await?;
+fn clamp_browse_limit(limit: usize) -> usize {
+    limit.clamp(1, MAX_BROWSE_LIMIT)
+}
 
-    Ok(parse_manga_list(response))
+/// Every rating MangaDex has. List endpoints default to all but pornographic, which would
+/// leave nothing for the explicit content prompt to gate.
+const CONTENT_RATING_PARAMS: &str =
+    "contentRating[]=safe&contentRating[]=suggestive&contentRating[]=erotica&contentRating[]=pornographic";
+
+/// A `/manga` list URL including authors, artists and covers for every content rating,
+/// followed by `params`.
+fn manga_list_url(params: &str) -> String {
+    format!(
+        "{}/manga?includes[]=author&includes[]=artist&includes[]=cover_art&{}&{}",
+        BASE_URL, CONTENT_RATING_PARAMS, params
+    )
 }
 
-pub async fn get_popular_now() -> Result<Vec<Manga>, Error> {
-    let url = format!(
-        "{}/manga?includes[]=author&includes[]=artist&includes[]=cover_art&order[followedCount]=desc&limit=20",
-        BASE_URL
-    );
+pub async fn get_recently_updated(limit: usize) -> Result<Vec<Manga>, ApiError> {
+    let url = manga_list_url(&format!(
+        "order[latestUploadedChapter]=desc&limit={}",
+        clamp_browse_limit(limit)
+    ));
 
-    let client = build_client();
-    let response: MangaResponse = client.get(&url).send().await?.json().await?;
+    let response: MangaResponse = fetch_json_cached(&url).await?;
 
     Ok(parse_manga_list(response))
 }
 
-pub async fn search_manga(query: &str) -> Result<Vec<Manga>, Error> {
-    let encoded_query = urlencoding::encode(query);
-    let url = format!(
-        "{}/manga?includes[]=author&includes[]=artist&includes[]=cover_art&title={}&limit=20",
-        BASE_URL, encoded_query
-    );
+pub async fn get_popular_now(limit: usize) -> Result<Vec<Manga>, ApiError> {
+    let url = manga_list_url(&format!("order[followedCount]=desc&limit={}", clamp_browse_limit(limit)));
 
-    let client = build_client();
-    let response: MangaResponse = client.get(&url).send().await?.json().await?;
+    let response: MangaResponse = fetch_json_cached(&url).await?;
 
     Ok(parse_manga_list(response))
 }
 
-pub async fn get_manga_chapters(manga_id: &str) -> Result<Vec<Chapter>, Error> {
-    let url = format!(
-        "{}/manga/{}/feed?translatedLanguage[]=en&order[chapter]=asc&limit=100",
-        BASE_URL, manga_id
-    );
+/// Order of search results, cycled from the Search tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchOrder {
+    #[default]
+    Relevance,
+    Title,
+    MostFollowed,
+    Latest,
+}
 
-    log::debug!("Fetching chapters from: {}", url);
+impl SearchOrder {
+    pub fn next(self) -> Self {
+        match self {
+            SearchOrder::Relevance => SearchOrder::Title,
+            SearchOrder::Title => SearchOrder::MostFollowed,
+            SearchOrder::MostFollowed => SearchOrder::Latest,
+            SearchOrder::Latest => SearchOrder::Relevance,
+        }
+    }
 
-    let client = build_client();
-    let unparsed_response = match client.get(&url).send().await {
-         Ok(resp) => {
-            log::debug!("Response status: {}", resp.status());
-            resp
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchOrder::Relevance => "relevance",
+            SearchOrder::Title => "title A-Z",
+            SearchOrder::MostFollowed => "most followed",
+            SearchOrder::Latest => "latest update",
         }
-        Err(e) => {
-            log::error!("Network error fetching chapters: {}", e);
-            return Err(e);
+    }
+
+    fn query_param(self) -> &'static str {
+        match self {
+            SearchOrder::Relevance => "order[relevance]=desc",
+            SearchOrder::Title => "order[title]=asc",
+            SearchOrder::MostFollowed => "order[followedCount]=desc",
+            SearchOrder::Latest => "order[latestUploadedChapter]=desc",
         }
+    }
+}
+
+fn search_url(query: &str, order: SearchOrder, tag_id: Option<&str>) -> String {
+    // With no title there's nothing to rank by relevance, so list the most followed first
+    let order = match order {
+        SearchOrder::Relevance if query.is_empty() => SearchOrder::MostFollowed,
+        order => order,
     };
+    let mut url = manga_list_url(&format!("{}&limit=20", order.query_param()));
+    if !query.is_empty() {
+        url.push_str(&format!("&title={}", urlencoding::encode(query)));
+    }
+    if let Some(tag_id) = tag_id {
+        url.push_str(&format!("&includedTags[]={}", urlencoding::encode(tag_id)));
+    }
+    url
+}
+
+/// Searches by title, optionally only among manga with the tag `tag_id`. With a tag, the
+/// title may be empty to list everything tagged with it.
+pub async fn search_manga(
+    query: &str,
+    order: SearchOrder,
+    tag_id: Option<&str>,
+) -> Result<Vec<Manga>, ApiError> {
+    let url = search_url(query, order, tag_id);
+
+    let response: MangaResponse = fetch_json(&url).await?;
+
+    Ok(parse_manga_list(response))
+}
+
+/// Which feed entries to keep when listing a manga's chapters.
+#[derive(Debug, Clone)]
+pub struct FeedOptions {
+    /// Translation languages in priority order
+    pub languages: Vec<String>,
+    /// Keep a single entry per chapter number
+    pub dedupe: bool,
+    /// Scanlation group names or UUIDs in priority order
+    pub preferred_groups: Vec<String>,
+    /// Fetch every language, so chapters with no translation in `languages` still show up
+    pub other_languages: bool,
+}
 
-    let response: ChapterResponse = match unparsed_response.json::<ChapterResponse>().await {
-        Ok(text) => {
-            log::debug!("Raw chapters response; first chapter and last ({}th) chapter : {}, {}", &text.data.len(), &text.data[0].id, &text.data.last().unwrap().id);
-            text
+/// Keeps, for each chapter number, only the entries in the highest-priority
+/// language that has a translation for it. `languages` is ordered by preference.
+fn select_preferred_languages(data: Vec<ChapterData>, languages: &[String]) -> Vec<ChapterData> {
+    let priority = |c: &ChapterData| {
+        languages
+            .iter()
+            .position(|l| *l == c.attributes.translated_language)
+            .unwrap_or(usize::MAX)
+    };
+
+    let mut best: HashMap<Option<String>, usize> = HashMap::new();
+    for c in &data {
+        let p = priority(c);
+        best.entry(c.attributes.chapter.clone())
+            .and_modify(|b| *b = (*b).min(p))
+            .or_insert(p);
+    }
+
+    data.into_iter()
+        .filter(|c| best.get(&c.attributes.chapter) == Some(&priority(c)))
+        .collect()
+}
+
+/// For each chapter number with an upload from a preferred group, keeps only the uploads
+/// from the highest-priority such group. Numbers no preferred group covers keep every upload.
+fn select_preferred_groups(data: Vec<ChapterData>, preferred: &[String]) -> Vec<ChapterData> {
+    if preferred.is_empty() {
+        return data;
+    }
+    let rank = |c: &ChapterData| group_rank(&c.groups(), preferred).unwrap_or(usize::MAX);
+
+    let mut best: HashMap<String, usize> = HashMap::new();
+    for c in &data {
+        if let Some(number) = c.attributes.chapter.clone().filter(|n| !n.is_empty()) {
+            let r = rank(c);
+            best.entry(number).and_modify(|b| *b = (*b).min(r)).or_insert(r);
         }
-        Err(e) => {
-            log::error!("Failed to read response text: {}", e);
-            return Err(e);
+    }
+
+    data.into_iter()
+        .filter(|c| match c.attributes.chapter.as_ref().filter(|n| !n.is_empty()) {
+            Some(number) => best.get(number) == Some(&rank(c)),
+            None => true,
+        })
+        .collect()
+}
+
+/// Collapses entries sharing a chapter number (e.g. from several scanlation groups) into the
+/// first one, preferring an in-app readable upload over an external link. Chapters without a
+/// number are left alone since they're usually distinct oneshots.
+fn dedupe_chapters(data: Vec<ChapterData>) -> Vec<ChapterData> {
+    let mut kept: Vec<ChapterData> = Vec::with_capacity(data.len());
+    let mut index_by_number: HashMap<String, usize> = HashMap::new();
+
+    for c in data {
+        let Some(number) = c.attributes.chapter.clone().filter(|n| !n.is_empty()) else {
+            kept.push(c);
+            continue;
+        };
+
+        match index_by_number.get(&number) {
+            Some(&idx) => {
+                if kept[idx].attributes.external_url.is_some() && c.attributes.external_url.is_none() {
+                    kept[idx] = c;
+                }
+            }
+            None => {
+                index_by_number.insert(number, kept.len());
+                kept.push(c);
+            }
         }
-    };
+    }
 
-    log::debug!("{}", &response.data[0].attributes.pages);
+    kept
+}
 
-    let chapters: Vec<Chapter> = response
-        .data
+/// Feed entries fetched per request, the most MangaDex allows
+const FEED_PAGE_SIZE: usize = 500;
+/// MangaDex rejects feed requests that reach past this many entries
+const FEED_MAX_ENTRIES: usize = 10_000;
+
+/// Where the next page of a feed starts, after `fetched` entries from `start`, or `None`
+/// once all `total` are in.
+fn next_feed_offset(start: usize, fetched: usize, total: usize) -> Option<usize> {
+    let next = start + fetched;
+    (fetched > 0 && next < total && next + FEED_PAGE_SIZE <= FEED_MAX_ENTRIES).then_some(next)
+}
+
+/// Drops uploads that can't be opened, then picks among what's left by language, group and
+/// chapter number. Unreadable uploads go first so one can't win a chapter over a readable one
+/// and then take the whole chapter with it.
+fn select_feed_entries(data: Vec<ChapterData>, options: &FeedOptions) -> Vec<ChapterData> {
+    let readable: Vec<ChapterData> = data
         .into_iter()
         .filter(|c| c.attributes.pages > 0 || c.attributes.external_url.is_some())
+        .collect();
+    let mut data = select_preferred_languages(readable, &options.languages);
+    data = select_preferred_groups(data, &options.preferred_groups);
+    if options.dedupe {
+        data = dedupe_chapters(data);
+    }
+    data
+}
+
+fn manga_by_ids_url(ids: &[String]) -> String {
+    let id_params: String = ids.iter().map(|id| format!("ids[]={}&", id)).collect();
+    manga_list_url(&format!("{}limit=100", id_params))
+}
+
+/// Current metadata for specific manga. MangaDex caps `ids[]` at 100 per request.
+pub async fn get_manga_by_ids(ids: &[String]) -> Result<Vec<Manga>, ApiError> {
+    let url = manga_by_ids_url(ids);
+
+    let response: MangaResponse = fetch_json(&url).await?;
+
+    Ok(parse_manga_list(response))
+}
+
+pub async fn get_manga_chapters(manga_id: &str, options: &FeedOptions) -> Result<Vec<Chapter>, ApiError> {
+    let language_params: String = if options.other_languages {
+        String::new()
+    } else {
+        options
+            .languages
+            .iter()
+            .map(|l| format!("translatedLanguage[]={}&", urlencoding::encode(l)))
+            .collect()
+    };
+
+    // Without a language filter a popular title's feed runs to thousands of entries
+    let mut data = Vec::new();
+    let mut offset = Some(0);
+    while let Some(start) = offset {
+        let url = format!(
+            "{}/manga/{}/feed?{}includes[]=scanlation_group&order[chapter]=asc&limit={}&offset={}",
+            BASE_URL, manga_id, language_params, FEED_PAGE_SIZE, start
+        );
+        let response: ChapterResponse = match fetch_json_cached(&url).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                log::error!("Failed to fetch chapters: {}", e);
+                return Err(e);
+            }
+        };
+        offset = next_feed_offset(start, response.data.len(), response.total);
+        data.extend(response.data);
+    }
+
+    let chapters: Vec<Chapter> = select_feed_entries(data, options)
+        .into_iter()
         .map(|c| Chapter {
+            groups: c.groups(),
             id: c.id,
-            chapter: c.attributes.chapter.unwrap_or_else(|| "0".to_string()),
+            chapter: c.attributes.chapter.unwrap_or_default(),
             title: c.attributes.title.unwrap_or_else(|| "No Title".to_string()),
             volume: c.attributes.volume,
             pages: c.attributes.pages,
             external_url: c.attributes.external_url,
+            other_language: !options.languages.contains(&c.attributes.translated_language),
+            language: c.attributes.translated_language,
         })
         .collect();
-    
-    log::debug!("Chapters successfully processed; first chapter and last ({}th) chapter : {}, {}", &chapters.len(), &chapters[0].title, &chapters.last().unwrap().title);
+
+    log::debug!("Chapters successfully processed: {} chapters", chapters.len());
     Ok(chapters)
 }
 
+/// Page URLs for a chapter, preferring the compressed data-saver files and falling back to
+/// full quality when the server has no data-saver copies.
+fn build_page_urls(base_url: &str, chapter: &AtHomeChapter) -> Vec<String> {
+    let (quality, files) = if !chapter.data_saver.is_empty() {
+        ("data-saver", &chapter.data_saver)
+    } else {
+        ("data", &chapter.data)
+    };
+    log::debug!("Using {} {} page files for chapter hash {}", files.len(), quality, chapter.hash);
+
+    files
+        .iter()
+        .map(|filename| format!("{}/{}/{}/{}", base_url, quality, chapter.hash, filename))
+        .collect()
+}
+
 pub async fn get_chapter_pages(chapter_id: &str) -> Option<Vec<String>> {
     let url = format!("{}/at-home/server/{}", BASE_URL, chapter_id);
-    log::debug!("Fetching from URL: {}", url);
 
-    let client = build_client();
-    let unparsed_response: reqwest::Response = match client.get(&url).send().await {
-        Ok(resp) => {
-            log::debug!("Response status: {}", resp.status());
-            resp
-        }
+    let response: AtHomeResponse = match fetch_json(&url).await {
+        Ok(resp) => resp,
         Err(e) => {
-            log::error!("Network error: {}", e);
+            log::error!("Failed to fetch page URLs: {}", e);
             return None;
         }
     };
-    
-    let response: AtHomeResponse = match unparsed_response.json::<AtHomeResponse>().await {
-        Ok(resp) => {
-            log::trace!("Response url: {}", resp.base_url);
-            resp
-        }
-        Err(e) => {
-            log::error!("Parsing error: {}", e);
-            return None
-        }
-    };
 
-    let pages = response
-        .chapter
-        .data_saver
-        .into_iter()
-        .map(|filename| {
-            format!(
-                "{}/data-saver/{}/{}",
-                response.base_url, response.chapter.hash, filename
-            )
-        })
-        .collect();
+    let pages = build_page_urls(&response.base_url, &response.chapter);
+    if pages.is_empty() {
+        log::warn!("Chapter {} has no page files in either quality", chapter_id);
+    }
 
     Some(pages)
 }
 
-pub async fn fetch_page_image(page_url: &str) -> Option<DynamicImage> {
-    let client = build_client();
-    let response = client.get(page_url).send().await.ok()?;
-    let bytes = response.bytes().await.ok()?;
+pub fn manga_web_url(manga_id: &str) -> String {
+    format!("{}/title/{}", WEB_URL, manga_id)
+}
 
-    image::ImageReader::new(Cursor::new(bytes))
+pub fn chapter_web_url(chapter_id: &str) -> String {
+    format!("{}/chapter/{}", WEB_URL, chapter_id)
+}
+
+/// MangaDex as a [`Source`], backed by the functions above.
+pub struct MangaDex;
+
+impl Source for MangaDex {
+    fn name(&self) -> &'static str {
+        "MangaDex"
+    }
+
+    fn popular(&self, limit: usize) -> BoxFuture<'_, Result<Vec<Manga>, ApiError>> {
+        Box::pin(get_popular_now(limit))
+    }
+
+    fn recent(&self, limit: usize) -> BoxFuture<'_, Result<Vec<Manga>, ApiError>> {
+        Box::pin(get_recently_updated(limit))
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a str,
+        order: SearchOrder,
+        tag_id: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Vec<Manga>, ApiError>> {
+        Box::pin(search_manga(query, order, tag_id))
+    }
+
+    fn chapters<'a>(
+        &'a self,
+        manga_id: &'a str,
+        options: &'a FeedOptions,
+    ) -> BoxFuture<'a, Result<Vec<Chapter>, ApiError>> {
+        Box::pin(get_manga_chapters(manga_id, options))
+    }
+
+    fn chapter_pages<'a>(&'a self, chapter_id: &'a str) -> BoxFuture<'a, Option<Vec<String>>> {
+        Box::pin(get_chapter_pages(chapter_id))
+    }
+
+    fn cover<'a>(&'a self, cover_url: &'a str) -> BoxFuture<'a, Option<DynamicImage>> {
+        Box::pin(fetch_cover_image(cover_url))
+    }
+
+    fn manga_url(&self, manga_id: &str) -> Option<String> {
+        Some(manga_web_url(manga_id))
+    }
+
+    fn chapter_url(&self, chapter_id: &str) -> Option<String> {
+        Some(chapter_web_url(chapter_id))
+    }
+}
+
+/// A downloaded page: the bytes as served plus the decoded image.
+pub struct FetchedPage {
+    pub bytes: Vec<u8>,
+    pub image: DynamicImage,
+}
+
+pub async fn fetch_page_image(page_url: &str) -> Option<FetchedPage> {
+    let _permit = acquire_fetch_permit().await;
+    let response = send_get(page_url).await.ok()?;
+    let bytes = response.bytes().await.ok()?.to_vec();
+    record_download(bytes.len());
+
+    let image = image::ImageReader::new(Cursor::new(&bytes))
         .with_guessed_format()
         .ok()?
         .decode()
-        .ok()
+        .ok()?;
+
+    Some(FetchedPage { bytes, image })
 }
 
 #[cfg(test)]
@@ -340,7 +1160,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_recently_updated() {
-        let result = get_recently_updated().await;
+        let result = get_recently_updated(DEFAULT_BROWSE_LIMIT).await;
         match &result {
             Ok(_) => {}
             Err(e) => println!("Error: {:?}", e),
@@ -364,7 +1184,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_popular_now() {
-        let result = get_popular_now().await;
+        let result = get_popular_now(DEFAULT_BROWSE_LIMIT).await;
         match &result {
             Ok(_) => {}
             Err(e) => println!("Error: {:?}", e),
@@ -386,6 +1206,290 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rate_limit_wait() {
+        let now = 1_700_000_000;
+        assert_eq!(rate_limit_wait(Some("12"), None, now), Duration::from_secs(12));
+        assert_eq!(rate_limit_wait(None, Some("1700000030"), now), Duration::from_secs(30));
+        // An HTTP-date Retry-After isn't parsed, so the reset time or default applies
+        assert_eq!(rate_limit_wait(Some("Wed, 21 Oct 2015 07:28:00 GMT"), None, now), DEFAULT_RATE_LIMIT_WAIT);
+        assert_eq!(rate_limit_wait(Some("100000"), None, now), MAX_RATE_LIMIT_WAIT);
+    }
+
+    #[test]
+    fn test_list_urls_ask_for_every_content_rating() {
+        let urls = [
+            manga_list_url("order[followedCount]=desc&limit=20"),
+            search_url("berserk", SearchOrder::Relevance, None),
+            search_url("", SearchOrder::Title, Some("tag-1")),
+            manga_by_ids_url(&["a".to_string(), "b".to_string()]),
+        ];
+        for url in &urls {
+            for rating in ["safe", "suggestive", "erotica", "pornographic"] {
+                assert!(url.contains(&format!("contentRating[]={}", rating)), "{} lacks {}", url, rating);
+            }
+        }
+        assert!(urls[3].contains("ids[]=a&ids[]=b&limit=100"));
+    }
+
+    #[test]
+    fn test_tag_only_search_orders_by_follows() {
+        let url = search_url("", SearchOrder::Relevance, Some("tag-1"));
+        assert!(url.contains("order[followedCount]=desc"));
+        assert!(!url.contains("order[relevance]"));
+        assert!(search_url("berserk", SearchOrder::Relevance, Some("tag-1")).contains("order[relevance]=desc"));
+    }
+
+    #[test]
+    fn test_parse_malformed_manga_response() {
+        // `attributes.title` should be a map, and `relationships` is missing
+        let body = r#"{"data": [{"id": "abc", "attributes": {"title": "oops"}}]}"#;
+        let result = parse_json::<MangaResponse>(body);
+        assert!(matches!(result, Err(ApiError::Parse(_))));
+
+        let truncated = r#"{"data": [{"id": "abc", "#;
+        let result = parse_json::<MangaResponse>(truncated);
+        assert!(matches!(result, Err(ApiError::Parse(_))));
+    }
+
+    #[test]
+    fn test_select_preferred_languages() {
+        let chapter = |id: &str, number: &str, lang: &str| ChapterData {
+            id: id.to_string(),
+            attributes: ChapterAttributes {
+                chapter: Some(number.to_string()),
+                title: None,
+                volume: None,
+                pages: 10,
+                translated_language: lang.to_string(),
+                external_url: None,
+            },
+            relationships: vec![],
+        };
+        let data = vec![
+            chapter("1-ja", "1", "ja"),
+            chapter("1-en", "1", "en"),
+            chapter("2-es", "2", "es"),
+            chapter("2-ja", "2", "ja"),
+            chapter("3-ja", "3", "ja"),
+        ];
+        let languages = vec!["en".to_string(), "es".to_string(), "ja".to_string()];
+
+        let ids: Vec<String> = select_preferred_languages(data, &languages)
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(ids, vec!["1-en", "2-es", "3-ja"]);
+
+        // An empty upload in a preferred language leaves the chapter to a readable fallback
+        let mut empty = chapter("4-en", "4", "en");
+        empty.attributes.pages = 0;
+        let options = FeedOptions {
+            languages,
+            dedupe: false,
+            preferred_groups: vec![],
+            other_languages: false,
+        };
+        let ids: Vec<String> = select_feed_entries(vec![empty, chapter("4-ja", "4", "ja")], &options)
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(ids, vec!["4-ja"]);
+    }
+
+    #[test]
+    fn test_select_preferred_groups() {
+        let chapter = |id: &str, number: &str, group: &str| ChapterData {
+            id: id.to_string(),
+            attributes: ChapterAttributes {
+                chapter: Some(number.to_string()),
+                title: None,
+                volume: None,
+                pages: 10,
+                translated_language: "en".to_string(),
+                external_url: None,
+            },
+            relationships: vec![Relationship {
+                id: format!("{}-uuid", group),
+                rel_type: "scanlation_group".to_string(),
+                attributes: Some(RelationshipAttributes {
+                    name: Some(group.to_string()),
+                    file_name: None,
+                }),
+            }],
+        };
+        let data = vec![
+            chapter("1-other", "1", "Other Scans"),
+            chapter("1-fav", "1", "Fav Scans"),
+            chapter("1-second", "1", "Second Scans"),
+            chapter("2-second", "2", "Second Scans"),
+            chapter("2-other", "2", "Other Scans"),
+            chapter("3-other", "3", "Other Scans"),
+        ];
+        let preferred = vec!["fav scans".to_string(), "Second Scans-uuid".to_string()];
+
+        let ids: Vec<String> = select_preferred_groups(data, &preferred)
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(ids, vec!["1-fav", "2-second", "3-other"]);
+
+        // A preferred group's empty upload doesn't hide another group's readable one
+        let mut empty = chapter("4-fav", "4", "Fav Scans");
+        empty.attributes.pages = 0;
+        let options = FeedOptions {
+            languages: vec!["en".to_string()],
+            dedupe: false,
+            preferred_groups: preferred,
+            other_languages: false,
+        };
+        let ids: Vec<String> = select_feed_entries(vec![empty, chapter("4-other", "4", "Other Scans")], &options)
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(ids, vec!["4-other"]);
+    }
+
+    #[test]
+    fn test_next_feed_offset() {
+        assert_eq!(next_feed_offset(0, 500, 1200), Some(500));
+        assert_eq!(next_feed_offset(1000, 200, 1200), None);
+        // An empty page ends it even if the total says otherwise
+        assert_eq!(next_feed_offset(500, 0, 1200), None);
+        assert_eq!(next_feed_offset(9000, 500, 20_000), Some(9500));
+        assert_eq!(next_feed_offset(9500, 500, 20_000), None);
+    }
+
+    #[test]
+    fn test_dedupe_chapters() {
+        let chapter = |id: &str, number: Option<&str>, external: bool| ChapterData {
+            id: id.to_string(),
+            attributes: ChapterAttributes {
+                chapter: number.map(str::to_string),
+                title: None,
+                volume: None,
+                pages: if external { 0 } else { 10 },
+                translated_language: "en".to_string(),
+                external_url: external.then(|| "https://example.com".to_string()),
+            },
+            relationships: vec![],
+        };
+        let data = vec![
+            chapter("1-a", Some("1"), false),
+            chapter("1-b", Some("1"), false),
+            chapter("2-ext", Some("2"), true),
+            chapter("2-a", Some("2"), false),
+            chapter("oneshot-a", None, false),
+            chapter("oneshot-b", None, false),
+        ];
+
+        let ids: Vec<String> = dedupe_chapters(data).into_iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec!["1-a", "2-a", "oneshot-a", "oneshot-b"]);
+    }
+
+    #[test]
+    fn test_build_page_urls_falls_back_to_full_quality() {
+        let chapter = AtHomeChapter {
+            hash: "abc".to_string(),
+            data: vec!["1.png".to_string()],
+            data_saver: vec![],
+        };
+        assert_eq!(
+            build_page_urls("https://uploads.example", &chapter),
+            vec!["https://uploads.example/data/abc/1.png"]
+        );
+
+        let chapter = AtHomeChapter {
+            data_saver: vec!["1.jpg".to_string()],
+            ..chapter
+        };
+        assert_eq!(
+            build_page_urls("https://uploads.example", &chapter),
+            vec!["https://uploads.example/data-saver/abc/1.jpg"]
+        );
+
+        let empty = AtHomeChapter { hash: "abc".to_string(), data: vec![], data_saver: vec![] };
+        assert!(build_page_urls("https://uploads.example", &empty).is_empty());
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off() {
+        let policy = RetryPolicy { retries: 5, base_delay_ms: 100 };
+        for (retry, base) in [(1, 100), (2, 200), (3, 400)] {
+            let delay = policy.delay(retry).as_millis() as u64;
+            assert!((base..base + base / 2).contains(&delay), "retry {} waited {}ms", retry, delay);
+        }
+    }
+
+    #[test]
+    fn test_chapter_number() {
+        let chapter = |number: &str| Chapter {
+            id: String::new(),
+            chapter: number.to_string(),
+            title: String::new(),
+            volume: None,
+            pages: 1,
+            external_url: None,
+            groups: vec![],
+            language: String::new(),
+            other_language: false,
+        };
+        assert_eq!(chapter("10").number(), Some(10.0));
+        assert_eq!(chapter("10.5").number(), Some(10.5));
+        assert_eq!(chapter("Extra").number(), None);
+
+        let oneshot = chapter("");
+        assert!(oneshot.is_oneshot());
+        assert_eq!(oneshot.short_label(), "Oneshot");
+        assert!(oneshot.sort_key() < chapter("1").sort_key());
+        assert!(chapter("Extra").sort_key() > chapter("100").sort_key());
+    }
+
+    #[test]
+    fn test_pick_alt_titles() {
+        let alt = |lang: &str, title: &str| HashMap::from([(lang.to_string(), title.to_string())]);
+        let alt_titles = vec![
+            alt("ja", "僕のヒーローアカデミア"),
+            alt("ja-ro", "Boku no Hero Academia"),
+            alt("en", "BnHA"),
+            alt("en", "My Hero Academia"),
+        ];
+
+        let picked = pick_alt_titles(&alt_titles, "My Hero Academia");
+        assert_eq!(picked, vec!["BnHA", "Boku no Hero Academia", "僕のヒーローアカデミア"]);
+    }
+
+    #[test]
+    fn test_pick_display_title_prefers_language() {
+        let map = |entries: &[(&str, &str)]| -> HashMap<String, String> {
+            entries.iter().map(|(l, t)| (l.to_string(), t.to_string())).collect()
+        };
+        let title = map(&[("en", "Attack on Titan")]);
+        let alts = vec![map(&[("ja", "進撃の巨人")]), map(&[("ja-ro", "Shingeki no Kyojin")])];
+
+        assert_eq!(pick_display_title(&title, &alts, None), "Attack on Titan");
+        assert_eq!(pick_display_title(&title, &alts, Some("ja-ro")), "Shingeki no Kyojin");
+        assert_eq!(pick_display_title(&title, &alts, Some("ja")), "進撃の巨人");
+        // No title in the preferred language falls back to English
+        assert_eq!(pick_display_title(&title, &alts, Some("fr")), "Attack on Titan");
+        assert_eq!(pick_display_title(&map(&[("ja-ro", "Berserk")]), &[], Some("fr")), "Berserk");
+    }
+
+    #[test]
+    fn test_parse_manga_tags() {
+        let body = r#"{"data": [{"id": "abc", "relationships": [], "attributes": {
+            "title": {"en": "Title"},
+            "tags": [
+                {"id": "t1", "attributes": {"name": {"en": "Mystery"}}},
+                {"id": "t2", "attributes": {"name": {"ja": "日常"}}}
+            ]
+        }}]}"#;
+        let manga = parse_manga_list(parse_json::<MangaResponse>(body).unwrap());
+        let names: Vec<&str> = manga[0].tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Mystery", "日常"]);
+        assert_eq!(manga[0].tags[0].id, "t1");
+    }
+
     // #[tokio::test]
     // async fn test_api_structure() {
     //     use serde_json::Value;
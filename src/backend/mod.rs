@@ -1,3 +1,13 @@
+pub mod account;
 pub mod bookmarks;
 pub mod cache;
-pub mod mangadex;
\ No newline at end of file
+pub mod cbz;
+pub mod config;
+pub mod downloads;
+pub mod http_cache;
+pub mod local;
+pub mod log_file;
+pub mod mangadex;
+pub mod progress;
+pub mod recency;
+pub mod source;
@@ -0,0 +1,450 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use super::bookmarks::BookmarkedManga;
+use super::config::app_data_dir;
+use super::mangadex::{Chapter, Manga};
+use super::recency::Recency;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastRead {
+    pub manga: BookmarkedManga,
+    pub chapter_id: String,
+    pub page: usize,
+}
+
+/// Where the chapter grid was left when a details page closed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridPosition {
+    pub chapter_id: String,
+    pub scroll_row: usize,
+}
+
+/// How the reader sizes pages, cycled with `f` and remembered across launches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageFit {
+    /// The whole page fits in the window
+    #[default]
+    Whole,
+    /// Fills the window's width; Up/Down scroll
+    Width,
+    /// Fills the window's height; Up/Down scroll wide pages sideways
+    Height,
+}
+
+impl PageFit {
+    pub fn next(self) -> Self {
+        match self {
+            PageFit::Whole => PageFit::Width,
+            PageFit::Width => PageFit::Height,
+            PageFit::Height => PageFit::Whole,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PageFit::Whole => "fit page",
+            PageFit::Width => "fit width",
+            PageFit::Height => "fit height",
+        }
+    }
+}
+
+/// Chapters read in one series, for the stats view's most-read list.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SeriesStats {
+    pub title: String,
+    pub chapters_read: u64,
+}
+
+/// Lifetime reading counters, updated as the reader moves through pages.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReadingStats {
+    /// Page turns in the reader
+    #[serde(default)]
+    pub pages_read: u64,
+    /// Time spent on pages, with each page's share capped so idle time isn't counted
+    #[serde(default)]
+    pub reading_secs: u64,
+    /// By manga ID
+    #[serde(default)]
+    pub series: HashMap<String, SeriesStats>,
+}
+
+/// What the stats view shows and `e` exports.
+#[derive(Debug, Serialize)]
+pub struct StatsSummary {
+    pub chapters_read: usize,
+    pub pages_read: u64,
+    pub manga_completed: usize,
+    pub reading_secs: u64,
+    pub most_read: Vec<SeriesStats>,
+}
+
+/// Series listed under "most read"
+const MOST_READ_COUNT: usize = 5;
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ProgressStore {
+    #[serde(default)]
+    pub read_chapters: HashSet<String>,
+    /// Manga whose every readable chapter was read, as of the last time their chapters loaded
+    #[serde(default)]
+    pub completed_manga: HashSet<String>,
+    #[serde(default)]
+    pub last_read: Option<LastRead>,
+    /// Last page viewed in each chapter, by chapter ID
+    #[serde(default)]
+    pub chapter_pages: HashMap<String, usize>,
+    /// Manga read with inverted page colors
+    #[serde(default)]
+    pub inverted_manga: HashSet<String>,
+    /// Latest position in each series, most recently read first
+    #[serde(default)]
+    pub in_progress: Vec<LastRead>,
+    /// Page sizing last chosen in the reader
+    #[serde(default)]
+    pub reader_fit: PageFit,
+    #[serde(default)]
+    pub stats: ReadingStats,
+    /// Chapter grid selection and scroll on each manga's details page, by manga ID
+    #[serde(default)]
+    pub grid_positions: HashMap<String, GridPosition>,
+    /// Order `grid_positions` were last saved in, for dropping the oldest
+    #[serde(default)]
+    pub grid_order: Recency,
+    /// Chapter IDs shown on each bookmarked manga's details page so far, by manga ID
+    #[serde(default)]
+    pub seen_chapters: HashMap<String, HashSet<String>>,
+    /// Order `seen_chapters` were last updated in, for dropping the oldest
+    #[serde(default)]
+    pub seen_order: Recency,
+    /// When the first position not yet on disk was recorded
+    #[serde(skip)]
+    unsaved_since: Option<Instant>,
+}
+
+/// Series remembered for the home screen's Continue Reading row
+const MAX_IN_PROGRESS: usize = 30;
+/// Manga whose chapter grid position is remembered
+const MAX_GRID_POSITIONS: usize = 200;
+/// Manga with a snapshot of seen chapters for the NEW badges
+const MAX_SEEN_MANGA: usize = 200;
+/// How long page turns can go unsaved, so paging through a chapter doesn't rewrite
+/// progress.json on every turn
+const POSITION_SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Whether a chapter counts as read: the reader reached `ratio` of its pages, or moved
+/// on to the next chapter from it.
+pub fn chapter_finished(page: usize, page_count: usize, ratio: f32, moved_on: bool) -> bool {
+    moved_on || (page_count > 0 && (page + 1) as f32 / page_count as f32 >= ratio.clamp(0.0, 1.0))
+}
+
+fn get_progress_path() -> PathBuf {
+    app_data_dir().join("progress.json")
+}
+
+impl ProgressStore {
+    pub fn load() -> Self {
+        let path = get_progress_path();
+
+        if path.exists()
+            && let Ok(content) = fs::read_to_string(&path)
+            && let Ok(mut progress) = serde_json::from_str::<ProgressStore>(&content)
+        {
+            // Stores written before `in_progress` existed still know the last read
+            if progress.in_progress.is_empty() {
+                progress.in_progress.extend(progress.last_read.clone());
+            }
+            return progress;
+        }
+
+        ProgressStore::default()
+    }
+
+    pub fn save(&self) {
+        let path = get_progress_path();
+        if let Ok(content) = serde_json::to_string_pretty(self) {
+            fs::write(path, content).ok();
+        }
+    }
+
+    pub fn is_read(&self, chapter_id: &str) -> bool {
+        self.read_chapters.contains(chapter_id)
+    }
+
+    /// Marks a chapter read, crediting it to `manga` in the stats the first time.
+    pub fn mark_read(&mut self, manga: &Manga, chapter_id: &str) {
+        if self.read_chapters.insert(chapter_id.to_string()) {
+            let series = self.stats.series.entry(manga.id.clone()).or_default();
+            series.title = manga.title.clone();
+            series.chapters_read += 1;
+            self.save();
+        }
+    }
+
+    /// Marks chapters of `manga_id` unread again, forgetting their saved pages and taking
+    /// them back out of the stats. Returns how many were read before.
+    pub fn mark_unread(&mut self, manga_id: &str, chapter_ids: &[String]) -> u64 {
+        let mut unmarked = 0;
+        for chapter_id in chapter_ids {
+            self.chapter_pages.remove(chapter_id);
+            if self.read_chapters.remove(chapter_id) {
+                unmarked += 1;
+            }
+        }
+        if let Some(series) = self.stats.series.get_mut(manga_id) {
+            series.chapters_read = series.chapters_read.saturating_sub(unmarked);
+        }
+        if unmarked > 0 {
+            self.completed_manga.remove(manga_id);
+        }
+        self.save();
+        unmarked
+    }
+
+    /// Adds time spent reading. Saved along with the next position.
+    pub fn add_reading_time(&mut self, secs: u64) {
+        self.stats.reading_secs += secs;
+    }
+
+    pub fn record_position(&mut self, manga: &Manga, chapter_id: &str, page: usize) {
+        let entry = LastRead {
+            manga: BookmarkedManga::from(manga),
+            chapter_id: chapter_id.to_string(),
+            page,
+        };
+        self.in_progress.retain(|e| e.manga.id != manga.id);
+        self.in_progress.insert(0, entry.clone());
+        self.in_progress.truncate(MAX_IN_PROGRESS);
+        self.last_read = Some(entry);
+        // Reopening a chapter at its saved page isn't a page turn
+        if self.chapter_pages.insert(chapter_id.to_string(), page) != Some(page) {
+            self.stats.pages_read += 1;
+        }
+        self.unsaved_since.get_or_insert_with(Instant::now);
+    }
+
+    /// Saves positions recorded more than `POSITION_SAVE_INTERVAL` ago. Called from the
+    /// event loop.
+    pub fn save_if_due(&mut self) {
+        if self.unsaved_since.is_some_and(|since| since.elapsed() >= POSITION_SAVE_INTERVAL) {
+            self.flush();
+        }
+    }
+
+    /// Saves now, including positions that were waiting for the interval.
+    pub fn flush(&mut self) {
+        self.unsaved_since = None;
+        self.save();
+    }
+
+    pub fn saved_page(&self, chapter_id: &str) -> Option<usize> {
+        self.chapter_pages.get(chapter_id).copied()
+    }
+
+    /// Series whose latest position is in a chapter that isn't finished yet, newest first.
+    pub fn continue_reading(&self) -> Vec<&LastRead> {
+        self.in_progress
+            .iter()
+            .filter(|e| !self.is_read(&e.chapter_id))
+            .collect()
+    }
+
+    pub fn is_inverted(&self, manga_id: &str) -> bool {
+        self.inverted_manga.contains(manga_id)
+    }
+
+    pub fn set_inverted(&mut self, manga_id: &str, inverted: bool) {
+        let changed = if inverted {
+            self.inverted_manga.insert(manga_id.to_string())
+        } else {
+            self.inverted_manga.remove(manga_id)
+        };
+        if changed {
+            self.save();
+        }
+    }
+
+    pub fn set_reader_fit(&mut self, fit: PageFit) {
+        if self.reader_fit != fit {
+            self.reader_fit = fit;
+            self.save();
+        }
+    }
+
+    pub fn set_grid_position(&mut self, manga_id: &str, position: GridPosition) {
+        self.grid_positions.insert(manga_id.to_string(), position);
+        self.grid_order.touch(manga_id);
+        self.grid_order.evict(&mut self.grid_positions, MAX_GRID_POSITIONS, |_| false);
+        self.save();
+    }
+
+    /// Where to put the chapter grid cursor when a manga opens: where it was left, or
+    /// else the chapter last read. The scroll row only applies to the first.
+    pub fn grid_anchor(&self, manga_id: &str) -> Option<(String, Option<usize>)> {
+        if let Some(position) = self.grid_positions.get(manga_id) {
+            return Some((position.chapter_id.clone(), Some(position.scroll_row)));
+        }
+        self.in_progress
+            .iter()
+            .find(|e| e.manga.id == manga_id)
+            .map(|e| (e.chapter_id.clone(), None))
+    }
+
+    /// IDs of `chapters` that were never shown on the manga's details page before. A
+    /// manga without a snapshot yet has nothing to compare against, so none are new.
+    pub fn unseen_chapters(&self, manga_id: &str, chapters: &[Chapter]) -> HashSet<String> {
+        let Some(seen) = self.seen_chapters.get(manga_id) else {
+            return HashSet::new();
+        };
+        chapters
+            .iter()
+            .filter(|c| !seen.contains(&c.id))
+            .map(|c| c.id.clone())
+            .collect()
+    }
+
+    /// Adds `chapters` to what's been seen of a manga. Chapters are only ever added, so
+    /// ones hidden by a language or group filter for a while aren't new when they return.
+    pub fn mark_chapters_seen(&mut self, manga_id: &str, chapters: &[Chapter]) {
+        let seen = self.seen_chapters.entry(manga_id.to_string()).or_default();
+        let before = seen.len();
+        seen.extend(chapters.iter().map(|c| c.id.clone()));
+        let added = seen.len() != before;
+
+        self.seen_order.touch(manga_id);
+        self.seen_order.evict(&mut self.seen_chapters, MAX_SEEN_MANGA, |_| false);
+        if added {
+            self.save();
+        }
+    }
+
+    pub fn is_completed(&self, manga_id: &str) -> bool {
+        self.completed_manga.contains(manga_id)
+    }
+
+    pub fn stats_summary(&self) -> StatsSummary {
+        let mut most_read: Vec<SeriesStats> = self.stats.series.values().cloned().collect();
+        most_read.sort_by(|a, b| b.chapters_read.cmp(&a.chapters_read).then_with(|| a.title.cmp(&b.title)));
+        most_read.truncate(MOST_READ_COUNT);
+
+        StatsSummary {
+            chapters_read: self.read_chapters.len(),
+            pages_read: self.stats.pages_read,
+            manga_completed: self.completed_manga.len(),
+            reading_secs: self.stats.reading_secs,
+            most_read,
+        }
+    }
+
+    /// Writes the stats summary to `reading-stats.json` in the data directory.
+    pub fn export_stats(&self) -> io::Result<PathBuf> {
+        let path = app_data_dir().join("reading-stats.json");
+        let content = serde_json::to_string_pretty(&self.stats_summary())?;
+        fs::write(&path, content)?;
+        Ok(path)
+    }
+
+    /// Recomputes whether every non-external chapter of a manga has been read.
+    pub fn update_completion(&mut self, manga_id: &str, chapters: &[Chapter]) {
+        let mut readable = chapters.iter().filter(|c| c.external_url.is_none()).peekable();
+        let completed = readable.peek().is_some() && readable.all(|c| self.is_read(&c.id));
+
+        let changed = if completed {
+            self.completed_manga.insert(manga_id.to_string())
+        } else {
+            self.completed_manga.remove(manga_id)
+        };
+
+        if changed {
+            self.save();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_read_series_first() {
+        let mut progress = ProgressStore::default();
+        for (id, title, chapters) in [("a", "Alpha", 2), ("b", "Beta", 7), ("c", "Gamma", 2)] {
+            progress.stats.series.insert(
+                id.to_string(),
+                SeriesStats { title: title.to_string(), chapters_read: chapters },
+            );
+        }
+
+        let titles: Vec<String> = progress.stats_summary().most_read.into_iter().map(|s| s.title).collect();
+        assert_eq!(titles, ["Beta", "Alpha", "Gamma"]);
+    }
+
+    #[test]
+    fn test_chapter_finished_at_ratio_or_next_chapter() {
+        assert!(!chapter_finished(8, 10, 1.0, false));
+        assert!(chapter_finished(9, 10, 1.0, false));
+        assert!(chapter_finished(8, 10, 0.9, false));
+        assert!(!chapter_finished(0, 0, 0.0, false));
+        // Skipping ahead with `n` finishes the chapter wherever the reader was
+        assert!(chapter_finished(2, 10, 1.0, true));
+        assert!(chapter_finished(0, 0, 1.0, true));
+    }
+
+    #[test]
+    fn test_unseen_chapters_compare_against_the_snapshot() {
+        let chapter = |id: &str| Chapter {
+            id: id.to_string(),
+            chapter: String::new(),
+            title: String::new(),
+            volume: None,
+            pages: 1,
+            external_url: None,
+            groups: vec![],
+            language: String::new(),
+            other_language: false,
+        };
+        let mut progress = ProgressStore::default();
+        let chapters = vec![chapter("a"), chapter("b"), chapter("c")];
+        assert!(progress.unseen_chapters("m", &chapters).is_empty());
+
+        // "b" was filtered out on the last visit but had been seen before that
+        progress.seen_chapters.insert("m".to_string(), HashSet::from(["a".to_string(), "b".to_string()]));
+        assert_eq!(progress.unseen_chapters("m", &chapters), HashSet::from(["c".to_string()]));
+    }
+
+    #[test]
+    fn test_page_turns_wait_for_the_save_interval() {
+        let mut progress = ProgressStore::default();
+        let manga = Manga {
+            id: "m".to_string(),
+            title: "Manga".to_string(),
+            alt_titles: Vec::new(),
+            author: String::new(),
+            artist: String::new(),
+            status: String::new(),
+            description: String::new(),
+            cover_url: String::new(),
+            latest_chapter_id: None,
+            content_rating: String::new(),
+            tags: Vec::new(),
+        };
+
+        progress.record_position(&manga, "c1", 0);
+        let first = progress.unsaved_since.unwrap();
+        progress.record_position(&manga, "c1", 1);
+        progress.record_position(&manga, "c1", 2);
+        // The interval runs from the oldest unsaved turn, so steady paging still saves
+        assert_eq!(progress.unsaved_since, Some(first));
+        progress.save_if_due();
+        assert!(progress.unsaved_since.is_some());
+        assert_eq!(progress.saved_page("c1"), Some(2));
+        assert_eq!(progress.stats.pages_read, 3);
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Keys of a per-manga map in the order they were last updated, oldest first, so the map
+/// can be kept to a fixed size by dropping what hasn't been touched the longest.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Recency(Vec<String>);
+
+impl Recency {
+    /// Marks `key` as the most recently updated.
+    pub fn touch(&mut self, key: &str) {
+        self.0.retain(|k| k != key);
+        self.0.push(key.to_string());
+    }
+
+    /// Removes entries from `map` until it holds at most `cap`, oldest first. Keys that
+    /// predate the ordering count as the oldest. Entries `keep` asks for are never removed.
+    pub fn evict<V>(&mut self, map: &mut HashMap<String, V>, cap: usize, keep: impl Fn(&str) -> bool) {
+        self.0.retain(|k| map.contains_key(k));
+        let untracked: Vec<String> = map.keys().filter(|k| !self.0.contains(k)).cloned().collect();
+        let candidates: Vec<String> = untracked
+            .into_iter()
+            .chain(self.0.iter().cloned())
+            .filter(|k| !keep(k))
+            .collect();
+
+        for key in candidates {
+            if map.len() <= cap {
+                break;
+            }
+            map.remove(&key);
+            self.0.retain(|k| *k != key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evicts_least_recently_touched() {
+        let mut map: HashMap<String, u32> = ["old", "a", "b", "c"].iter().map(|k| (k.to_string(), 0)).collect();
+        let mut order = Recency::default();
+        for key in ["a", "b", "c", "a"] {
+            order.touch(key);
+        }
+
+        // "old" has no place in the ordering, then "b" is the least recent; "c" is kept
+        order.evict(&mut map, 2, |k| k == "c");
+        let mut left: Vec<&String> = map.keys().collect();
+        left.sort();
+        assert_eq!(left, ["a", "c"]);
+        assert_eq!(order.0, ["c", "a"]);
+
+        order.evict(&mut map, 0, |k| k == "c");
+        assert_eq!(map.keys().collect::<Vec<_>>(), ["c"]);
+    }
+}
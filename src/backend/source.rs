@@ -0,0 +1,65 @@
+use futures::future::BoxFuture;
+use image::DynamicImage;
+
+use super::local::{self, LocalSource};
+use super::mangadex::{ApiError, Chapter, FeedOptions, Manga, MangaDex, SearchOrder};
+
+/// Somewhere manga can be browsed and read from.
+///
+/// Methods return boxed futures so sources can be used as trait objects. IDs and cover
+/// URLs are opaque strings owned by the source that produced them; `for_id` maps one back
+/// to its source.
+pub trait Source: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn popular(&self, limit: usize) -> BoxFuture<'_, Result<Vec<Manga>, ApiError>>;
+
+    fn recent(&self, limit: usize) -> BoxFuture<'_, Result<Vec<Manga>, ApiError>>;
+
+    /// Manga whose title matches `query`, limited to those tagged `tag_id` if given.
+    fn search<'a>(
+        &'a self,
+        query: &'a str,
+        order: SearchOrder,
+        tag_id: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Vec<Manga>, ApiError>>;
+
+    fn chapters<'a>(
+        &'a self,
+        manga_id: &'a str,
+        options: &'a FeedOptions,
+    ) -> BoxFuture<'a, Result<Vec<Chapter>, ApiError>>;
+
+    /// Page URLs of a chapter in reading order, or `None` if they couldn't be fetched.
+    fn chapter_pages<'a>(&'a self, chapter_id: &'a str) -> BoxFuture<'a, Option<Vec<String>>>;
+
+    fn cover<'a>(&'a self, cover_url: &'a str) -> BoxFuture<'a, Option<DynamicImage>>;
+
+    /// Web page for a manga, if the source has one.
+    fn manga_url(&self, _manga_id: &str) -> Option<String> {
+        None
+    }
+
+    /// Web page for a chapter, if the source has one.
+    fn chapter_url(&self, _chapter_id: &str) -> Option<String> {
+        None
+    }
+}
+
+static MANGADEX: MangaDex = MangaDex;
+static LOCAL: LocalSource = LocalSource::new();
+
+/// The source used for the home lists and search.
+pub fn mangadex() -> &'static dyn Source {
+    &MANGADEX
+}
+
+/// The local library source. Its root is set once at startup with `LocalSource::init_root`.
+pub fn local() -> &'static LocalSource {
+    &LOCAL
+}
+
+/// The source a manga, chapter, page or cover ID belongs to.
+pub fn for_id(id: &str) -> &'static dyn Source {
+    if local::is_local(id) { &LOCAL } else { &MANGADEX }
+}
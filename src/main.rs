@@ -1,49 +1,111 @@
 mod backend;
+mod shutdown;
 mod ui;
 
-use backend::cache::PageCache;
+use backend::account::{self, SyncResult};
+use backend::bookmarks::Bookmarks;
+use backend::cache::{PageCache, MAX_MEMORY_PAGES};
+use backend::config::{app_data_dir, get_config_path, Config, StartupView};
+use backend::downloads::{ActiveDownload, DownloadChapter, PendingDownload};
+use backend::http_cache;
+use backend::log_file::{self, RotatingLog};
+use backend::local;
 use backend::mangadex::{
-    fetch_cover_image, fetch_page_image, get_chapter_pages, get_manga_chapters,
-    get_popular_now, get_recently_updated, search_manga, Manga,
+    bytes_downloaded, fetch_page_image, fetch_cover_image_now, get_manga_by_ids, init_fetch_limit, init_http_client, init_title_language,
+    rate_limit_hits, rate_limit_pause, set_verbose_network, ApiError, FeedOptions, Manga, RetryPolicy, SearchOrder,
 };
+use backend::source::{self, Source};
+use backend::progress::{LastRead, PageFit};
 use image::DynamicImage;
-use ui::ui::{App, Focus, Tab, View, ui};
+use ui::chapter_grid::GridMove;
+use ui::palette::{Command, CommandPalette};
+use ui::ui::{
+    format_bytes, App, AppState, BatchDownload, ChapterPrefetch, Focus, PageScroll, ReaderState, Tab, View,
+    MAX_COVER_PROTOCOLS, ui,
+};
 
 use crossterm::{
-    event::{Event, EventStream, KeyCode},
+    cursor,
+    event::{Event, EventStream, KeyCode, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use futures::StreamExt;
 use ratatui::{backend::CrosstermBackend, Terminal};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
 use std::{error::Error, io};
 use tokio::sync::mpsc;
 
 enum BackgroundTask {
-    CoverLoaded { manga_id: String, image: DynamicImage },
-    ChaptersLoaded { chapters: Vec<backend::mangadex::Chapter> },
+    HomeLoaded {
+        recent: Result<Vec<Manga>, ApiError>,
+        popular: Result<Vec<Manga>, ApiError>,
+    },
+    CoverLoaded { manga_id: String, image: DynamicImage, explicit: bool, pornographic: bool },
+    CoverLoadFailed { manga_id: String },
+    ChaptersLoaded { manga_id: String, chapters: Vec<backend::mangadex::Chapter> },
+    ChaptersLoadFailed { manga_id: String },
     ChapterThumbnailLoaded { chapter_id: String, image: DynamicImage },
     PageUrlsLoaded { urls: Vec<String> },
     PageUrlsLoadFailed,
+    /// The server answered but listed no page files for the chapter
+    ChapterHasNoPages,
     PageImageLoaded { image: DynamicImage },
-    PageImageLoadFailed,
-    PagePreloaded { page_url: String },
+    PageImageLoadFailed { page_url: String },
+    /// A page download failed and is about to be tried again
+    PageImageRetrying { page_url: String, retry: u32, retries: u32 },
+    /// A preload finished; `ok` is false if the page couldn't be fetched
+    PagePreloaded { page_url: String, ok: bool },
+    /// Pages of the open chapter found in the page cache
+    PagesCached { page_urls: Vec<String> },
+    PageThumbnailLoaded { page_url: String, image: DynamicImage },
+    /// A page of a prefetching chapter was checked, and is now cached unless the fetch failed
+    ChapterPrefetchProgress { chapter_id: String, page_url: String, cached: bool },
+    /// Chapters finished by a batch download, and how many of those couldn't be fetched
+    BatchDownloadProgress { done: usize, failed: usize },
     SearchResults { results: Vec<Manga> },
+    BookmarksRefreshed { mangas: Vec<Manga> },
+    LocalLibraryLoaded { mangas: Vec<Manga> },
+    /// A startup sync with `username`'s MangaDex account finished; `sent` is the pending
+    /// changes it had
+    MangaDexSynced { username: String, result: Result<SyncResult, ApiError>, sent: HashMap<String, bool> },
+    BookmarkPushed { username: String, manga_id: String, bookmarked: bool, ok: bool },
+    /// A reading status cycled on a card couldn't be sent to the MangaDex account
+    StatusPushFailed,
+    /// The page cache was emptied, freeing this many bytes on disk
+    CacheCleared { freed: u64 },
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    env_logger::init_from_env(env_logger::Env::default().default_filter_or("info"));
+    let config = Config::load();
+    // Before logging starts, since the data directory decides where the log file goes
+    let mut startup_warnings = config.init_dirs();
+    let log_file = init_logging(config.log_to_file || std::env::var_os("TACHIYOMI_LOG_FILE").is_some());
     log::debug!("Starting manga reader...");
+    let mut interrupt = shutdown::listen_for_interrupt();
 
     enable_raw_mode()?;
+    let restore = shutdown::OnDrop::new(restore_terminal);
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new();
-    let cache = PageCache::new();
+    let cache = PageCache::new(config.page_cache_format, config.page_cache_jpeg_quality);
+    // Shown together, since a second toast would replace the first
+    startup_warnings.extend(cache.disk_error().map(str::to_string));
+    let mut app = App::new(config, startup_warnings);
+    init_fetch_limit(app.config.max_concurrent_fetches);
+    init_title_language(app.config.display_title_language.clone());
+    init_http_client(app.config.request_timeout_secs, app.config.connect_timeout_secs);
+    set_verbose_network(
+        app.config.verbose_network || std::env::var_os("TACHIYOMI_VERBOSE_NET").is_some(),
+    );
 
     // Create channel for background tasks
     let (task_tx, mut task_rx) = mpsc::unbounded_channel::<BackgroundTask>();
@@ -52,39 +114,181 @@ async fn main() -> Result<(), Box<dyn Error>> {
     app.set_loading("Connecting to MangaDex...");
     terminal.draw(|f| ui(f, &mut app))?;
 
-    // Fetch manga data
-    app.set_loading("Fetching recently updated manga...");
-    terminal.draw(|f| ui(f, &mut app))?;
+    let Some(fetched) = shutdown::until_interrupted(fetch_home_lists(&mut terminal, &mut app), &mut interrupt).await
+    else {
+        return Ok(());
+    };
+    let (recent_manga, popular_manga) = fetched?;
 
-    let recent_manga = get_recently_updated().await.unwrap_or_default();
+    // Track which manga IDs are already loading
+    let mut pending_covers = std::collections::HashSet::new();
 
-    app.set_loading("Fetching popular manga...");
-    terminal.draw(|f| ui(f, &mut app))?;
+    // Store manga data and switch to ready (or offline) state
+    if store_home_results(&mut app, recent_manga, popular_manga) {
+        // Spawn background tasks to load initial covers
+        spawn_home_covers(&app, &mut pending_covers, &task_tx);
+        finish_startup(&mut app, &task_tx);
+    }
+    // The local library doesn't need the network, so scan it even when offline
+    if let Some(root) = app.config.local_library_dir() {
+        source::local().init_root(root);
+        app.local_scanning = true;
+        spawn_local_library_scan(task_tx.clone());
+    }
+
+    let res = run_app(
+        &mut terminal,
+        &mut app,
+        &mut task_rx,
+        task_tx,
+        cache.clone(),
+        pending_covers,
+        &mut interrupt,
+    )
+    .await;
+
+    drop(restore);
+
+    match res {
+        Ok(()) => print_exit_summary(&app, &cache),
+        Err(err) => {
+            // Positions still waiting for the save interval
+            app.progress.flush();
+            eprintln!("{err}");
+        }
+    }
+    if let Some(path) = log_file {
+        println!("Log written to {}", path.display());
+    }
+    Ok(())
+}
+
+/// Leaves raw mode and the alternate screen and shows the cursor again.
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, cursor::Show);
+}
 
-    let popular_manga = get_popular_now().await.unwrap_or_default();
+/// Fetches the home screen's browse lists, showing which one is loading.
+async fn fetch_home_lists(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+) -> io::Result<(Result<Vec<Manga>, ApiError>, Result<Vec<Manga>, ApiError>)> {
+    app.set_loading(&format!("Fetching recently updated manga from {}...", app.source.name()));
+    terminal.draw(|f| ui(f, app))?;
+
+    let recent_manga = app.source.recent(app.config.browse_limit).await;
+
+    app.set_loading(&format!("Fetching popular manga from {}...", app.source.name()));
+    terminal.draw(|f| ui(f, app))?;
 
-    // Store manga data
-    app.recently_updated = recent_manga;
-    app.popular_now = popular_manga;
+    let popular_manga = app.source.popular(app.config.browse_limit).await;
+    Ok((recent_manga, popular_manga))
+}
 
-    // Spawn background tasks to load initial covers
-    spawn_cover_loaders(&app.recently_updated, 0, 6, task_tx.clone());
-    spawn_cover_loaders(&app.popular_now, 0, 6, task_tx.clone());
+/// Logs to stderr, or to the rotating log file in the data directory when `to_file` is
+/// set, since stderr can't be seen behind the alternate screen. Returns the file's path.
+fn init_logging(to_file: bool) -> Option<PathBuf> {
+    let mut builder = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"));
+    let mut log_file = None;
+    if to_file {
+        let path = log_file::log_path();
+        match RotatingLog::open(&path, log_file::MAX_LOG_BYTES) {
+            Ok(log) => {
+                builder.target(env_logger::Target::Pipe(Box::new(log)));
+                log_file = Some(path);
+            }
+            Err(e) => eprintln!("Couldn't open log file {}: {}", path.display(), e),
+        }
+    }
+    builder.init();
+    log_file
+}
 
-    // Data loaded, switch to ready state
-    app.set_ready();
+/// A few lines of session stats, after making sure progress and bookmarks are on disk.
+fn print_exit_summary(app: &App, cache: &PageCache) {
+    app.progress.save();
+    app.bookmarks.save();
 
-    let res = run_app(&mut terminal, &mut app, &mut task_rx, task_tx, cache).await;
+    println!(
+        "Session: {} pages cached, {} downloaded",
+        cache.pages_saved_this_session(),
+        format_bytes(bytes_downloaded())
+    );
+    println!(
+        "Library: {} bookmarks, {} chapters read; progress saved to {}",
+        app.bookmarks.manga_ids.len(),
+        app.progress.read_chapters.len(),
+        app_data_dir().display()
+    );
+}
 
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+/// Stores the browse lists, or switches to the offline screen if MangaDex couldn't be
+/// reached at all. Returns true if the app is ready.
+fn store_home_results(
+    app: &mut App,
+    recent: Result<Vec<Manga>, ApiError>,
+    popular: Result<Vec<Manga>, ApiError>,
+) -> bool {
+    match (recent, popular) {
+        (Err(recent_err), Err(popular_err)) => {
+            log::error!("Failed to reach MangaDex: {}; {}", recent_err, popular_err);
+            app.set_offline(&recent_err.to_string());
+            false
+        }
+        (recent, popular) => {
+            app.recently_updated = recent.unwrap_or_default();
+            app.popular_now = popular.unwrap_or_default();
+            app.set_ready();
+            true
+        }
+    }
+}
 
-    if let Err(err) = res {
-        eprintln!("{err}");
+/// Starts what waits for MangaDex to be reachable, the first time the home lists load:
+/// at launch, or after retrying from the offline screen.
+fn finish_startup(app: &mut App, task_tx: &mpsc::UnboundedSender<BackgroundTask>) {
+    if std::mem::replace(&mut app.started, true) {
+        return;
     }
-    Ok(())
+    apply_startup_view(app, task_tx);
+    if app.bookmarks.update_check_due() {
+        refresh_bookmarks(app, task_tx);
+    }
+    spawn_mangadex_sync(app, task_tx.clone());
+    app.resume_download_prompt = PendingDownload::load();
+}
+
+fn apply_startup_view(app: &mut App, task_tx: &mpsc::UnboundedSender<BackgroundTask>) {
+    match app.config.startup_view {
+        StartupView::Home => {}
+        StartupView::Bookmarks => app.tab = Tab::Bookmarks,
+        StartupView::Resume => {
+            if let Some(last) = app.progress.last_read.clone() {
+                resume_reading(app, last, task_tx);
+            }
+        }
+    }
+}
+
+/// Opens a manga and drops straight into the reader at a saved position once its
+/// chapters have loaded.
+fn resume_reading(app: &mut App, entry: LastRead, task_tx: &mpsc::UnboundedSender<BackgroundTask>) {
+    let manga = Manga::from(&entry.manga);
+    if !app.image_states.contains_key(&manga.id) {
+        spawn_cover_loaders(std::slice::from_ref(&manga), 0, 1, task_tx.clone());
+    }
+    spawn_chapters_loader(manga.id.clone(), app.config.feed_options(), task_tx.clone());
+    app.open_manga(manga);
+    app.pending_resume = Some((entry.chapter_id, entry.page));
+}
+
+fn spawn_home_loader(source: &'static dyn Source, limit: usize, tx: mpsc::UnboundedSender<BackgroundTask>) {
+    tokio::spawn(async move {
+        let recent = source.recent(limit).await;
+        let popular = source.popular(limit).await;
+        let _ = tx.send(BackgroundTask::HomeLoaded { recent, popular });
+    });
 }
 
 fn spawn_cover_loaders(
@@ -96,21 +300,111 @@ fn spawn_cover_loaders(
     for manga in mangas.iter().skip(start).take(count) {
         let manga_id = manga.id.clone();
         let cover_url = manga.cover_url.clone();
+        let explicit = manga.is_explicit();
+        let pornographic = manga.is_pornographic();
         let tx = tx.clone();
 
         tokio::spawn(async move {
-            if let Some(image) = fetch_cover_image(&cover_url).await {
-                let _ = tx.send(BackgroundTask::CoverLoaded { manga_id, image });
+            match load_cover(&cover_url).await {
+                Some(image) => {
+                    let _ = tx.send(BackgroundTask::CoverLoaded { manga_id, image, explicit, pornographic });
+                }
+                None => {
+                    let _ = tx.send(BackgroundTask::CoverLoadFailed { manga_id });
+                }
             }
         });
     }
 }
 
-fn spawn_chapters_loader(manga_id: String, tx: mpsc::UnboundedSender<BackgroundTask>) {
+/// Loads the first covers of each home row. A manga that shows up in more than one row,
+/// or whose cover is already loaded, is only fetched once. Eager loading stops once the
+/// rows fill the cover cache, since covers past that would only evict earlier ones.
+fn spawn_home_covers(
+    app: &App,
+    pending: &mut std::collections::HashSet<String>,
+    tx: &mpsc::UnboundedSender<BackgroundTask>,
+) {
+    let initial_covers = app.config.initial_covers();
+    let continuing = app.continue_reading_manga();
+    let mut budget = MAX_COVER_PROTOCOLS;
+    for row in [&continuing, &app.recently_updated, &app.popular_now] {
+        let count = initial_covers.min(budget);
+        preload_covers(row, 0, (count, 0), pending, &app.image_states, tx.clone());
+        budget -= count.min(row.len());
+    }
+}
+
+async fn load_cover(cover_url: &str) -> Option<DynamicImage> {
+    source::for_id(cover_url).cover(cover_url).await
+}
+
+/// Fetches the focused card's cover straight away when it isn't loaded or on the way,
+/// such as after switching tabs or rows.
+fn fetch_focused_cover(
+    app: &App,
+    pending: &mut std::collections::HashSet<String>,
+    tx: &mpsc::UnboundedSender<BackgroundTask>,
+) {
+    let Some(manga) = app.focused_card() else {
+        return;
+    };
+    if app.image_states.contains_key(&manga.id) || !pending.insert(manga.id.clone()) {
+        return;
+    }
+    spawn_cover_load(&manga, true, tx.clone());
+}
+
+/// Loads one cover in the background. A `priority` load goes ahead of requests still
+/// waiting for the fetch limit, for the card the user is looking at.
+fn spawn_cover_load(manga: &Manga, priority: bool, tx: mpsc::UnboundedSender<BackgroundTask>) {
+    let manga_id = manga.id.clone();
+    let cover_url = manga.cover_url.clone();
+    let explicit = manga.is_explicit();
+    let pornographic = manga.is_pornographic();
+
+    tokio::spawn(async move {
+        // Local covers are read from disk, so only MangaDex ones can skip the queue
+        let image = if priority && !local::is_local(&cover_url) {
+            fetch_cover_image_now(&cover_url).await
+        } else {
+            load_cover(&cover_url).await
+        };
+        let _ = tx.send(match image {
+            Some(image) => BackgroundTask::CoverLoaded { manga_id, image, explicit, pornographic },
+            None => BackgroundTask::CoverLoadFailed { manga_id },
+        });
+    });
+}
+
+fn spawn_chapters_loader(
+    manga_id: String,
+    options: FeedOptions,
+    tx: mpsc::UnboundedSender<BackgroundTask>,
+) {
     tokio::spawn(async move {
-        if let Ok(chapters) = get_manga_chapters(&manga_id).await {
-            let _ = tx.send(BackgroundTask::ChaptersLoaded { chapters });
-        }
+        let task = match source::for_id(&manga_id).chapters(&manga_id, &options).await {
+            Ok(chapters) => BackgroundTask::ChaptersLoaded { manga_id, chapters },
+            Err(_) => BackgroundTask::ChaptersLoadFailed { manga_id },
+        };
+        let _ = tx.send(task);
+    });
+}
+
+async fn chapter_page_urls(chapter_id: &str) -> Option<Vec<String>> {
+    source::for_id(chapter_id).chapter_pages(chapter_id).await
+}
+
+/// A chapter's page list through the cache, which shares one request between everything
+/// asking for the same chapter at once and refetches lists old enough to have expired.
+async fn cached_page_urls(chapter_id: &str, cache: &PageCache) -> Option<Vec<String>> {
+    cache.chapter_urls(chapter_id, chapter_page_urls(chapter_id)).await
+}
+
+fn spawn_local_library_scan(tx: mpsc::UnboundedSender<BackgroundTask>) {
+    tokio::spawn(async move {
+        let mangas = source::local().popular(0).await.unwrap_or_default();
+        let _ = tx.send(BackgroundTask::LocalLibraryLoaded { mangas });
     });
 }
 
@@ -128,6 +422,7 @@ fn spawn_chapter_thumbnail_loader(
 
 fn spawn_chapter_thumbnails_preloader(
     chapters: Vec<backend::mangadex::Chapter>,
+    delay_ms: u64,
     tx: mpsc::UnboundedSender<BackgroundTask>,
     cache: PageCache,
 ) {
@@ -138,7 +433,7 @@ fn spawn_chapter_thumbnails_preloader(
             }
             
             // Small delay between requests to avoid rate limiting
-            tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+            tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
             
             if let Some(image) = load_chapter_thumbnail(&chapter.id, &cache).await {
                 let _ = tx.send(BackgroundTask::ChapterThumbnailLoaded { 
@@ -151,24 +446,8 @@ fn spawn_chapter_thumbnails_preloader(
 }
 
 async fn load_chapter_thumbnail(chapter_id: &str, cache: &PageCache) -> Option<DynamicImage> {
-    // Check if we have cached URLs for this chapter
-    if let Some(urls) = cache.get_chapter_urls(chapter_id).await {
-        if let Some(first_url) = urls.first() {
-            return fetch_first_page_thumbnail(first_url, cache).await;
-        }
-    }
-
-    // Fetch URLs from API
-    if let Some(urls) = get_chapter_pages(chapter_id).await {
-        if !urls.is_empty() {
-            cache.insert_chapter_urls(chapter_id.to_string(), urls.clone()).await;
-            if let Some(first_url) = urls.first() {
-                return fetch_first_page_thumbnail(first_url, cache).await;
-            }
-        }
-    }
-    
-    None
+    let urls = cached_page_urls(chapter_id, cache).await?;
+    fetch_first_page_thumbnail(urls.first()?, cache).await
 }
 
 async fn fetch_first_page_thumbnail(page_url: &str, cache: &PageCache) -> Option<DynamicImage> {
@@ -178,8 +457,9 @@ async fn fetch_first_page_thumbnail(page_url: &str, cache: &PageCache) -> Option
     }
     
     // Fetch from network and cache
-    if let Some(image) = fetch_page_image(page_url).await {
-        cache.insert_page(page_url.to_string(), image.clone()).await;
+    if let Some(page) = fetch_page_image(page_url).await {
+        let image = page.image.clone();
+        cache.insert_page(page_url.to_string(), page).await;
         return Some(image);
     }
     
@@ -189,22 +469,14 @@ async fn fetch_first_page_thumbnail(page_url: &str, cache: &PageCache) -> Option
 fn spawn_page_urls_loader(chapter_id: String, tx: mpsc::UnboundedSender<BackgroundTask>, cache: PageCache) {
     log::debug!("Loading page URLs for chapter: {}", chapter_id);
     tokio::spawn(async move {
-        if let Some(cached_urls) = cache.get_chapter_urls(&chapter_id).await {
-            log::debug!("Found cached URLs for chapter {}: {} pages", chapter_id, cached_urls.len());
-            let _ = tx.send(BackgroundTask::PageUrlsLoaded { urls: cached_urls });
-            return;
-        }
-
-        log::debug!("Fetching page URLs from API for chapter: {}", chapter_id);
-        match get_chapter_pages(&chapter_id).await {
+        match cached_page_urls(&chapter_id, &cache).await {
             Some(urls) => {
                 if !urls.is_empty() {
                     log::debug!("Loaded {} page URLs for chapter {}", urls.len(), chapter_id);
-                    cache.insert_chapter_urls(chapter_id, urls.clone()).await;
                     let _ = tx.send(BackgroundTask::PageUrlsLoaded { urls });
                 } else {
                     log::error!("Chapter {} has empty page URLs", chapter_id);
-                    let _ = tx.send(BackgroundTask::PageUrlsLoadFailed);
+                    let _ = tx.send(BackgroundTask::ChapterHasNoPages);
                 }
             }
             None => {
@@ -215,7 +487,12 @@ fn spawn_page_urls_loader(chapter_id: String, tx: mpsc::UnboundedSender<Backgrou
     });
 }
 
-fn spawn_page_image_loader(page_url: String, tx: mpsc::UnboundedSender<BackgroundTask>, cache: PageCache) {
+fn spawn_page_image_loader(
+    page_url: String,
+    retry: RetryPolicy,
+    tx: mpsc::UnboundedSender<BackgroundTask>,
+    cache: PageCache,
+) {
     log::debug!("Loading page image: {}", page_url);
     tokio::spawn(async move {
         if let Some(cached_image) = cache.get_page(&page_url).await {
@@ -224,43 +501,470 @@ fn spawn_page_image_loader(page_url: String, tx: mpsc::UnboundedSender<Backgroun
             return;
         }
 
-        const MAX_RETRIES: u32 = 3;
-        for attempt in 0..MAX_RETRIES {
+        for attempt in 0..=retry.retries {
+            if attempt > 0 {
+                let _ = tx.send(BackgroundTask::PageImageRetrying {
+                    page_url: page_url.clone(),
+                    retry: attempt,
+                    retries: retry.retries,
+                });
+            }
             log::debug!("Attempt {} to fetch image: {}", attempt + 1, page_url);
-            if let Some(image) = fetch_page_image(&page_url).await {
+            if let Some(page) = fetch_page_image(&page_url).await {
                 log::debug!("Successfully loaded image (attempt {})", attempt + 1);
-                cache.insert_page(page_url, image.clone()).await;
+                let image = page.image.clone();
+                cache.insert_page(page_url, page).await;
                 let _ = tx.send(BackgroundTask::PageImageLoaded { image });
                 return;
             }
-            if attempt < MAX_RETRIES - 1 {
-                let delay = 500 * (attempt as u64 + 1);
-                log::warn!("Image fetch failed, retrying in {}ms", delay);
-                tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
+            if attempt < retry.retries {
+                let delay = retry.delay(attempt + 1);
+                log::warn!("Image fetch failed, retrying in {}ms", delay.as_millis());
+                tokio::time::sleep(delay).await;
             }
         }
-        log::error!("Failed to load image after {} retries: {}", MAX_RETRIES, page_url);
-        let _ = tx.send(BackgroundTask::PageImageLoadFailed);
+        log::error!("Failed to load image after {} retries: {}", retry.retries, page_url);
+        let _ = tx.send(BackgroundTask::PageImageLoadFailed { page_url });
     });
 }
 
 fn spawn_page_preloader(page_url: String, tx: mpsc::UnboundedSender<BackgroundTask>, cache: PageCache) {
     tokio::spawn(async move {
-        if cache.has_page(&page_url).await {
-            let _ = tx.send(BackgroundTask::PagePreloaded { page_url });
+        let ok = preload_page(&page_url, &cache).await;
+        let _ = tx.send(BackgroundTask::PagePreloaded { page_url, ok });
+    });
+}
+
+/// Makes sure a page is in the cache, fetching it if needed. Returns false if the fetch failed.
+async fn preload_page(page_url: &str, cache: &PageCache) -> bool {
+    if cache.has_page(page_url).await {
+        return true;
+    }
+
+    if let Some(page) = fetch_page_image(page_url).await {
+        cache.insert_page(page_url.to_string(), page).await;
+        return true;
+    }
+
+    false
+}
+
+fn spawn_chapter_prefetch(
+    chapter_id: String,
+    page_urls: Vec<String>,
+    tx: mpsc::UnboundedSender<BackgroundTask>,
+    cache: PageCache,
+) -> tokio::task::AbortHandle {
+    tokio::spawn(async move {
+        prefetch_chapter_pages(&chapter_id, &page_urls, &tx, &cache).await;
+    })
+    .abort_handle()
+}
+
+/// Fetches every uncached page of a chapter in order, reporting progress after each.
+async fn prefetch_chapter_pages(
+    chapter_id: &str,
+    page_urls: &[String],
+    tx: &mpsc::UnboundedSender<BackgroundTask>,
+    cache: &PageCache,
+) {
+    for (i, url) in page_urls.iter().enumerate() {
+        let mut cached = true;
+        if !cache.has_page(url).await {
+            // Throttle network fetches to stay under the rate limit
+            tokio::time::sleep(tokio::time::Duration::from_millis(250)).await;
+            cached = preload_page(url, cache).await;
+            if !cached {
+                log::warn!("Prefetch failed for page {} of chapter {}", i + 1, chapter_id);
+            }
+        }
+        let _ = tx.send(BackgroundTask::ChapterPrefetchProgress {
+            chapter_id: chapter_id.to_string(),
+            page_url: url.clone(),
+            cached,
+        });
+    }
+}
+
+/// Fetches every page of a chapter into its staging folder, from the cache where it has
+/// them, then zips them into the CBZ. Pages staged by an interrupted run aren't fetched
+/// again. Pages fetched here are cached too, so the chapter also opens offline in the app.
+/// Returns false if a page couldn't be fetched or the archive couldn't be written.
+async fn download_chapter_cbz(chapter: &DownloadChapter, page_urls: &[String], cache: &PageCache) -> bool {
+    if let Err(e) = tokio::fs::create_dir_all(chapter.staging_dir()).await {
+        log::warn!("Couldn't create {}: {}", chapter.staging_dir().display(), e);
+        return false;
+    }
+    for (i, url) in page_urls.iter().enumerate() {
+        let staged = chapter.staged_page(i);
+        if tokio::fs::try_exists(&staged).await.unwrap_or(false) {
+            continue;
+        }
+        let bytes = match cache.page_bytes(url).await {
+            Some(bytes) => bytes,
+            None => {
+                // Throttle network fetches to stay under the rate limit
+                tokio::time::sleep(tokio::time::Duration::from_millis(250)).await;
+                let Some(page) = fetch_page_image(url).await else {
+                    log::warn!("Download failed for page {} of chapter {}", i + 1, chapter.id);
+                    return false;
+                };
+                let bytes = page.bytes.clone();
+                cache.insert_page(url.clone(), page).await;
+                bytes
+            }
+        };
+        // Moved into place once written, so a cut-short write isn't taken as a staged page
+        let partial = staged.with_extension("part");
+        let written = match tokio::fs::write(&partial, &bytes).await {
+            Ok(()) => tokio::fs::rename(&partial, &staged).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = written {
+            log::warn!("Couldn't stage page {} of chapter {}: {}", i + 1, chapter.id, e);
+            return false;
+        }
+    }
+
+    let finished = chapter.clone();
+    let pages = page_urls.len();
+    match tokio::task::spawn_blocking(move || finished.finish(pages)).await {
+        Ok(Ok(())) => true,
+        Ok(Err(e)) => {
+            log::warn!("Couldn't write {}: {}", chapter.path.display(), e);
+            false
+        }
+        Err(_) => false,
+    }
+}
+
+/// Downloads several chapters as CBZ files, one at a time, with a pause between chapters
+/// so a long range doesn't hammer the page servers. Starts after the chapters `pending`
+/// has already finished, and records each one as it completes so the download can
+/// resume after a restart.
+fn spawn_batch_download(
+    download: ActiveDownload,
+    tx: mpsc::UnboundedSender<BackgroundTask>,
+    cache: PageCache,
+) -> tokio::task::AbortHandle {
+    download.pending.save();
+    tokio::spawn(async move {
+        let mut download = download;
+        let pending = &mut download.pending;
+        let start = pending.done;
+        for i in start..pending.total() {
+            if i > start {
+                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+            }
+            let chapter = pending.chapters[i].clone();
+            let saved = match cached_page_urls(&chapter.id, &cache).await {
+                Some(urls) if !urls.is_empty() => download_chapter_cbz(&chapter, &urls, &cache).await,
+                _ => {
+                    log::warn!("Skipping chapter {} in batch download: no page list", chapter.id);
+                    false
+                }
+            };
+            if !saved {
+                // It's counted as done, so its pages wouldn't be picked up again
+                tokio::fs::remove_dir_all(chapter.staging_dir()).await.ok();
+                pending.failed += 1;
+            }
+            pending.done = i + 1;
+            pending.save();
+            let _ = tx.send(BackgroundTask::BatchDownloadProgress {
+                done: pending.done,
+                failed: pending.failed,
+            });
+        }
+        PendingDownload::clear();
+    })
+    .abort_handle()
+}
+
+/// Starts a batch download, picking up after any chapters it already finished.
+fn start_batch_download(
+    app: &mut App,
+    pending: PendingDownload,
+    task_tx: &mpsc::UnboundedSender<BackgroundTask>,
+    cache: &PageCache,
+) {
+    let (done, total) = (pending.done, pending.total());
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let download = ActiveDownload::new(pending, cancelled.clone());
+    let handle = spawn_batch_download(download, task_tx.clone(), cache.clone());
+    app.batch_download = Some(BatchDownload {
+        done,
+        total,
+        handle,
+        cancelled,
+    });
+}
+
+/// Finds which pages of a chapter are already cached, for the reader's cache status dots.
+fn spawn_page_cache_check(page_urls: Vec<String>, tx: mpsc::UnboundedSender<BackgroundTask>, cache: PageCache) {
+    tokio::spawn(async move {
+        let mut cached = Vec::new();
+        for url in page_urls {
+            if cache.has_page(&url).await {
+                cached.push(url);
+            }
+        }
+        let _ = tx.send(BackgroundTask::PagesCached { page_urls: cached });
+    });
+}
+
+fn spawn_page_thumbnail_loader(page_url: String, tx: mpsc::UnboundedSender<BackgroundTask>, cache: PageCache) {
+    tokio::spawn(async move {
+        // Only use pages that are already cached so the strip never triggers fetches
+        if !cache.has_page(&page_url).await {
             return;
         }
+        if let Some(image) = cache.get_page(&page_url).await {
+            let image = image.thumbnail(PAGE_THUMBNAIL_SIZE.0, PAGE_THUMBNAIL_SIZE.1);
+            let _ = tx.send(BackgroundTask::PageThumbnailLoaded { page_url, image });
+        }
+    });
+}
+
+const PAGE_THUMBNAIL_SIZE: (u32, u32) = (96, 144);
+
+/// Re-fetches metadata for every bookmark, once per session.
+fn refresh_bookmarks(app: &mut App, tx: &mpsc::UnboundedSender<BackgroundTask>) {
+    app.bookmarks_refreshed = true;
+    let ids: Vec<String> = app
+        .bookmarks
+        .manga_cache
+        .iter()
+        .map(|m| m.id.clone())
+        .filter(|id| !local::is_local(id))
+        .collect();
+    if !ids.is_empty() {
+        spawn_bookmarks_refresh(ids, tx.clone());
+    }
+}
+
+/// Reconciles bookmarks with the MangaDex account when syncing is turned on.
+fn spawn_mangadex_sync(app: &App, tx: mpsc::UnboundedSender<BackgroundTask>) {
+    let Some(account) = app.config.sync_account().cloned() else {
+        return;
+    };
+    let local = app.bookmarks.syncable_ids();
+    // Everything pending is cleared once synced, even changes meant for another account
+    let sent = app.bookmarks.sync_pending.clone();
+    let (pending, last_synced) = app.bookmarks.sync_state(&account.username);
+    let statuses = app.bookmarks.statuses.clone();
+    tokio::spawn(async move {
+        let result = account::sync_bookmarks(&account, &local, &statuses, &pending, &last_synced).await;
+        let _ = tx.send(BackgroundTask::MangaDexSynced { username: account.username, result, sent });
+    });
+}
+
+fn spawn_bookmark_push(app: &App, manga_id: String, bookmarked: bool, tx: mpsc::UnboundedSender<BackgroundTask>) {
+    let Some(account) = app.config.sync_account().cloned() else {
+        return;
+    };
+    if local::is_local(&manga_id) {
+        return;
+    }
+    let status = app.bookmarks.status(&manga_id);
+    tokio::spawn(async move {
+        let ok = match account::push_bookmark(&account, &manga_id, bookmarked, status).await {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("Failed to push bookmark {} to MangaDex: {}", manga_id, e);
+                false
+            }
+        };
+        let _ = tx.send(BackgroundTask::BookmarkPushed { username: account.username, manga_id, bookmarked, ok });
+    });
+}
+
+/// Sends a status cycled on a bookmarked manga's card to the account. Clearing it isn't
+/// sent, since the account would then drop the manga from its library.
+fn spawn_status_push(app: &App, manga_id: String, tx: mpsc::UnboundedSender<BackgroundTask>) {
+    let Some(account) = app.config.sync_account().cloned() else {
+        return;
+    };
+    let Some(status) = app.bookmarks.status(&manga_id) else {
+        return;
+    };
+    if local::is_local(&manga_id) || !app.bookmarks.is_bookmarked(&manga_id) {
+        return;
+    }
+    tokio::spawn(async move {
+        if let Err(e) = account::push_status(&account, &manga_id, status).await {
+            log::warn!("Failed to push reading status of {} to MangaDex: {}", manga_id, e);
+            let _ = tx.send(BackgroundTask::StatusPushFailed);
+        }
+    });
+}
+
+fn spawn_bookmarks_refresh(ids: Vec<String>, tx: mpsc::UnboundedSender<BackgroundTask>) {
+    tokio::spawn(async move {
+        for (i, batch) in ids.chunks(100).enumerate() {
+            if i > 0 {
+                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+            }
+            match get_manga_by_ids(batch).await {
+                Ok(mangas) => {
+                    let _ = tx.send(BackgroundTask::BookmarksRefreshed { mangas });
+                }
+                Err(e) => log::warn!("Failed to refresh bookmark metadata: {}", e),
+            }
+        }
+    });
+}
+
+/// Edits the open command palette. Returns the command to run once one is picked.
+fn handle_palette_input(app: &mut App, key: KeyCode) -> Option<Command> {
+    let view = app.view;
+    let palette = app.command_palette.as_mut()?;
+    match key {
+        KeyCode::Esc => app.command_palette = None,
+        KeyCode::Up => palette.selected = palette.selected.saturating_sub(1),
+        KeyCode::Down => {
+            let last = palette.matches(view).len().saturating_sub(1);
+            palette.selected = (palette.selected + 1).min(last);
+        }
+        KeyCode::Enter => {
+            let command = palette.selected_command(view);
+            app.command_palette = None;
+            return command;
+        }
+        KeyCode::Char(c) => {
+            palette.query.push(c);
+            palette.selected = 0;
+        }
+        KeyCode::Backspace => {
+            palette.query.pop();
+            palette.selected = 0;
+        }
+        _ => {}
+    }
+    None
+}
 
-        if let Some(image) = fetch_page_image(&page_url).await {
-            cache.insert_page(page_url.clone(), image).await;
-            let _ = tx.send(BackgroundTask::PagePreloaded { page_url });
+/// Runs a command picked in the palette through the same code as its key. Returns true
+/// to quit.
+fn run_command(
+    app: &mut App,
+    command: Command,
+    task_tx: &mpsc::UnboundedSender<BackgroundTask>,
+    cache: &PageCache,
+) -> bool {
+    match command {
+        Command::GoHome => show_tab(app, Tab::Home),
+        Command::GoBookmarks => show_tab(app, Tab::Bookmarks),
+        Command::GoSearch => show_tab(app, Tab::Search),
+        Command::GoLocal => show_tab(app, Tab::Local),
+        Command::ResumeReading => match app.progress.last_read.clone() {
+            Some(last) => {
+                return_home(app);
+                resume_reading(app, last, task_tx);
+            }
+            None => app.show_toast("Nothing to resume yet"),
+        },
+        Command::Refresh => refresh_view(app, task_tx),
+        Command::OpenStats => {
+            return_home(app);
+            app.open_stats();
         }
+        Command::ExportStats => app.export_stats(),
+        Command::DownloadChapters => download_chapter_selection(app, task_tx, cache),
+        Command::ReportBrokenChapter => app.report_broken_chapter(),
+        Command::ClearCache => clear_cache(app, task_tx, cache),
+        Command::ShowSettings => {
+            app.show_toast(&format!("Settings are read from {}", get_config_path().display()));
+        }
+        Command::Quit => return app.request_quit(),
+    }
+    false
+}
+
+/// Empties the page and response caches and drops the loaded covers and chapter
+/// thumbnails, which are fetched again for whatever is on screen once the disk is cleared.
+fn clear_cache(app: &mut App, task_tx: &mpsc::UnboundedSender<BackgroundTask>, cache: &PageCache) {
+    app.clear_image_caches();
+    let (tx, cache) = (task_tx.clone(), cache.clone());
+    tokio::spawn(async move {
+        let freed = cache.clear().await + http_cache::clear().await;
+        let _ = tx.send(BackgroundTask::CacheCleared { freed });
     });
 }
 
-fn spawn_search(query: String, tx: mpsc::UnboundedSender<BackgroundTask>) {
+/// Backs out of the reader, a details page or the stats to the home screen, saving
+/// progress on the way like Esc does.
+fn return_home(app: &mut App) {
+    app.comparing = false;
+    app.cover_zoom = false;
+    while app.view != View::Home {
+        app.go_back();
+    }
+}
+
+fn show_tab(app: &mut App, tab: Tab) {
+    return_home(app);
+    app.tab = tab;
+    app.focus = Focus::Header;
+}
+
+/// Re-fetches whatever the current view shows: the browse lists on Home, the chapter list
+/// on a details page, bookmarks from disk, the last search, or the local library.
+fn refresh_view(app: &mut App, tx: &mpsc::UnboundedSender<BackgroundTask>) {
+    if app.refreshing {
+        return;
+    }
+    match app.view {
+        View::MangaDetail => {
+            if let Some(manga) = &app.selected_manga {
+                app.refreshing = true;
+                spawn_chapters_loader(manga.id.clone(), app.config.feed_options(), tx.clone());
+            }
+        }
+        View::Home => match app.tab {
+            Tab::Home => {
+                app.refreshing = true;
+                spawn_home_loader(app.source, app.config.browse_limit, tx.clone());
+            }
+            Tab::Bookmarks => {
+                app.bookmarks = Bookmarks { track_sync: app.bookmarks.track_sync, ..Bookmarks::load() };
+                refresh_bookmarks(app, tx);
+                app.show_toast("Bookmarks reloaded");
+            }
+            Tab::Search => {
+                if (!app.last_search_query.is_empty() || app.search_tag.is_some()) && !app.searching {
+                    start_search(app, app.last_search_query.clone(), tx);
+                }
+            }
+            Tab::Local => {
+                if app.config.local_library_dir().is_some() && !app.local_scanning {
+                    app.local_scanning = true;
+                    spawn_local_library_scan(tx.clone());
+                }
+            }
+        },
+        View::Reader | View::Stats => {}
+    }
+}
+
+/// Runs a search for `query` with the current order and tag filter.
+fn start_search(app: &mut App, query: String, tx: &mpsc::UnboundedSender<BackgroundTask>) {
+    app.searching = true;
+    app.search_debounce = None;
+    app.last_search_query = query.clone();
+    let tag_id = app.search_tag.as_ref().map(|t| t.id.clone());
+    spawn_search(app.source, query, app.search_order, tag_id, tx.clone());
+}
+
+fn spawn_search(
+    source: &'static dyn Source,
+    query: String,
+    order: SearchOrder,
+    tag_id: Option<String>,
+    tx: mpsc::UnboundedSender<BackgroundTask>,
+) {
     tokio::spawn(async move {
-        if let Ok(results) = search_manga(&query).await {
+        if let Ok(results) = source.search(&query, order, tag_id.as_deref()).await {
             let _ = tx.send(BackgroundTask::SearchResults { results });
         } else {
             let _ = tx.send(BackgroundTask::SearchResults { results: Vec::new() });
@@ -274,36 +978,60 @@ async fn run_app(
     task_rx: &mut mpsc::UnboundedReceiver<BackgroundTask>,
     task_tx: mpsc::UnboundedSender<BackgroundTask>,
     cache: PageCache,
+    mut pending_covers: std::collections::HashSet<String>,
+    interrupt: &mut tokio::sync::watch::Receiver<bool>,
 ) -> io::Result<()> {
     let mut event_stream = EventStream::new();
-    let mut pending_covers: std::collections::HashSet<String> = std::collections::HashSet::new();
     let mut preloading_pages: std::collections::HashSet<String> = std::collections::HashSet::new();
-
-    // Track which manga IDs are already loading
-    for manga in app.recently_updated.iter().take(6) {
-        pending_covers.insert(manga.id.clone());
-    }
-    for manga in app.popular_now.iter().take(6) {
-        pending_covers.insert(manga.id.clone());
-    }
+    // Search results alone can fill the cover cache
+    let initial_covers = app.config.initial_covers().min(MAX_COVER_PROTOCOLS);
 
     const DEBOUNCE_MS: u64 = 300;
+    // Time of the last key press, for the idle preload
+    let mut last_input = Instant::now();
+    let mut idle_preload_started = false;
+    let mut rate_limits_seen = rate_limit_hits();
 
     loop {
         terminal.draw(|f| ui(f, app))?;
 
+        if rate_limit_hits() > rate_limits_seen {
+            rate_limits_seen = rate_limit_hits();
+            if let Some(wait) = rate_limit_pause() {
+                app.show_toast(&format!("Rate limited by MangaDex, slowing down for {}s", wait.as_secs().max(1)));
+            }
+        }
+
         // Check if we need to trigger a debounced search
-        if let Some(debounce_time) = app.search_debounce {
-            if debounce_time.elapsed().as_millis() >= DEBOUNCE_MS as u128 {
-                app.search_debounce = None;
-                if !app.search_query.is_empty() 
-                    && !app.searching 
-                    && app.search_query != app.last_search_query 
-                {
-                    app.searching = true;
-                    app.last_search_query = app.search_query.clone();
-                    spawn_search(app.search_query.clone(), task_tx.clone());
-                }
+        if let Some(debounce_time) = app.search_debounce
+            && debounce_time.elapsed().as_millis() >= DEBOUNCE_MS as u128
+        {
+            app.search_debounce = None;
+            if !app.search_query.is_empty() 
+                && !app.searching 
+                && app.search_query != app.last_search_query 
+            {
+                start_search(app, app.search_query.clone(), &task_tx);
+            }
+        }
+
+        app.progress.save_if_due();
+
+        if app.view == View::Reader {
+            advance_auto_scroll(app, &task_tx, &cache, &mut preloading_pages);
+
+            // Start the wider preload once; finished pages keep it going until a key is pressed
+            let ahead = preload_ahead(app, last_input);
+            if ahead > PRELOAD_AHEAD && !idle_preload_started {
+                idle_preload_started = true;
+                log::debug!("Reader idle, preloading {} pages ahead", ahead);
+                preload_pages_ahead(
+                    &app.reader,
+                    ahead,
+                    &mut preloading_pages,
+                    &task_tx,
+                    &cache,
+                );
             }
         }
 
@@ -311,51 +1039,226 @@ async fn run_app(
             // Timeout to check debounce timer
             _ = tokio::time::sleep(tokio::time::Duration::from_millis(50)) => {}
 
+            // Quit through the normal exit path so the terminal is restored
+            _ = shutdown::interrupted(interrupt) => return Ok(()),
+
             // Handle keyboard events
             Some(Ok(event)) = event_stream.next() => {
                 if let Event::Key(key) = event {
-                    match app.view {
-                        View::Home => handle_home_input(app, key.code, &mut pending_covers, &task_tx, &cache),
-                        View::MangaDetail => handle_detail_input(app, key.code, &task_tx, &cache),
-                        View::Reader => handle_reader_input(app, key.code, &task_tx, &cache, &mut preloading_pages),
+                    last_input = Instant::now();
+                    idle_preload_started = false;
+
+                    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                        return Ok(());
+                    }
+
+                    if app.state != AppState::Ready {
+                        if app.state == AppState::Offline && key.code == KeyCode::Char('r') {
+                            app.set_loading("Retrying connection to MangaDex...");
+                            spawn_home_loader(app.source, app.config.browse_limit, task_tx.clone());
+                        }
+                        if key.code == KeyCode::Char('q') {
+                            return Ok(());
+                        }
+                        continue;
+                    }
+
+                    // `R` types into the search box, so only F5 refreshes there
+                    let refresh_key = key.code == KeyCode::F(5)
+                        || (key.code == KeyCode::Char('R') && !(app.view == View::Home && app.tab == Tab::Search));
+                    if refresh_key && app.view != View::Reader && !app.is_text_input_active() {
+                        refresh_view(app, &task_tx);
+                        continue;
+                    }
+
+                    if app.quit_prompt {
+                        match key.code {
+                            KeyCode::Char('y') => return Ok(()),
+                            KeyCode::Char('n') | KeyCode::Esc => app.quit_prompt = false,
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    if app.explicit_prompt {
+                        match key.code {
+                            KeyCode::Char('y') => app.answer_explicit_prompt(true),
+                            KeyCode::Char('n') | KeyCode::Esc => app.answer_explicit_prompt(false),
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    if let Some(pending) = app.resume_download_prompt.take() {
+                        match key.code {
+                            KeyCode::Char('y') => start_batch_download(app, pending, &task_tx, &cache),
+                            KeyCode::Char('n') | KeyCode::Esc => pending.discard(),
+                            _ => app.resume_download_prompt = Some(pending),
+                        }
+                        continue;
+                    }
+
+                    if app.command_palette.is_some() {
+                        if let Some(command) = handle_palette_input(app, key.code)
+                            && run_command(app, command, &task_tx, &cache)
+                        {
+                            return Ok(());
+                        }
+                        continue;
+                    }
+                    // `:` types into the search box, so only Ctrl-P opens the palette there
+                    let palette_key = (key.code == KeyCode::Char('p') && key.modifiers.contains(KeyModifiers::CONTROL))
+                        || (key.code == KeyCode::Char(':')
+                            && !app.is_text_input_active()
+                            && !(app.view == View::Home && app.tab == Tab::Search));
+                    if palette_key {
+                        app.command_palette = Some(CommandPalette::default());
+                        continue;
+                    }
+
+                    match app.view {
+                        View::Home => {
+                            handle_home_input(app, key.code, &mut pending_covers, &task_tx, &cache);
+                            fetch_focused_cover(app, &mut pending_covers, &task_tx);
+                        }
+                        View::MangaDetail => handle_detail_input(app, key.code, &task_tx, &cache),
+                        View::Reader => handle_reader_input(app, key.code, key.modifiers, &task_tx, &cache, &mut preloading_pages),
+                        View::Stats => handle_stats_input(app, key.code),
                     }
                     
-                    if key.code == KeyCode::Char('q') {
+                    if key.code == KeyCode::Char('q') && !app.is_text_input_active() && app.request_quit() {
                         return Ok(());
                     }
+
+                    if app.tab == Tab::Bookmarks && !app.bookmarks_refreshed {
+                        refresh_bookmarks(app, &task_tx);
+                    }
                 }
             }
 
             // Handle background task results
             Some(task) = task_rx.recv() => {
                 match task {
-                    BackgroundTask::CoverLoaded { manga_id, image } => {
-                        app.add_cover_image(&manga_id, image);
+                    BackgroundTask::HomeLoaded { recent, popular } => {
+                        // A failed refresh keeps the lists already on screen
+                        if std::mem::take(&mut app.refreshing) && recent.is_err() && popular.is_err() {
+                            app.show_toast("Couldn't reach MangaDex to refresh");
+                            continue;
+                        }
+                        if store_home_results(app, recent, popular) {
+                            spawn_home_covers(app, &mut pending_covers, &task_tx);
+                            finish_startup(app, &task_tx);
+                        }
+                    }
+                    BackgroundTask::CoverLoaded { manga_id, image, explicit, pornographic } => {
+                        app.add_cover_image(&manga_id, image, explicit, pornographic);
                         pending_covers.remove(&manga_id);
                     }
-                    BackgroundTask::ChaptersLoaded { chapters } => {
-                        app.chapters = chapters.clone();
-                        // Preload all chapter thumbnails in background
-                        spawn_chapter_thumbnails_preloader(
-                            chapters,
-                            task_tx.clone(),
-                            cache.clone(),
-                        );
+                    BackgroundTask::CoverLoadFailed { manga_id } => {
+                        // Let the next preload pass try again
+                        log::warn!("Failed to load cover for {}", manga_id);
+                        pending_covers.remove(&manga_id);
+                    }
+                    BackgroundTask::ChaptersLoaded { manga_id, chapters } => {
+                        app.progress.update_completion(&manga_id, &chapters);
+
+                        let quick_open = app.quick_open.as_deref() == Some(manga_id.as_str());
+                        if quick_open {
+                            app.quick_open = None;
+                            // Only jump if the user is still looking at the bookmarks
+                            if app.view == View::Home
+                                && app.tab == Tab::Bookmarks
+                                && let Some(manga) = app
+                                    .bookmarks
+                                    .get_bookmarked_manga(app.config.bookmark_sort)
+                                    .into_iter()
+                                    .find(|m| m.id == manga_id)
+                            {
+                                app.open_manga(manga);
+                            }
+                        }
+
+                        // Ignore chapters for a manga that's no longer open
+                        if app.selected_manga.as_ref().map(|m| m.id.as_str()) != Some(manga_id.as_str()) {
+                            continue;
+                        }
+
+                        // A refresh keeps the selected chapter, wherever it ends up in the new list
+                        let selected_id = app.chapters.get(app.chapter_selected).map(|c| c.id.clone());
+                        app.chapters = chapters;
+                        if std::mem::take(&mut app.refreshing) {
+                            app.chapter_selected = selected_id
+                                .and_then(|id| app.chapters.iter().position(|c| c.id == id))
+                                .unwrap_or(0);
+                        } else {
+                            app.restore_grid_position();
+                        }
+                        // Only followed series keep a snapshot to compare against
+                        if app.bookmarks.is_bookmarked(&manga_id) {
+                            let new = app.progress.unseen_chapters(&manga_id, &app.chapters);
+                            app.new_chapters.extend(new);
+                        }
+                        if let Some((chapter_id, page)) = app.pending_resume.take() {
+                            if let Some(idx) = app.chapters.iter().position(|c| c.id == chapter_id) {
+                                app.chapter_selected = idx;
+                                app.open_reader(idx);
+                                app.reader.resume_page = Some(page);
+                                spawn_page_urls_loader(chapter_id, task_tx.clone(), cache.clone());
+                            } else {
+                                app.show_toast("Couldn't find the last-read chapter");
+                            }
+                        } else if quick_open && app.view == View::MangaDetail {
+                            if let Some(idx) = app.next_unread_chapter().or_else(|| app.latest_chapter()) {
+                                let chapter_id = app.chapters[idx].id.clone();
+                                app.chapter_selected = idx;
+                                app.open_reader(idx);
+                                spawn_page_urls_loader(chapter_id, task_tx.clone(), cache.clone());
+                            } else {
+                                app.show_toast("No readable chapters");
+                            }
+                        }
+                        if app.view == View::MangaDetail {
+                            prefetch_adjacent_chapter_urls(app, &cache);
+                        }
+                        // Preload the thumbnails around the focused card in background
+                        if app.show_chapter_thumbnails {
+                            spawn_chapter_thumbnails_preloader(
+                                app.thumbnail_preload_chapters(),
+                                app.config.thumbnail_preload_delay_ms,
+                                task_tx.clone(),
+                                cache.clone(),
+                            );
+                        }
+                    }
+                    BackgroundTask::ChaptersLoadFailed { manga_id } => {
+                        let open = app.selected_manga.as_ref().is_some_and(|m| m.id == manga_id);
+                        if open && std::mem::take(&mut app.refreshing) {
+                            app.show_toast("Couldn't refresh chapters");
+                        }
                     }
                     BackgroundTask::ChapterThumbnailLoaded { chapter_id, image } => {
                         app.add_chapter_thumbnail(&chapter_id, image);
                     }
                     BackgroundTask::PageUrlsLoaded { urls } => {
+                        // Preloads still running for the previous chapter report back harmlessly
+                        preloading_pages.clear();
                         app.reader.page_urls = urls;
                         app.reader.error = None;
-                        // Load first page
-                        if let Some(url) = app.reader.page_urls.first() {
-                            spawn_page_image_loader(url.clone(), task_tx.clone(), cache.clone());
+                        if let Some(page) = app.reader.resume_page.take() {
+                            let page = page.saturating_sub(app.config.resume_rewind);
+                            app.reader.current_page = page.min(app.reader.page_urls.len().saturating_sub(1));
+                        }
+                        app.track_read_progress();
+                        if app.config.show_page_cache_status {
+                            spawn_page_cache_check(app.reader.page_urls.clone(), task_tx.clone(), cache.clone());
+                        }
+                        // Load the first (or resumed) page
+                        if let Some(url) = app.reader.page_urls.get(app.reader.current_page) {
+                            spawn_page_image_loader(url.clone(), app.config.retry_policy(), task_tx.clone(), cache.clone());
                         }
                         // Preload next few pages in background
                         preload_upcoming_pages(
-                            &app.reader.page_urls,
-                            0,
+                            &app.reader,
                             &mut preloading_pages,
                             &task_tx,
                             &cache,
@@ -364,40 +1267,214 @@ async fn run_app(
                     BackgroundTask::PageUrlsLoadFailed => {
                         app.set_page_load_error("Failed to load chapter pages. Press 'r' to retry.".to_string());
                     }
+                    BackgroundTask::ChapterHasNoPages => {
+                        app.set_page_load_error(
+                            "This chapter has no readable pages on the server. Try another chapter (n/p) or open it in the browser (o).".to_string(),
+                        );
+                    }
                     BackgroundTask::PageImageLoaded { image } => {
+                        if let Some(url) = app.reader.page_urls.get(app.reader.current_page) {
+                            app.reader.cached_pages.insert(url.clone());
+                        }
+                        if app.reader.show_thumbnails
+                            && let Some(url) = app.reader.page_urls.get(app.reader.current_page).cloned()
+                        {
+                            let thumbnail = image.thumbnail(PAGE_THUMBNAIL_SIZE.0, PAGE_THUMBNAIL_SIZE.1);
+                            app.add_page_thumbnail(&url, thumbnail);
+                        }
                         app.set_page_image(image);
                         // Preload upcoming pages when current page loads
                         preload_upcoming_pages(
-                            &app.reader.page_urls,
-                            app.reader.current_page,
+                            &app.reader,
                             &mut preloading_pages,
                             &task_tx,
                             &cache,
                         );
                     }
-                    BackgroundTask::PageImageLoadFailed => {
+                    BackgroundTask::PageImageRetrying { page_url, retry, retries } => {
+                        app.reader.retry_status = Some((page_url, retry, retries));
+                    }
+                    BackgroundTask::PageImageLoadFailed { page_url } => {
+                        if app.reader.page_urls.contains(&page_url) && !app.reader.failed_pages.contains(&page_url) {
+                            app.reader.failed_pages.push(page_url);
+                        }
                         app.set_page_load_error("Failed to load page image. Press 'r' to retry.".to_string());
                     }
-                    BackgroundTask::PagePreloaded { page_url } => {
+                    BackgroundTask::PagesCached { page_urls } => {
+                        // The reader may have moved to another chapter since the check started
+                        let current: Vec<String> =
+                            page_urls.into_iter().filter(|url| app.reader.page_urls.contains(url)).collect();
+                        app.reader.cached_pages.extend(current);
+                    }
+                    BackgroundTask::PagePreloaded { page_url, ok } => {
+                        // A failed page is tried again the next time the window is topped up
                         preloading_pages.remove(&page_url);
-                        // Continue preloading from this page's position
-                        if let Some(idx) = app.reader.page_urls.iter().position(|u| u == &page_url) {
-                            preload_upcoming_pages(
-                                &app.reader.page_urls,
-                                idx,
+                        if !ok {
+                            continue;
+                        }
+                        app.reader.cached_pages.insert(page_url.clone());
+                        if app.reader.show_thumbnails && !app.reader.page_thumbnails.contains_key(&page_url) {
+                            spawn_page_thumbnail_loader(page_url.clone(), task_tx.clone(), cache.clone());
+                        }
+                        // Keep the window ahead of the current page topped up; it only
+                        // reaches past the usual few pages while the user is idle
+                        if app.reader.page_urls.contains(&page_url) {
+                            preload_pages_ahead(
+                                &app.reader,
+                                preload_ahead(app, last_input),
                                 &mut preloading_pages,
                                 &task_tx,
                                 &cache,
                             );
                         }
                     }
+                    BackgroundTask::PageThumbnailLoaded { page_url, image } => {
+                        // Ignore thumbnails that arrive after switching chapters
+                        if app.reader.page_urls.contains(&page_url) {
+                            app.add_page_thumbnail(&page_url, image);
+                        }
+                    }
+                    BackgroundTask::ChapterPrefetchProgress { chapter_id, page_url, cached } => {
+                        let reading = app.reader.chapters.get(app.reader.current_chapter_idx);
+                        if cached && reading.is_some_and(|c| c.id == chapter_id) {
+                            app.reader.cached_pages.insert(page_url);
+                        }
+                        if let Some(prefetch) = app.reader.prefetch.as_mut()
+                            && prefetch.chapter_id == chapter_id
+                        {
+                            if cached {
+                                prefetch.done += 1;
+                            } else {
+                                prefetch.failed += 1;
+                            }
+                            if prefetch.done + prefetch.failed >= prefetch.total {
+                                let message = match prefetch.failed {
+                                    0 => "Chapter cached for offline reading".to_string(),
+                                    failed => format!(
+                                        "Cached {} of {} pages; {} couldn't be fetched",
+                                        prefetch.done, prefetch.total, failed
+                                    ),
+                                };
+                                app.reader.prefetch = None;
+                                app.show_toast(&message);
+                            }
+                        }
+                    }
+                    BackgroundTask::BatchDownloadProgress { done, failed } => {
+                        if let Some(batch) = app.batch_download.as_mut() {
+                            batch.done = done;
+                            if done >= batch.total {
+                                let total = batch.total;
+                                app.batch_download = None;
+                                if failed > 0 {
+                                    app.show_toast(&format!(
+                                        "Downloaded {} of {} chapters",
+                                        total - failed,
+                                        total
+                                    ));
+                                } else {
+                                    app.show_toast(&format!(
+                                        "Saved {} chapters to {}",
+                                        total,
+                                        app.config.downloads_dir().display()
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                    BackgroundTask::MangaDexSynced { username, result, sent } => match result {
+                        Ok(result) => {
+                            app.bookmarks.apply_sync(
+                                &username,
+                                &result.added,
+                                &result.removed,
+                                &sent,
+                                &result.failed,
+                                &result.statuses,
+                            );
+                            let bookmarked = app.filtered_bookmarks();
+                            preload_covers(
+                                &bookmarked,
+                                app.bookmark_offset,
+                                (app.config.cover_preload_ahead, 0),
+                                &mut pending_covers,
+                                &app.image_states,
+                                task_tx.clone(),
+                            );
+                            app.show_toast(&format!(
+                                "Synced with MangaDex: +{} / -{} / pushed {}",
+                                result.added.len(),
+                                result.removed.len(),
+                                result.pushed
+                            ));
+                        }
+                        Err(e) => {
+                            log::warn!("MangaDex sync failed: {}", e);
+                            app.show_toast(&format!("MangaDex sync failed: {}", e));
+                        }
+                    },
+                    BackgroundTask::CacheCleared { freed } => {
+                        app.show_toast(&format!("Cache cleared, {} freed", format_bytes(freed)));
+                        // Bring back what's on screen
+                        spawn_home_covers(app, &mut pending_covers, &task_tx);
+                        if let Some(manga) = &app.selected_manga {
+                            spawn_cover_loaders(std::slice::from_ref(manga), 0, 1, task_tx.clone());
+                        }
+                        if app.view == View::MangaDetail {
+                            preload_chapter_thumbnails(app, app.chapter_selected, &task_tx, &cache);
+                        }
+                    }
+                    BackgroundTask::StatusPushFailed => {
+                        app.show_toast("Couldn't update the reading status on MangaDex");
+                    }
+                    BackgroundTask::BookmarkPushed { username, manga_id, bookmarked, ok } => {
+                        if ok {
+                            app.bookmarks.confirm_push(&username, &manga_id, bookmarked);
+                        } else {
+                            app.show_toast("Couldn't update MangaDex; will retry at next launch");
+                        }
+                    }
+                    BackgroundTask::BookmarksRefreshed { mangas } => {
+                        if app.bookmarks.record_latest_chapters(&mangas) > 0 {
+                            let count = app.bookmarks.updated_ids.len();
+                            let noun = if count == 1 { "series has" } else { "series have" };
+                            app.show_toast(&format!("{} {} new chapters", count, noun));
+                        }
+                        // Drop stale covers so the new ones load on the next preload
+                        for manga_id in app.bookmarks.refresh_cached(&mangas) {
+                            app.image_states.remove(&manga_id);
+                            pending_covers.remove(&manga_id);
+                        }
+                        let bookmarked = app.filtered_bookmarks();
+                        preload_covers(
+                            &bookmarked,
+                            app.bookmark_offset,
+                            (app.config.cover_preload_ahead, 0),
+                            &mut pending_covers,
+                            &app.image_states,
+                            task_tx.clone(),
+                        );
+                    }
+                    BackgroundTask::LocalLibraryLoaded { mangas } => {
+                        app.local_library = mangas;
+                        app.local_scanning = false;
+                        app.local_offset = app.local_offset.min(app.local_library.len().saturating_sub(1));
+                        preload_covers(
+                            &app.local_library,
+                            app.local_offset,
+                            (app.config.cover_preload_ahead, 0),
+                            &mut pending_covers,
+                            &app.image_states,
+                            task_tx.clone(),
+                        );
+                    }
                     BackgroundTask::SearchResults { results } => {
                         app.search_results = results;
                         app.searching = false;
                         app.search_offset = 0;
                         // Load covers for search results
-                        spawn_cover_loaders(&app.search_results, 0, 6, task_tx.clone());
-                        for manga in app.search_results.iter().take(6) {
+                        spawn_cover_loaders(&app.search_results, 0, initial_covers, task_tx.clone());
+                        for manga in app.search_results.iter().take(initial_covers) {
                             pending_covers.insert(manga.id.clone());
                         }
                     }
@@ -414,10 +1491,38 @@ fn handle_home_input(
     task_tx: &mpsc::UnboundedSender<BackgroundTask>,
     cache: &PageCache,
 ) {
+    if app.comparing {
+        handle_compare_input(app, key, task_tx);
+        return;
+    }
+
+    // `S` would be typed into the search box there
+    if key == KeyCode::Char('S') && app.tab != Tab::Search && !app.is_text_input_active() {
+        app.open_stats();
+        return;
+    }
+
     match app.tab {
         Tab::Home => handle_home_tab_input(app, key, pending_covers, task_tx, cache),
         Tab::Bookmarks => handle_bookmarks_tab_input(app, key, pending_covers, task_tx, cache),
         Tab::Search => handle_search_tab_input(app, key, pending_covers, task_tx, cache),
+        Tab::Local => handle_local_tab_input(app, key, pending_covers, task_tx, cache),
+    }
+}
+
+fn handle_compare_input(app: &mut App, key: KeyCode, task_tx: &mpsc::UnboundedSender<BackgroundTask>) {
+    match key {
+        KeyCode::Esc => app.comparing = false,
+        KeyCode::Char(c @ ('1' | '2')) => {
+            let idx = if c == '1' { 0 } else { 1 };
+            if let Some(manga) = app.compare_pins.get(idx).cloned() {
+                app.comparing = false;
+                let manga_id = manga.id.clone();
+                app.open_manga(manga);
+                spawn_chapters_loader(manga_id, app.config.feed_options(), task_tx.clone());
+            }
+        }
+        _ => {}
     }
 }
 
@@ -428,10 +1533,17 @@ fn handle_home_tab_input(
     task_tx: &mpsc::UnboundedSender<BackgroundTask>,
     _cache: &PageCache,
 ) {
+    let continuing = app.continue_reading_manga();
+    // The row disappears once its last chapter is finished
+    if app.focus == Focus::Continue && continuing.is_empty() {
+        app.focus = Focus::Recent;
+    }
+
     match key {
         KeyCode::Tab | KeyCode::Down => {
             app.focus = match app.focus {
-                Focus::Header => Focus::Recent,
+                Focus::Header if !continuing.is_empty() => Focus::Continue,
+                Focus::Header | Focus::Continue => Focus::Recent,
                 Focus::Recent => Focus::Popular,
                 Focus::Popular => Focus::Header,
             }
@@ -439,63 +1551,93 @@ fn handle_home_tab_input(
         KeyCode::Up => {
             app.focus = match app.focus {
                 Focus::Header => Focus::Popular,
+                Focus::Continue => Focus::Header,
+                Focus::Recent if !continuing.is_empty() => Focus::Continue,
                 Focus::Recent => Focus::Header,
                 Focus::Popular => Focus::Recent,
             }
         }
-        KeyCode::Left => match app.focus {
-            Focus::Header => {
-                app.tab = Tab::Search;
-            }
-            Focus::Recent => {
-                app.recent_offset = app.recent_offset.saturating_sub(1);
-            }
-            Focus::Popular => {
-                app.popular_offset = app.popular_offset.saturating_sub(1);
-            }
-        },
-        KeyCode::Right => match app.focus {
-            Focus::Header => {
-                app.tab = Tab::Bookmarks;
-            }
-            Focus::Recent => {
-                app.recent_offset += 1;
-                preload_covers(
-                    &app.recently_updated,
-                    app.recent_offset,
-                    pending_covers,
-                    &app.image_states,
-                    task_tx.clone(),
-                );
-            }
-            Focus::Popular => {
-                app.popular_offset += 1;
-                preload_covers(
-                    &app.popular_now,
-                    app.popular_offset,
-                    pending_covers,
-                    &app.image_states,
-                    task_tx.clone(),
-                );
+        KeyCode::Left | KeyCode::Right => {
+            let forward = key == KeyCode::Right;
+            let wrap = app.config.wrap_navigation;
+            let span = app.cover_preload_span(forward);
+            let (offset, mangas) = match app.focus {
+                Focus::Header => {
+                    app.tab = if forward { Tab::Bookmarks } else { Tab::Local };
+                    return;
+                }
+                Focus::Continue => (&mut app.continue_offset, &continuing),
+                Focus::Recent => (&mut app.recent_offset, &app.recently_updated),
+                Focus::Popular => (&mut app.popular_offset, &app.popular_now),
+            };
+            if step_card_row(offset, mangas.len(), forward, wrap) {
+                preload_covers(mangas, *offset, span, pending_covers, &app.image_states, task_tx.clone());
             }
-        },
+        }
         KeyCode::Enter => {
+            if app.focus == Focus::Continue {
+                let entry = app.progress.continue_reading().get(app.continue_offset).map(|e| (*e).clone());
+                if let Some(entry) = entry {
+                    resume_reading(app, entry, task_tx);
+                }
+                return;
+            }
+
             let manga = match app.focus {
                 Focus::Recent => app.recently_updated.get(app.recent_offset).cloned(),
                 Focus::Popular => app.popular_now.get(app.popular_offset).cloned(),
-                Focus::Header => None,
+                Focus::Header | Focus::Continue => None,
             };
             
             if let Some(manga) = manga {
                 let manga_id = manga.id.clone();
                 app.open_manga(manga);
-                spawn_chapters_loader(manga_id, task_tx.clone());
+                spawn_chapters_loader(manga_id, app.config.feed_options(), task_tx.clone());
+            }
+        }
+        KeyCode::Char('v') => {
+            let manga = match app.focus {
+                Focus::Continue => continuing.get(app.continue_offset).cloned(),
+                Focus::Recent => app.recently_updated.get(app.recent_offset).cloned(),
+                Focus::Popular => app.popular_now.get(app.popular_offset).cloned(),
+                Focus::Header => None,
+            };
+            if let Some(manga) = manga {
+                reveal_cover(app, &manga, task_tx);
+            }
+        }
+        KeyCode::Char('t') => {
+            let manga_id = match app.focus {
+                Focus::Continue => continuing.get(app.continue_offset).map(|m| m.id.clone()),
+                Focus::Recent => app.recently_updated.get(app.recent_offset).map(|m| m.id.clone()),
+                Focus::Popular => app.popular_now.get(app.popular_offset).map(|m| m.id.clone()),
+                Focus::Header => None,
+            };
+            if let Some(manga_id) = manga_id {
+                app.cycle_reading_status(&manga_id);
+                spawn_status_push(app, manga_id, task_tx.clone());
             }
         }
         _ => {}
     }
 }
 
+/// Moves a card row's offset one card, wrapping past either end when `wrap` is set.
+/// Returns true if the offset changed.
+fn step_card_row(offset: &mut usize, len: usize, forward: bool, wrap: bool) -> bool {
+    let last = len.saturating_sub(1);
+    let next = match (forward, wrap) {
+        (true, _) if *offset < last => *offset + 1,
+        (true, true) => 0,
+        (false, _) if *offset > 0 => (*offset - 1).min(last),
+        (false, true) => last,
+        _ => *offset,
+    };
+    let changed = next != *offset;
+    *offset = next;
+    changed
+}
+
 fn handle_bookmarks_tab_input(
     app: &mut App,
     key: KeyCode,
@@ -503,31 +1645,77 @@ fn handle_bookmarks_tab_input(
     task_tx: &mpsc::UnboundedSender<BackgroundTask>,
     _cache: &PageCache,
 ) {
-    let bookmarked = app.bookmarks.get_bookmarked_manga();
-    
-    match key {
-        KeyCode::Left => {
-            if app.focus == Focus::Header {
-                app.tab = Tab::Home;
-            } else {
-                app.bookmark_offset = app.bookmark_offset.saturating_sub(1);
+    if app.bookmark_filter_active {
+        match key {
+            KeyCode::Char(c) => {
+                app.bookmark_filter.push(c);
+                app.bookmark_offset = 0;
+            }
+            KeyCode::Backspace => {
+                app.bookmark_filter.pop();
+                app.bookmark_offset = 0;
+            }
+            KeyCode::Enter => {
+                app.bookmark_filter_active = false;
             }
+            KeyCode::Esc => {
+                app.bookmark_filter.clear();
+                app.bookmark_filter_active = false;
+                app.bookmark_offset = 0;
+            }
+            _ => {}
         }
-        KeyCode::Right => {
-            if app.focus == Focus::Header {
-                app.tab = Tab::Search;
-            } else if !bookmarked.is_empty() {
-                let max_offset = bookmarked.len().saturating_sub(1);
-                if app.bookmark_offset < max_offset {
-                    app.bookmark_offset += 1;
-                    preload_covers(
-                        &bookmarked,
-                        app.bookmark_offset,
-                        pending_covers,
-                        &app.image_states,
-                        task_tx.clone(),
-                    );
+        return;
+    }
+
+    let bookmarked = app.filtered_bookmarks();
+
+    if std::mem::take(&mut app.bookmark_jump_active) {
+        if let KeyCode::Char(letter) = key {
+            let titles = bookmarked.iter().map(|m| m.title.as_str());
+            match ui::text::next_with_initial(titles, app.bookmark_offset, letter) {
+                Some(offset) => {
+                    app.bookmark_offset = offset;
+                    app.focus = Focus::Recent;
+                    let span = app.cover_preload_span(true);
+                    preload_covers(&bookmarked, offset, span, pending_covers, &app.image_states, task_tx.clone());
                 }
+                None => app.show_toast(&format!("No bookmarks starting with {}", letter)),
+            }
+        }
+        return;
+    }
+
+    match key {
+        KeyCode::Char('g') => {
+            app.bookmark_jump_active = !bookmarked.is_empty();
+        }
+        KeyCode::Char('/') => {
+            app.bookmark_filter_active = true;
+        }
+        KeyCode::Esc if !app.bookmark_filter.is_empty() => {
+            app.bookmark_filter.clear();
+            app.bookmark_offset = 0;
+        }
+        KeyCode::Left | KeyCode::Right => {
+            let forward = key == KeyCode::Right;
+            if app.focus == Focus::Header {
+                app.tab = if forward { Tab::Search } else { Tab::Home };
+            } else if step_card_row(
+                &mut app.bookmark_offset,
+                bookmarked.len(),
+                forward,
+                app.config.wrap_navigation,
+            ) {
+                let span = app.cover_preload_span(forward);
+                preload_covers(
+                    &bookmarked,
+                    app.bookmark_offset,
+                    span,
+                    pending_covers,
+                    &app.image_states,
+                    task_tx.clone(),
+                );
             }
         }
         KeyCode::Tab | KeyCode::Down => {
@@ -537,12 +1725,50 @@ fn handle_bookmarks_tab_input(
             app.focus = Focus::Header;
         }
         KeyCode::Enter => {
-            if app.focus != Focus::Header {
-                if let Some(manga) = bookmarked.get(app.bookmark_offset).cloned() {
-                    let manga_id = manga.id.clone();
-                    app.open_manga(manga);
-                    spawn_chapters_loader(manga_id, task_tx.clone());
-                }
+            if app.focus != Focus::Header
+                && let Some(manga) = bookmarked.get(app.bookmark_offset).cloned()
+            {
+                let manga_id = manga.id.clone();
+                app.open_manga(manga);
+                spawn_chapters_loader(manga_id, app.config.feed_options(), task_tx.clone());
+            }
+        }
+        KeyCode::Char('v') => {
+            if app.focus != Focus::Header
+                && let Some(manga) = bookmarked.get(app.bookmark_offset).cloned()
+            {
+                reveal_cover(app, &manga, task_tx);
+            }
+        }
+        KeyCode::Char('l') => {
+            if app.focus != Focus::Header
+                && app.quick_open.is_none()
+                && let Some(manga) = bookmarked.get(app.bookmark_offset)
+            {
+                app.quick_open = Some(manga.id.clone());
+                spawn_chapters_loader(manga.id.clone(), app.config.feed_options(), task_tx.clone());
+            }
+        }
+        KeyCode::Char('p') => {
+            if app.focus != Focus::Header
+                && let Some(manga) = bookmarked.get(app.bookmark_offset)
+            {
+                let pinned = app.bookmarks.toggle_pin(&manga.id);
+                // Keep the same manga selected now that it has moved
+                app.bookmark_offset = app
+                    .filtered_bookmarks()
+                    .iter()
+                    .position(|m| m.id == manga.id)
+                    .unwrap_or(0);
+                app.show_toast(if pinned { "Pinned to top" } else { "Unpinned" });
+            }
+        }
+        KeyCode::Char('t') => {
+            if app.focus != Focus::Header
+                && let Some(manga) = bookmarked.get(app.bookmark_offset)
+            {
+                app.cycle_reading_status(&manga.id);
+                spawn_status_push(app, manga.id.clone(), task_tx.clone());
             }
         }
         _ => {}
@@ -557,11 +1783,49 @@ fn handle_search_tab_input(
     _cache: &PageCache,
 ) {
     match key {
+        KeyCode::Char('p') if app.focus != Focus::Header => {
+            app.toggle_compare_pin();
+        }
+        KeyCode::Char('t') if app.focus != Focus::Header => {
+            if let Some(manga_id) = app.search_results.get(app.search_offset).map(|m| m.id.clone()) {
+                app.cycle_reading_status(&manga_id);
+                spawn_status_push(app, manga_id, task_tx.clone());
+            }
+        }
+        KeyCode::Char('c') if app.focus != Focus::Header => {
+            if app.compare_pins.len() < 2 {
+                app.show_toast("Pin two results with p to compare them");
+            } else {
+                // Pins from an earlier search may have lost their covers
+                let missing: Vec<Manga> = app
+                    .compare_pins
+                    .iter()
+                    .filter(|m| !app.image_states.contains_key(&m.id) && !pending_covers.contains(&m.id))
+                    .cloned()
+                    .collect();
+                for manga in &missing {
+                    pending_covers.insert(manga.id.clone());
+                }
+                spawn_cover_loaders(&missing, 0, missing.len(), task_tx.clone());
+                app.comparing = true;
+            }
+        }
+        KeyCode::Char('s') if app.focus != Focus::Header => {
+            app.search_order = app.search_order.next();
+            // Re-run the last search so the new order comes from the server, not just this page
+            if (!app.last_search_query.is_empty() || app.search_tag.is_some()) && !app.searching {
+                start_search(app, app.last_search_query.clone(), task_tx);
+            }
+        }
         KeyCode::Char(c) => {
             app.search_query.push(c);
             app.search_debounce = Some(std::time::Instant::now());
         }
         KeyCode::Backspace => {
+            // Backspace on an empty query drops the tag filter
+            if app.search_query.is_empty() {
+                app.search_tag = None;
+            }
             app.search_query.pop();
             if app.search_query.is_empty() {
                 app.search_results.clear();
@@ -574,43 +1838,37 @@ fn handle_search_tab_input(
         KeyCode::Enter => {
             if app.focus == Focus::Header {
                 // Immediate search on Enter
-                if !app.search_query.is_empty() && !app.searching {
-                    app.searching = true;
-                    app.last_search_query = app.search_query.clone();
-                    app.search_debounce = None;
-                    spawn_search(app.search_query.clone(), task_tx.clone());
+                if (!app.search_query.is_empty() || app.search_tag.is_some()) && !app.searching {
+                    start_search(app, app.search_query.clone(), task_tx);
                 }
             } else {
                 // Open manga when focused on results
                 if let Some(manga) = app.search_results.get(app.search_offset).cloned() {
                     let manga_id = manga.id.clone();
                     app.open_manga(manga);
-                    spawn_chapters_loader(manga_id, task_tx.clone());
+                    spawn_chapters_loader(manga_id, app.config.feed_options(), task_tx.clone());
                 }
             }
         }
-        KeyCode::Left => {
-            if app.focus == Focus::Header {
-                app.tab = Tab::Bookmarks;
-            } else {
-                app.search_offset = app.search_offset.saturating_sub(1);
-            }
-        }
-        KeyCode::Right => {
+        KeyCode::Left | KeyCode::Right => {
+            let forward = key == KeyCode::Right;
             if app.focus == Focus::Header {
-                app.tab = Tab::Home;
-            } else if !app.search_results.is_empty() {
-                let max_offset = app.search_results.len().saturating_sub(1);
-                if app.search_offset < max_offset {
-                    app.search_offset += 1;
-                    preload_covers(
-                        &app.search_results,
-                        app.search_offset,
-                        pending_covers,
-                        &app.image_states,
-                        task_tx.clone(),
-                    );
-                }
+                app.tab = if forward { Tab::Local } else { Tab::Bookmarks };
+            } else if step_card_row(
+                &mut app.search_offset,
+                app.search_results.len(),
+                forward,
+                app.config.wrap_navigation,
+            ) {
+                let span = app.cover_preload_span(forward);
+                preload_covers(
+                    &app.search_results,
+                    app.search_offset,
+                    span,
+                    pending_covers,
+                    &app.image_states,
+                    task_tx.clone(),
+                );
             }
         }
         KeyCode::Tab | KeyCode::Down => {
@@ -625,49 +1883,142 @@ fn handle_search_tab_input(
             } else {
                 app.search_query.clear();
                 app.search_results.clear();
+                app.search_tag = None;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_local_tab_input(
+    app: &mut App,
+    key: KeyCode,
+    pending_covers: &mut std::collections::HashSet<String>,
+    task_tx: &mpsc::UnboundedSender<BackgroundTask>,
+    _cache: &PageCache,
+) {
+    match key {
+        KeyCode::Left | KeyCode::Right => {
+            let forward = key == KeyCode::Right;
+            if app.focus == Focus::Header {
+                app.tab = if forward { Tab::Home } else { Tab::Search };
+            } else if step_card_row(
+                &mut app.local_offset,
+                app.local_library.len(),
+                forward,
+                app.config.wrap_navigation,
+            ) {
+                let span = app.cover_preload_span(forward);
+                preload_covers(
+                    &app.local_library,
+                    app.local_offset,
+                    span,
+                    pending_covers,
+                    &app.image_states,
+                    task_tx.clone(),
+                );
+            }
+        }
+        KeyCode::Tab | KeyCode::Down => {
+            app.focus = Focus::Recent;
+        }
+        KeyCode::Up => {
+            app.focus = Focus::Header;
+        }
+        KeyCode::Enter => {
+            if app.focus != Focus::Header
+                && let Some(manga) = app.local_library.get(app.local_offset).cloned()
+            {
+                let manga_id = manga.id.clone();
+                app.open_manga(manga);
+                spawn_chapters_loader(manga_id, app.config.feed_options(), task_tx.clone());
+            }
+        }
+        KeyCode::Char('r') if app.config.local_library_dir().is_some() && !app.local_scanning => {
+            app.local_scanning = true;
+            spawn_local_library_scan(task_tx.clone());
+        }
+        KeyCode::Char('t') if app.focus != Focus::Header => {
+            if let Some(manga_id) = app.local_library.get(app.local_offset).map(|m| m.id.clone()) {
+                app.cycle_reading_status(&manga_id);
             }
         }
         _ => {}
     }
 }
 
+fn handle_stats_input(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Char('e') => app.export_stats(),
+        KeyCode::Esc => app.go_back(),
+        _ => {}
+    }
+}
+
 fn handle_detail_input(
     app: &mut App,
     key: KeyCode,
     task_tx: &mpsc::UnboundedSender<BackgroundTask>,
     cache: &PageCache,
 ) {
-    let cols = app.chapter_grid_cols.max(1);
-    
+    if app.cover_zoom {
+        if matches!(key, KeyCode::Esc | KeyCode::Char('z')) {
+            app.cover_zoom = false;
+        }
+        return;
+    }
+
     match key {
+        KeyCode::Char('z') => {
+            app.cover_zoom = true;
+        }
+        KeyCode::Esc if app.selected_tag.is_some() => {
+            app.selected_tag = None;
+        }
+        KeyCode::Char('t') => {
+            app.cycle_selected_tag();
+        }
+        KeyCode::Enter if app.selected_tag.is_some() => {
+            if let Some(tag) = app.search_by_selected_tag() {
+                start_search(app, String::new(), task_tx);
+                app.show_toast(&format!("Searching for {} manga", tag.name));
+            }
+        }
+        KeyCode::Esc if app.chapter_selection_anchor.is_some() => {
+            app.chapter_selection_anchor = None;
+        }
         KeyCode::Esc => {
             app.go_back();
         }
-        KeyCode::Left => {
-            if app.chapter_selected > 0 {
-                app.chapter_selected -= 1;
-                preload_chapter_thumbnails(app, app.chapter_selected, task_tx, cache);
-            }
+        KeyCode::Char('m') => {
+            app.toggle_selection_anchor();
         }
-        KeyCode::Right => {
-            if app.chapter_selected + 1 < app.chapters.len() {
-                app.chapter_selected += 1;
-                preload_chapter_thumbnails(app, app.chapter_selected, task_tx, cache);
-            }
+        KeyCode::Char('D') => {
+            download_chapter_selection(app, task_tx, cache);
         }
-        KeyCode::Up => {
-            if app.chapter_selected >= cols {
-                app.chapter_selected -= cols;
+        KeyCode::Char('u') => {
+            app.toggle_selection_read();
+        }
+        KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {
+            let mv = match key {
+                KeyCode::Left => GridMove::Left,
+                KeyCode::Right => GridMove::Right,
+                KeyCode::Up => GridMove::Up,
+                _ => GridMove::Down,
+            };
+            if app.move_chapter_cursor(mv) && app.selected_volume_header.is_none() {
                 preload_chapter_thumbnails(app, app.chapter_selected, task_tx, cache);
+                prefetch_adjacent_chapter_urls(app, cache);
             }
         }
-        KeyCode::Down => {
-            let new_idx = app.chapter_selected + cols;
-            if new_idx < app.chapters.len() {
-                app.chapter_selected = new_idx;
-                preload_chapter_thumbnails(app, app.chapter_selected, task_tx, cache);
+        KeyCode::Enter if app.selected_volume_header.is_some() => {
+            if let Some(volume) = app.selected_volume_header.clone() {
+                app.toggle_volume_collapsed(volume);
             }
         }
+        KeyCode::Char('g') => {
+            app.toggle_group_by_volume();
+        }
         KeyCode::Enter => {
             if let Some(chapter) = app.chapters.get(app.chapter_selected) {
                 if let Some(external_url) = &chapter.external_url {
@@ -681,60 +2032,393 @@ fn handle_detail_input(
             }
         }
         KeyCode::Char('b') => {
-            app.toggle_bookmark();
+            if let (Some(bookmarked), Some(manga)) = (app.toggle_bookmark(), &app.selected_manga) {
+                spawn_bookmark_push(app, manga.id.clone(), bookmarked, task_tx.clone());
+            }
+        }
+        KeyCode::Char('c') => {
+            if app.chapters.is_empty() {
+                app.show_toast("Chapters are still loading");
+            } else if let Some(idx) = app.next_unread_chapter() {
+                let chapter_id = app.chapters[idx].id.clone();
+                app.chapter_selected = idx;
+                app.open_reader(idx);
+                spawn_page_urls_loader(chapter_id, task_tx.clone(), cache.clone());
+            } else {
+                app.show_toast("All chapters read");
+            }
+        }
+        KeyCode::Char('x') => {
+            if app.chapters.is_empty() {
+                app.show_toast("Chapters are still loading");
+            } else if let Some(idx) = app.random_unread_chapter() {
+                let chapter = &app.chapters[idx];
+                let chapter_id = chapter.id.clone();
+                app.show_toast(&format!("Shuffled to {}", chapter.long_label()));
+                app.chapter_selected = idx;
+                app.open_reader(idx);
+                spawn_page_urls_loader(chapter_id, task_tx.clone(), cache.clone());
+            } else {
+                app.show_toast("All chapters read");
+            }
+        }
+        KeyCode::Char('i') => {
+            app.show_chapter_thumbnails = !app.show_chapter_thumbnails;
+            preload_chapter_thumbnails(app, app.chapter_selected, task_tx, cache);
+        }
+        KeyCode::Char('o') => {
+            if let Some(manga) = &app.selected_manga {
+                match source::for_id(&manga.id).manga_url(&manga.id) {
+                    Some(url) => open_in_browser(app, &url),
+                    None => app.show_toast("This manga has no web page"),
+                }
+            }
+        }
+        KeyCode::Char('s') => {
+            app.chapter_sort = app.chapter_sort.next();
+            app.show_toast(&format!("Chapters sorted {}", app.chapter_sort.label()));
+        }
+        KeyCode::Char('v') => {
+            if let Some(manga) = app.selected_manga.clone() {
+                reveal_cover(app, &manga, task_tx);
+            }
         }
         _ => {}
     }
 }
 
+/// Flips to the next page once auto-scroll has shown the current one for
+/// `auto_scroll_secs`. At the last page it moves on to the next chapter if
+/// `auto_scroll_next_chapter` is set, and stops otherwise.
+fn advance_auto_scroll(
+    app: &mut App,
+    task_tx: &mpsc::UnboundedSender<BackgroundTask>,
+    cache: &PageCache,
+    preloading_pages: &mut std::collections::HashSet<String>,
+) {
+    if !app.auto_scroll_due() {
+        return;
+    }
+
+    if app.next_page() {
+        if let Some(url) = app.reader.page_urls.get(app.reader.current_page) {
+            spawn_page_image_loader(url.clone(), app.config.retry_policy(), task_tx.clone(), cache.clone());
+        }
+        preload_upcoming_pages(
+            &app.reader,
+            preloading_pages,
+            task_tx,
+            cache,
+        );
+    } else if app.config.auto_scroll_next_chapter && app.next_chapter() {
+        if let Some(chapter) = app.reader.chapters.get(app.reader.current_chapter_idx) {
+            spawn_page_urls_loader(chapter.id.clone(), task_tx.clone(), cache.clone());
+        }
+    } else {
+        app.reader.auto_scroll = None;
+        app.show_toast("Auto-scroll stopped at the end of the chapter");
+    }
+}
+
+/// Starts a batch download of the marked chapter range, or of the selected chapter
+/// when no range is marked, saving each as a CBZ. External and local chapters are
+/// skipped. Cancels the download instead if one is already running.
+fn download_chapter_selection(
+    app: &mut App,
+    task_tx: &mpsc::UnboundedSender<BackgroundTask>,
+    cache: &PageCache,
+) {
+    if let Some(batch) = app.batch_download.take() {
+        batch.cancel();
+        app.show_toast(&format!("Download cancelled after {} of {} chapters", batch.done, batch.total));
+        return;
+    }
+    let Some(manga) = app.selected_manga.as_ref() else {
+        return;
+    };
+    if app.chapters.is_empty() || app.selected_volume_header.is_some() {
+        return;
+    }
+
+    let root = app.config.downloads_dir();
+    let selection = app
+        .chapter_selection()
+        .unwrap_or_else(|| vec![app.chapter_selected]);
+    let chapters: Vec<DownloadChapter> = selection
+        .iter()
+        .filter_map(|&idx| app.chapters.get(idx))
+        .filter(|c| c.external_url.is_none() && !local::is_local(&c.id))
+        .map(|c| DownloadChapter::new(&root, &manga.title, c))
+        .collect();
+    if chapters.is_empty() {
+        app.show_toast("Nothing to download: external and local chapters can't be saved");
+        return;
+    }
+
+    app.chapter_selection_anchor = None;
+    let total = chapters.len();
+    start_batch_download(app, PendingDownload::new(chapters), task_tx, cache);
+    app.show_toast(&format!("Downloading {} chapters", total));
+}
+
+/// Unblurs a cover hidden by `blur_covers`, fetching the original again.
+fn reveal_cover(app: &mut App, manga: &Manga, task_tx: &mpsc::UnboundedSender<BackgroundTask>) {
+    if app.reveal_cover(manga) {
+        spawn_cover_loaders(std::slice::from_ref(manga), 0, 1, task_tx.clone());
+    }
+}
+
+fn open_in_browser(app: &mut App, url: &str) {
+    if let Err(e) = webbrowser::open(url) {
+        log::error!("Failed to open {} in browser: {}", url, e);
+        app.show_toast("Couldn't open the browser");
+    }
+}
+
 fn preload_chapter_thumbnails(
     app: &App,
     current_idx: usize,
     task_tx: &mpsc::UnboundedSender<BackgroundTask>,
     cache: &PageCache,
 ) {
+    if !app.show_chapter_thumbnails {
+        return;
+    }
+
     // Only load thumbnail for the currently selected chapter to avoid rate limiting
-    if let Some(chapter) = app.chapters.get(current_idx) {
-        if chapter.external_url.is_none() && !app.chapter_thumbnails.contains_key(&chapter.id) {
-            spawn_chapter_thumbnail_loader(
-                chapter.id.clone(),
-                task_tx.clone(),
-                cache.clone(),
-            );
+    if let Some(chapter) = app.chapters.get(current_idx)
+        && chapter.external_url.is_none()
+        && !app.chapter_thumbnails.contains_key(&chapter.id)
+    {
+        spawn_chapter_thumbnail_loader(
+            chapter.id.clone(),
+            task_tx.clone(),
+            cache.clone(),
+        );
+    }
+}
+
+/// Looks up the page lists of the selected chapter and the cards either side of it, so
+/// opening one of them doesn't wait on the server. Requests go through the usual fetch limit.
+fn prefetch_adjacent_chapter_urls(app: &mut App, cache: &PageCache) {
+    if !app.config.prefetch_chapter_urls {
+        return;
+    }
+
+    for idx in app.chapters_near_cursor(3) {
+        let chapter = &app.chapters[idx];
+        if chapter.external_url.is_none() && app.chapter_url_prefetches.insert(chapter.id.clone()) {
+            spawn_chapter_urls_prefetch(chapter.id.clone(), cache.clone());
         }
     }
 }
 
+fn spawn_chapter_urls_prefetch(chapter_id: String, cache: PageCache) {
+    tokio::spawn(async move {
+        if cached_page_urls(&chapter_id, &cache).await.is_none_or(|urls| urls.is_empty()) {
+            log::debug!("Couldn't prefetch the page list of chapter {}", chapter_id);
+        }
+    });
+}
+
+/// Pages skipped by Shift+Left/Right
+const FAST_PAGE_STEP: usize = 5;
+
 fn handle_reader_input(
     app: &mut App,
     key: KeyCode,
+    modifiers: KeyModifiers,
     task_tx: &mpsc::UnboundedSender<BackgroundTask>,
     cache: &PageCache,
     preloading_pages: &mut std::collections::HashSet<String>,
 ) {
+    // Any other key pauses auto-scroll, and still does what it normally would
+    if app.reader.auto_scroll.is_some() && key != KeyCode::Char('a') {
+        app.toggle_auto_scroll();
+    }
+
+    if let Some(list) = app.reader.chapter_list.as_mut() {
+        let last = app.reader.chapters.len().saturating_sub(1);
+        let selected = list.selected().unwrap_or(0);
+        match key {
+            KeyCode::Up => list.select(Some(selected.saturating_sub(1))),
+            KeyCode::Down => list.select(Some((selected + 1).min(last))),
+            KeyCode::Esc | KeyCode::Char('c') => app.reader.chapter_list = None,
+            KeyCode::Enter => {
+                app.reader.chapter_list = None;
+                if let Some(chapter) = app.reader.chapters.get(selected) {
+                    if let Some(external_url) = chapter.external_url.clone() {
+                        open_in_browser(app, &external_url);
+                    } else {
+                        let chapter_id = chapter.id.clone();
+                        if app.go_to_chapter(selected) {
+                            spawn_page_urls_loader(chapter_id, task_tx.clone(), cache.clone());
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        return;
+    }
+
     match key {
+        KeyCode::Char('c') => {
+            app.toggle_chapter_list();
+        }
         KeyCode::Esc => {
-            app.go_back();
+            if !app.reader.page_jump_input.is_empty() {
+                app.reader.page_jump_input.clear();
+            } else {
+                app.go_back();
+            }
+        }
+        KeyCode::Char('o') => {
+            if let Some(chapter) = app.reader.chapters.get(app.reader.current_chapter_idx) {
+                match source::for_id(&chapter.id).chapter_url(&chapter.id) {
+                    Some(url) => open_in_browser(app, &url),
+                    None => app.show_toast("This chapter has no web page"),
+                }
+            }
+        }
+        KeyCode::Char('b') => app.report_broken_chapter(),
+        KeyCode::Char('D') => {
+            if app.reader.prefetch.is_some() {
+                app.show_toast("Already prefetching this chapter");
+            } else if app.reader.page_urls.is_empty() {
+                app.show_toast("Page list not loaded yet");
+            } else if let Some(chapter) = app.reader.chapters.get(app.reader.current_chapter_idx) {
+                let chapter_id = chapter.id.clone();
+                let handle = spawn_chapter_prefetch(
+                    chapter_id.clone(),
+                    app.reader.page_urls.clone(),
+                    task_tx.clone(),
+                    cache.clone(),
+                );
+                app.reader.prefetch = Some(ChapterPrefetch {
+                    chapter_id,
+                    done: 0,
+                    failed: 0,
+                    total: app.reader.page_urls.len(),
+                    handle,
+                });
+            }
+        }
+        KeyCode::Char('i') => {
+            app.toggle_invert();
+        }
+        KeyCode::Char('a') => {
+            app.toggle_auto_scroll();
+        }
+        KeyCode::Char('f') => {
+            app.cycle_page_fit();
+        }
+        KeyCode::Up | KeyCode::Down => {
+            let forward = key == KeyCode::Down;
+            match app.scroll_page(forward) {
+                PageScroll::Page => {
+                    if let Some(url) = app.reader.page_urls.get(app.reader.current_page) {
+                        spawn_page_image_loader(url.clone(), app.config.retry_policy(), task_tx.clone(), cache.clone());
+                    }
+                    if forward {
+                        preload_upcoming_pages(&app.reader, preloading_pages, task_tx, cache);
+                    }
+                }
+                PageScroll::Chapter => {
+                    if let Some(chapter) = app.reader.chapters.get(app.reader.current_chapter_idx) {
+                        spawn_page_urls_loader(chapter.id.clone(), task_tx.clone(), cache.clone());
+                    }
+                }
+                PageScroll::Stopped
+                    if forward
+                        && app.reader.fit != PageFit::Whole
+                        && app.reader.current_chapter_idx + 1 >= app.reader.chapters.len()
+                        && app.reader.current_page + 1 >= app.reader.page_urls.len() =>
+                {
+                    app.show_toast(app.last_chapter_message());
+                }
+                PageScroll::Stopped | PageScroll::Scrolled => {}
+            }
+        }
+        KeyCode::Char('s') => {
+            app.reader.show_sidebar = !app.reader.show_sidebar;
+        }
+        KeyCode::Char('y') => match app.reader.page_urls.get(app.reader.current_page) {
+            Some(url) => match ui::clipboard::copy(url) {
+                Ok(()) => app.show_toast("Copied the page URL"),
+                Err(e) => app.show_toast(&format!("Couldn't copy the page URL: {}", e)),
+            },
+            None => app.show_toast("No page URL yet"),
+        },
+        KeyCode::Char('t') => {
+            app.reader.show_thumbnails = !app.reader.show_thumbnails;
+            if app.reader.show_thumbnails {
+                for url in &app.reader.page_urls {
+                    if !app.reader.page_thumbnails.contains_key(url) {
+                        spawn_page_thumbnail_loader(url.clone(), task_tx.clone(), cache.clone());
+                    }
+                }
+            }
+        }
+        KeyCode::Char(c) if c.is_ascii_digit() && app.reader.page_jump_input.len() < 4 => {
+            app.reader.page_jump_input.push(c);
+        }
+        KeyCode::Backspace => {
+            app.reader.page_jump_input.pop();
+        }
+        KeyCode::Enter => {
+            let input = std::mem::take(&mut app.reader.page_jump_input);
+            if let Ok(page) = input.parse::<usize>()
+                && page > 0
+                && app.jump_to_page(page - 1)
+            {
+                if let Some(url) = app.reader.page_urls.get(app.reader.current_page) {
+                    spawn_page_image_loader(url.clone(), app.config.retry_policy(), task_tx.clone(), cache.clone());
+                }
+                preload_upcoming_pages(
+                    &app.reader,
+                    preloading_pages,
+                    task_tx,
+                    cache,
+                );
+            }
         }
         KeyCode::Left => {
-            if app.prev_page() {
+            let moved = if modifiers.contains(KeyModifiers::SHIFT) {
+                app.prev_page_by(FAST_PAGE_STEP)
+            } else {
+                app.prev_page()
+            };
+            if moved {
                 if let Some(url) = app.reader.page_urls.get(app.reader.current_page) {
-                    spawn_page_image_loader(url.clone(), task_tx.clone(), cache.clone());
+                    spawn_page_image_loader(url.clone(), app.config.retry_policy(), task_tx.clone(), cache.clone());
                 }
+            } else if app.retreat_past_chapter_start()
+                && let Some(chapter) = app.reader.chapters.get(app.reader.current_chapter_idx)
+            {
+                spawn_page_urls_loader(chapter.id.clone(), task_tx.clone(), cache.clone());
             }
         }
         KeyCode::Right => {
-            if app.next_page() {
+            let moved = if modifiers.contains(KeyModifiers::SHIFT) {
+                app.next_page_by(FAST_PAGE_STEP)
+            } else {
+                app.next_page()
+            };
+            if moved {
                 if let Some(url) = app.reader.page_urls.get(app.reader.current_page) {
-                    spawn_page_image_loader(url.clone(), task_tx.clone(), cache.clone());
+                    spawn_page_image_loader(url.clone(), app.config.retry_policy(), task_tx.clone(), cache.clone());
                 }
                 preload_upcoming_pages(
-                    &app.reader.page_urls,
-                    app.reader.current_page,
+                    &app.reader,
                     preloading_pages,
                     task_tx,
                     cache,
                 );
+            } else if app.advance_past_chapter_end()
+                && let Some(chapter) = app.reader.chapters.get(app.reader.current_chapter_idx)
+            {
+                spawn_page_urls_loader(chapter.id.clone(), task_tx.clone(), cache.clone());
             }
         }
         KeyCode::Char('n') => {
@@ -742,67 +2426,84 @@ fn handle_reader_input(
                 if let Some(chapter) = app.reader.chapters.get(app.reader.current_chapter_idx) {
                     spawn_page_urls_loader(chapter.id.clone(), task_tx.clone(), cache.clone());
                 }
+            } else if app.reader.current_chapter_idx + 1 >= app.reader.chapters.len() {
+                app.show_toast(app.last_chapter_message());
             }
         }
         KeyCode::Char('p') => {
-            if app.prev_chapter() {
-                if let Some(chapter) = app.reader.chapters.get(app.reader.current_chapter_idx) {
-                    spawn_page_urls_loader(chapter.id.clone(), task_tx.clone(), cache.clone());
-                }
+            if app.prev_chapter()
+                && let Some(chapter) = app.reader.chapters.get(app.reader.current_chapter_idx)
+            {
+                spawn_page_urls_loader(chapter.id.clone(), task_tx.clone(), cache.clone());
             }
         }
-        KeyCode::Char('r') => {
-            if app.reader.error.is_some() {
-                app.reader.loading = true;
-                app.reader.error = None;
-                if app.reader.page_urls.is_empty() {
-                    if let Some(chapter) = app.reader.chapters.get(app.reader.current_chapter_idx) {
-                        spawn_page_urls_loader(chapter.id.clone(), task_tx.clone(), cache.clone());
-                    }
-                } else if let Some(url) = app.reader.page_urls.get(app.reader.current_page) {
-                    spawn_page_image_loader(url.clone(), task_tx.clone(), cache.clone());
+        KeyCode::Char('r') if app.reader.error.is_some() => {
+            app.reader.loading = true;
+            app.reader.error = None;
+            if app.reader.page_urls.is_empty() {
+                if let Some(chapter) = app.reader.chapters.get(app.reader.current_chapter_idx) {
+                    spawn_page_urls_loader(chapter.id.clone(), task_tx.clone(), cache.clone());
                 }
+            } else if let Some(url) = app.reader.page_urls.get(app.reader.current_page) {
+                spawn_page_image_loader(url.clone(), app.config.retry_policy(), task_tx.clone(), cache.clone());
             }
         }
         _ => {}
     }
 }
 
+/// Loads covers for cards from `behind` before `offset` to `ahead` after it, skipping ones
+/// already loaded or on the way. The card at `offset` itself goes ahead of the queue.
 fn preload_covers(
     mangas: &[Manga],
     offset: usize,
+    (ahead, behind): (usize, usize),
     pending: &mut std::collections::HashSet<String>,
-    loaded: &std::collections::HashMap<String, ratatui_image::protocol::StatefulProtocol>,
+    loaded: &ui::lru::LruMap<ratatui_image::protocol::StatefulProtocol>,
     tx: mpsc::UnboundedSender<BackgroundTask>,
 ) {
-    for manga in mangas.iter().skip(offset).take(8) {
-        if !loaded.contains_key(&manga.id) && !pending.contains(&manga.id) {
-            pending.insert(manga.id.clone());
-            let manga_id = manga.id.clone();
-            let cover_url = manga.cover_url.clone();
-            let tx = tx.clone();
-
-            tokio::spawn(async move {
-                if let Some(image) = fetch_cover_image(&cover_url).await {
-                    let _ = tx.send(BackgroundTask::CoverLoaded { manga_id, image });
-                }
-            });
+    let start = offset.saturating_sub(behind);
+    for (idx, manga) in mangas.iter().enumerate().skip(start).take(offset - start + ahead) {
+        if !loaded.contains_key(&manga.id) && pending.insert(manga.id.clone()) {
+            spawn_cover_load(manga, idx == offset, tx.clone());
         }
     }
 }
 
+/// Pages kept preloaded past the current one while the user is reading
+const PRELOAD_AHEAD: usize = 3;
+
 fn preload_upcoming_pages(
-    page_urls: &[String],
-    current_page: usize,
+    reader: &ReaderState,
     preloading: &mut std::collections::HashSet<String>,
     tx: &mpsc::UnboundedSender<BackgroundTask>,
     cache: &PageCache,
 ) {
-    const PRELOAD_AHEAD: usize = 3;
+    preload_pages_ahead(reader, PRELOAD_AHEAD, preloading, tx, cache);
+}
+
+/// How far ahead to preload: further once no key has been pressed for
+/// `idle_preload_secs`, capped so the extra pages still fit in the memory cache.
+fn preload_ahead(app: &App, last_input: Instant) -> usize {
+    let idle_after = app.config.idle_preload_secs;
+    if idle_after > 0 && last_input.elapsed().as_secs() >= idle_after {
+        app.config.idle_preload_pages.clamp(PRELOAD_AHEAD, MAX_MEMORY_PAGES / 2)
+    } else {
+        PRELOAD_AHEAD
+    }
+}
 
-    for url in page_urls.iter().skip(current_page + 1).take(PRELOAD_AHEAD) {
-        if !preloading.contains(url) {
-            preloading.insert(url.clone());
+/// Starts preloading the pages after the current one that aren't cached or already on
+/// their way. `preloading` holds the pages in flight.
+fn preload_pages_ahead(
+    reader: &ReaderState,
+    ahead: usize,
+    preloading: &mut std::collections::HashSet<String>,
+    tx: &mpsc::UnboundedSender<BackgroundTask>,
+    cache: &PageCache,
+) {
+    for url in reader.page_urls.iter().skip(reader.current_page + 1).take(ahead) {
+        if !reader.cached_pages.contains(url) && preloading.insert(url.clone()) {
             spawn_page_preloader(url.clone(), tx.clone(), cache.clone());
         }
     }
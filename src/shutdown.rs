@@ -0,0 +1,79 @@
+use std::future::Future;
+use tokio::sync::watch;
+
+/// Runs a cleanup when dropped, so it happens on every way out of `main`: a normal quit,
+/// an error returned with `?`, a panic unwinding, or an interrupt.
+pub struct OnDrop<F: FnOnce()> {
+    cleanup: Option<F>,
+}
+
+impl<F: FnOnce()> OnDrop<F> {
+    pub fn new(cleanup: F) -> Self {
+        Self { cleanup: Some(cleanup) }
+    }
+}
+
+impl<F: FnOnce()> Drop for OnDrop<F> {
+    fn drop(&mut self) {
+        if let Some(cleanup) = self.cleanup.take() {
+            cleanup();
+        }
+    }
+}
+
+/// Starts listening for SIGINT (Ctrl-C sent from outside the terminal, since raw mode
+/// turns a typed one into a key event). The receiver flips to true once it arrives.
+/// Installed before the first await, so an interrupt during the startup fetches doesn't
+/// kill the process with the terminal still in raw mode.
+pub fn listen_for_interrupt() -> watch::Receiver<bool> {
+    let (tx, rx) = watch::channel(false);
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            log::debug!("Interrupted, quitting");
+            let _ = tx.send(true);
+        }
+    });
+    rx
+}
+
+/// Resolves once the interrupt has arrived. Never resolves if the listener went away
+/// without one.
+pub async fn interrupted(interrupt: &mut watch::Receiver<bool>) {
+    if interrupt.wait_for(|&interrupted| interrupted).await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Runs `work` unless an interrupt arrives first, in which case it's dropped and `None`
+/// is returned.
+pub async fn until_interrupted<T>(work: impl Future<Output = T>, interrupt: &mut watch::Receiver<bool>) -> Option<T> {
+    tokio::select! {
+        output = work => Some(output),
+        _ = interrupted(interrupt) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[tokio::test]
+    async fn test_cleanup_runs_when_interrupted() {
+        let restored = AtomicBool::new(false);
+        let (tx, mut rx) = watch::channel(false);
+
+        let finished = until_interrupted(async { 1 }, &mut rx).await;
+        assert_eq!(finished, Some(1));
+
+        let interrupted_work = {
+            let _guard = OnDrop::new(|| restored.store(true, Ordering::Relaxed));
+            tx.send(true).unwrap();
+            let work = until_interrupted(std::future::pending::<()>(), &mut rx).await;
+            assert!(!restored.load(Ordering::Relaxed));
+            work
+        };
+        assert_eq!(interrupted_work, None);
+        assert!(restored.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::HashSet;
+
+use crate::backend::mangadex::Chapter;
+
+/// A chapter's volume, `None` for chapters not assigned to one.
+pub type VolumeKey = Option<String>;
+
+/// One row of the detail view's chapter grid.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GridRow {
+    Header {
+        volume: VolumeKey,
+        count: usize,
+        collapsed: bool,
+    },
+    /// Indices into the chapter list
+    Chapters(Vec<usize>),
+}
+
+/// Something the grid cursor can rest on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GridItem {
+    Header(VolumeKey),
+    Chapter(usize),
+}
+
+/// Order of chapters in the detail grid. Chapters stay in feed order underneath, so grid
+/// indices always point into `App::chapters`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChapterSort {
+    #[default]
+    Ascending,
+    Descending,
+    /// Unread chapters first, read ones pushed to the end, each by number
+    UnreadFirst,
+}
+
+impl ChapterSort {
+    pub fn next(self) -> Self {
+        match self {
+            ChapterSort::Ascending => ChapterSort::Descending,
+            ChapterSort::Descending => ChapterSort::UnreadFirst,
+            ChapterSort::UnreadFirst => ChapterSort::Ascending,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ChapterSort::Ascending => "oldest first",
+            ChapterSort::Descending => "newest first",
+            ChapterSort::UnreadFirst => "unread first",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum GridMove {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl GridRow {
+    fn items(&self) -> Vec<GridItem> {
+        match self {
+            GridRow::Header { volume, .. } => vec![GridItem::Header(volume.clone())],
+            GridRow::Chapters(indices) => indices.iter().map(|&i| GridItem::Chapter(i)).collect(),
+        }
+    }
+}
+
+pub fn volume_key(chapter: &Chapter) -> VolumeKey {
+    chapter.volume.clone().filter(|v| !v.is_empty())
+}
+
+pub fn volume_label(volume: &VolumeKey) -> String {
+    match volume {
+        Some(v) => format!("Volume {}", v),
+        None => "No Volume".to_string(),
+    }
+}
+
+/// Chapter indices in display order.
+pub fn sorted_order(chapters: &[Chapter], sort: ChapterSort, is_read: impl Fn(&Chapter) -> bool) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..chapters.len()).collect();
+    let by_number = |a: &usize, b: &usize| chapters[*a].sort_key().total_cmp(&chapters[*b].sort_key());
+    match sort {
+        ChapterSort::Ascending => order.sort_by(by_number),
+        ChapterSort::Descending => order.sort_by(|a, b| by_number(b, a)),
+        ChapterSort::UnreadFirst => order.sort_by(|a, b| {
+            is_read(&chapters[*a])
+                .cmp(&is_read(&chapters[*b]))
+                .then_with(|| by_number(a, b))
+        }),
+    }
+    order
+}
+
+/// Chapter indices grouped by volume in order of first appearance, with "No Volume" last.
+fn group_by_volume(chapters: &[Chapter], order: &[usize]) -> Vec<(VolumeKey, Vec<usize>)> {
+    let mut groups: Vec<(VolumeKey, Vec<usize>)> = Vec::new();
+    for &idx in order {
+        let key = volume_key(&chapters[idx]);
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, indices)) => indices.push(idx),
+            None => groups.push((key, vec![idx])),
+        }
+    }
+    groups.sort_by_key(|(key, _)| key.is_none());
+    groups
+}
+
+/// Chapter indices in the order the grid shows them, including ones in collapsed volumes.
+pub fn display_order(chapters: &[Chapter], order: &[usize], grouped: bool) -> Vec<usize> {
+    if !grouped {
+        return order.to_vec();
+    }
+    group_by_volume(chapters, order)
+        .into_iter()
+        .flat_map(|(_, indices)| indices)
+        .collect()
+}
+
+/// Chapter indices shown from `anchor` to `cursor`, inclusive, in display order. Both are
+/// indices into the chapter list, so the range follows the grid rather than the feed.
+pub fn range_between(display: &[usize], anchor: usize, cursor: usize) -> Vec<usize> {
+    let position = |idx| display.iter().position(|&i| i == idx);
+    match (position(anchor), position(cursor)) {
+        (Some(a), Some(c)) => display[a.min(c)..=a.max(c)].to_vec(),
+        _ => vec![cursor],
+    }
+}
+
+/// Up to `count` chapter indices around `cursor` in display order, nearest first: the
+/// cursor itself, then alternately the next and previous cards.
+pub fn nearest_first(display: &[usize], cursor: usize, count: usize) -> Vec<usize> {
+    let Some(center) = display.iter().position(|&i| i == cursor) else {
+        return Vec::new();
+    };
+    let mut nearby = vec![cursor];
+    for distance in 1..display.len() {
+        if nearby.len() >= count {
+            break;
+        }
+        nearby.extend(display.get(center + distance));
+        nearby.extend(center.checked_sub(distance).map(|i| display[i]));
+    }
+    nearby.truncate(count);
+    nearby
+}
+
+/// Lays chapters out in `order`, in rows of `cols`, under collapsible volume headers when grouping.
+pub fn build_rows(
+    chapters: &[Chapter],
+    order: &[usize],
+    grouped: bool,
+    collapsed: &HashSet<VolumeKey>,
+    cols: usize,
+) -> Vec<GridRow> {
+    let cols = cols.max(1);
+    let chapter_rows = |indices: &[usize]| {
+        indices
+            .chunks(cols)
+            .map(|chunk| GridRow::Chapters(chunk.to_vec()))
+            .collect::<Vec<_>>()
+    };
+
+    if !grouped {
+        return chapter_rows(order);
+    }
+
+    let mut rows = Vec::new();
+    for (volume, indices) in group_by_volume(chapters, order) {
+        let is_collapsed = collapsed.contains(&volume);
+        rows.push(GridRow::Header {
+            volume,
+            count: indices.len(),
+            collapsed: is_collapsed,
+        });
+        if !is_collapsed {
+            rows.extend(chapter_rows(&indices));
+        }
+    }
+    rows
+}
+
+/// Row and column of an item, if it's visible.
+pub fn locate(rows: &[GridRow], item: &GridItem) -> Option<(usize, usize)> {
+    rows.iter().enumerate().find_map(|(row_idx, row)| {
+        row.items()
+            .iter()
+            .position(|i| i == item)
+            .map(|col| (row_idx, col))
+    })
+}
+
+/// Where the cursor lands after a move, or `None` at the edges of the grid. With `wrap`,
+/// Left from the first item goes to the last and Right from the last goes to the first.
+pub fn step(rows: &[GridRow], current: &GridItem, mv: GridMove, wrap: bool) -> Option<GridItem> {
+    let (row, col) = locate(rows, current)?;
+
+    match mv {
+        GridMove::Left | GridMove::Right => {
+            let flat: Vec<GridItem> = rows.iter().flat_map(|r| r.items()).collect();
+            let pos = flat.iter().position(|i| i == current)?;
+            let target = match mv {
+                GridMove::Left if pos == 0 && wrap => flat.len() - 1,
+                GridMove::Left => pos.checked_sub(1)?,
+                _ if pos + 1 == flat.len() && wrap => 0,
+                _ => pos + 1,
+            };
+            flat.get(target).filter(|item| *item != current).cloned()
+        }
+        GridMove::Up | GridMove::Down => {
+            let target_row = match mv {
+                GridMove::Up => row.checked_sub(1)?,
+                _ => row + 1,
+            };
+            let items = rows.get(target_row)?.items();
+            items.get(col.min(items.len().saturating_sub(1))).cloned()
+        }
+    }
+}
+
+/// Info panel width beside the grid when `info_panel_percent` isn't set
+const DEFAULT_INFO_WIDTH: u16 = 40;
+/// Narrowest the info panel gets before the cover and stats stop being readable
+const MIN_INFO_WIDTH: u16 = 24;
+/// Narrowest the chapter grid gets, room for one chapter card
+const MIN_GRID_WIDTH: u16 = 20;
+
+/// Columns of the detail view's info panel out of `total`: `percent` of it when set,
+/// otherwise a fixed width. Neither the panel nor the grid beside it shrinks below a
+/// usable width while there's room for both; when there isn't, the grid wins.
+pub fn info_panel_width(total: u16, percent: Option<u16>) -> u16 {
+    let wanted = match percent {
+        Some(percent) => (total as u32 * percent.min(100) as u32 / 100) as u16,
+        None => DEFAULT_INFO_WIDTH,
+    };
+    wanted
+        .min(total.saturating_sub(MIN_GRID_WIDTH))
+        .max(MIN_INFO_WIDTH.min(total.saturating_sub(MIN_GRID_WIDTH)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_grid_navigation() {
+        let chapter = |volume: Option<&str>| Chapter {
+            id: String::new(),
+            chapter: String::new(),
+            title: String::new(),
+            volume: volume.map(str::to_string),
+            pages: 1,
+            external_url: None,
+            groups: vec![],
+            language: String::new(),
+            other_language: false,
+        };
+        let chapters = vec![
+            chapter(None),
+            chapter(Some("1")),
+            chapter(Some("1")),
+            chapter(Some("1")),
+            chapter(Some("2")),
+        ];
+        let v1 = Some("1".to_string());
+        let order: Vec<usize> = (0..chapters.len()).collect();
+
+        let rows = build_rows(&chapters, &order, true, &HashSet::new(), 2);
+        assert_eq!(
+            rows,
+            vec![
+                GridRow::Header { volume: v1.clone(), count: 3, collapsed: false },
+                GridRow::Chapters(vec![1, 2]),
+                GridRow::Chapters(vec![3]),
+                GridRow::Header { volume: Some("2".to_string()), count: 1, collapsed: false },
+                GridRow::Chapters(vec![4]),
+                GridRow::Header { volume: None, count: 1, collapsed: false },
+                GridRow::Chapters(vec![0]),
+            ]
+        );
+
+        // Down from the second column clamps to the shorter row, then crosses into the next volume
+        assert_eq!(step(&rows, &GridItem::Chapter(2), GridMove::Down, false), Some(GridItem::Chapter(3)));
+        assert_eq!(
+            step(&rows, &GridItem::Chapter(3), GridMove::Down, false),
+            Some(GridItem::Header(Some("2".to_string())))
+        );
+        assert_eq!(step(&rows, &GridItem::Chapter(1), GridMove::Left, false), Some(GridItem::Header(v1.clone())));
+        assert_eq!(step(&rows, &GridItem::Chapter(0), GridMove::Right, false), None);
+        assert_eq!(step(&rows, &GridItem::Chapter(0), GridMove::Right, true), Some(GridItem::Header(v1.clone())));
+        assert_eq!(step(&rows, &GridItem::Header(v1.clone()), GridMove::Left, true), Some(GridItem::Chapter(0)));
+        assert_eq!(step(&rows, &GridItem::Header(v1.clone()), GridMove::Up, true), None);
+
+        let collapsed = HashSet::from([v1.clone()]);
+        let rows = build_rows(&chapters, &order, true, &collapsed, 2);
+        assert_eq!(step(&rows, &GridItem::Header(v1), GridMove::Right, false), Some(GridItem::Header(Some("2".to_string()))));
+        assert_eq!(locate(&rows, &GridItem::Chapter(1)), None);
+    }
+
+    #[test]
+    fn test_sorted_order_unread_first() {
+        let chapter = |id: &str, number: &str| Chapter {
+            id: id.to_string(),
+            chapter: number.to_string(),
+            title: String::new(),
+            volume: None,
+            pages: 1,
+            external_url: None,
+            groups: vec![],
+            language: String::new(),
+            other_language: false,
+        };
+        let chapters = vec![chapter("a", "1"), chapter("b", "2"), chapter("c", "3"), chapter("d", "4")];
+        let read = |c: &Chapter| c.id == "a" || c.id == "c";
+
+        assert_eq!(sorted_order(&chapters, ChapterSort::UnreadFirst, read), vec![1, 3, 0, 2]);
+        assert_eq!(sorted_order(&chapters, ChapterSort::Descending, read), vec![3, 2, 1, 0]);
+
+        // Navigation follows the sorted order, but indices still point into `chapters`
+        let order = sorted_order(&chapters, ChapterSort::UnreadFirst, read);
+        let rows = build_rows(&chapters, &order, false, &HashSet::new(), 2);
+        assert_eq!(step(&rows, &GridItem::Chapter(3), GridMove::Right, false), Some(GridItem::Chapter(0)));
+
+        // A range marked on screen covers what's between its ends there, not in the feed
+        let display = display_order(&chapters, &order, false);
+        assert_eq!(range_between(&display, 3, 0), vec![3, 0]);
+        assert_eq!(range_between(&display, 0, 1), vec![1, 3, 0]);
+
+        // Neighbours are the cards beside the cursor on screen
+        assert_eq!(nearest_first(&display, 3, 3), vec![3, 0, 1]);
+        assert_eq!(nearest_first(&display, 2, 10), vec![2, 0, 3, 1]);
+        assert_eq!(nearest_first(&display, 1, 0), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_info_panel_width_keeps_both_panels_usable() {
+        assert_eq!(info_panel_width(200, None), 40);
+        assert_eq!(info_panel_width(200, Some(30)), 60);
+        // Too small a share still leaves the cover readable
+        assert_eq!(info_panel_width(200, Some(5)), 24);
+        // Too large a share still leaves room for the grid
+        assert_eq!(info_panel_width(100, Some(95)), 80);
+        // A narrow terminal squeezes the info panel first
+        assert_eq!(info_panel_width(50, None), 30);
+        assert_eq!(info_panel_width(30, None), 10);
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+/// A string-keyed map that drops its least recently used entry once it exceeds `capacity`.
+///
+/// Used for the image protocols built for covers and thumbnails, which are large and would
+/// otherwise pile up over a long browsing session. Reads through `get_mut` count as a use,
+/// so anything drawn each frame stays resident.
+pub struct LruMap<V> {
+    entries: HashMap<String, (V, u64)>,
+    capacity: usize,
+    clock: u64,
+}
+
+impl<V> LruMap<V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity: capacity.max(1),
+            clock: 0,
+        }
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    pub fn insert(&mut self, key: String, value: V) {
+        let now = self.tick();
+        self.entries.insert(key, (value, now));
+
+        while self.entries.len() > self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(k, _)| k.clone());
+            match oldest {
+                Some(key) => {
+                    self.entries.remove(&key);
+                }
+                None => break,
+            }
+        }
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        let now = self.tick();
+        self.entries.get_mut(key).map(|(value, used)| {
+            *used = now;
+            value
+        })
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<V> {
+        self.entries.remove(key).map(|(value, _)| value)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut map = LruMap::new(2);
+        map.insert("a".to_string(), 1);
+        map.insert("b".to_string(), 2);
+
+        // Touching "a" makes "b" the eviction candidate
+        assert_eq!(map.get_mut("a"), Some(&mut 1));
+        map.insert("c".to_string(), 3);
+
+        assert!(map.contains_key("a"));
+        assert!(!map.contains_key("b"));
+        assert!(map.contains_key("c"));
+    }
+}
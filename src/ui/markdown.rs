@@ -0,0 +1,216 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+};
+
+/// Converts the small markdown subset used in MangaDex descriptions into styled text.
+///
+/// Handles headings, bullet lists, horizontal rules, `**bold**`, `*italic*`,
+/// `[links](url)` and backslash escapes. Anything else passes through as-is.
+pub fn markdown_to_text(source: &str) -> Text<'static> {
+    let mut lines: Vec<Line<'static>> = Vec::new();
+
+    for raw_line in source.lines() {
+        let line = raw_line.trim();
+
+        if line.is_empty() {
+            // Collapse runs of blank lines into one
+            if lines.last().is_some_and(|l| !l.spans.is_empty()) {
+                lines.push(Line::default());
+            }
+            continue;
+        }
+
+        if is_rule(line) {
+            lines.push(Line::from(Span::styled(
+                "────────",
+                Style::default().fg(Color::DarkGray),
+            )));
+            continue;
+        }
+
+        if let Some(heading) = strip_heading(line) {
+            lines.push(parse_inline(heading, Style::default().add_modifier(Modifier::BOLD)));
+            continue;
+        }
+
+        if let Some(item) = strip_bullet(line) {
+            let mut parsed = parse_inline(item, Style::default());
+            parsed.spans.insert(0, Span::raw("• "));
+            lines.push(parsed);
+            continue;
+        }
+
+        lines.push(parse_inline(line, Style::default()));
+    }
+
+    while lines.last().is_some_and(|l| l.spans.is_empty()) {
+        lines.pop();
+    }
+
+    Text::from(lines)
+}
+
+/// Markdown flattened to plain text, for places too small to show styling.
+pub fn markdown_to_plain(source: &str) -> String {
+    markdown_to_text(source)
+        .lines
+        .iter()
+        .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn is_rule(line: &str) -> bool {
+    line.len() >= 3
+        && ['-', '*', '_'].iter().any(|&c| line.chars().all(|x| x == c || x == ' '))
+}
+
+fn strip_heading(line: &str) -> Option<&str> {
+    let rest = line.trim_start_matches('#');
+    if rest.len() < line.len() && rest.starts_with(' ') {
+        Some(rest.trim_start())
+    } else {
+        None
+    }
+}
+
+fn strip_bullet(line: &str) -> Option<&str> {
+    ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix))
+}
+
+fn parse_inline(line: &str, base: Style) -> Line<'static> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut spans = Vec::new();
+    let mut buffer = String::new();
+    let mut bold = false;
+    let mut italic = false;
+    let mut i = 0;
+
+    let style_for = |bold: bool, italic: bool| {
+        let mut style = base;
+        if bold {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if italic {
+            style = style.add_modifier(Modifier::ITALIC);
+        }
+        style
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '\\' && i + 1 < chars.len() {
+            buffer.push(chars[i + 1]);
+            i += 2;
+            continue;
+        }
+
+        // Bold: ** or __
+        if (c == '*' || c == '_') && chars.get(i + 1) == Some(&c) {
+            let marker = [c, c];
+            if bold || contains_from(&chars, i + 2, &marker) {
+                flush(&mut spans, &mut buffer, style_for(bold, italic));
+                bold = !bold;
+                i += 2;
+                continue;
+            }
+        }
+
+        // Italic: * or _ (underscores only at word boundaries, so snake_case survives)
+        if c == '*' || c == '_' {
+            let at_boundary = c == '*'
+                || if italic {
+                    chars.get(i + 1).is_none_or(|n| !n.is_alphanumeric())
+                } else {
+                    i == 0 || !chars[i - 1].is_alphanumeric()
+                };
+            if at_boundary && (italic || contains_from(&chars, i + 1, &[c])) {
+                flush(&mut spans, &mut buffer, style_for(bold, italic));
+                italic = !italic;
+                i += 1;
+                continue;
+            }
+        }
+
+        if c == '['
+            && let Some((label, end)) = parse_link(&chars, i)
+        {
+            flush(&mut spans, &mut buffer, style_for(bold, italic));
+            spans.push(Span::styled(
+                label,
+                style_for(bold, italic)
+                    .fg(Color::Blue)
+                    .add_modifier(Modifier::UNDERLINED),
+            ));
+            i = end;
+            continue;
+        }
+
+        buffer.push(c);
+        i += 1;
+    }
+
+    flush(&mut spans, &mut buffer, style_for(bold, italic));
+    Line::from(spans)
+}
+
+fn flush(spans: &mut Vec<Span<'static>>, buffer: &mut String, style: Style) {
+    if !buffer.is_empty() {
+        spans.push(Span::styled(std::mem::take(buffer), style));
+    }
+}
+
+fn contains_from(chars: &[char], start: usize, marker: &[char]) -> bool {
+    start <= chars.len() && chars[start..].windows(marker.len()).any(|w| w == marker)
+}
+
+/// Parses `[label](url)` starting at `start`, returning the label and the index past `)`.
+fn parse_link(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let close = start + chars[start..].iter().position(|&c| c == ']')?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+    let end = close + 1 + chars[close + 1..].iter().position(|&c| c == ')')?;
+    let label: String = chars[start + 1..close].iter().collect();
+    Some((label.replace(['*', '_'], ""), end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_to_text() {
+        let text = markdown_to_text(
+            "A **bold** and *italic* tale.\n\n\n---\n**Links:**\n- [Official](https://example.com)\nsnake_case_name",
+        );
+        let lines: Vec<String> = text
+            .lines
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "A bold and italic tale.",
+                "",
+                "────────",
+                "Links:",
+                "• Official",
+                "snake_case_name",
+            ]
+        );
+
+        let first = &text.lines[0].spans;
+        assert!(first[1].style.add_modifier.contains(Modifier::BOLD));
+        assert!(first[3].style.add_modifier.contains(Modifier::ITALIC));
+        assert!(text.lines[4].spans[1].style.add_modifier.contains(Modifier::UNDERLINED));
+
+        assert_eq!(markdown_to_text("2 * 3 = 6").lines[0].spans.len(), 1);
+    }
+}
@@ -1 +1,9 @@
+pub mod chapter_grid;
+pub mod clipboard;
+pub mod lru;
+pub mod markdown;
+pub mod page_fit;
+pub mod palette;
+pub mod text;
+#[allow(clippy::module_inception, reason = "ui::ui holds the App state and draw code; renaming it would touch every import")]
 pub mod ui;
@@ -0,0 +1,128 @@
+use image::DynamicImage;
+use ratatui_image::FilterType;
+
+use crate::backend::config::PageEnhance;
+use crate::backend::progress::PageFit;
+
+/// Pages narrower than this are doubled by `PageEnhance::Upscale`
+const UPSCALE_BELOW_WIDTH: u32 = 1000;
+const SHARPEN_SIGMA: f32 = 1.0;
+/// Brightness difference below which the unsharp mask leaves pixels alone, so flat areas
+/// don't get noisy
+const SHARPEN_THRESHOLD: i32 = 2;
+
+/// Applies the configured enhancement to a downloaded page before it's displayed.
+pub fn enhance_page(page: DynamicImage, enhance: PageEnhance) -> DynamicImage {
+    match enhance {
+        PageEnhance::Off => page,
+        PageEnhance::Sharpen => page.unsharpen(SHARPEN_SIGMA, SHARPEN_THRESHOLD),
+        PageEnhance::Upscale => {
+            let page = if page.width() < UPSCALE_BELOW_WIDTH {
+                page.resize_exact(page.width() * 2, page.height() * 2, FilterType::CatmullRom)
+            } else {
+                page
+            };
+            page.unsharpen(SHARPEN_SIGMA, SHARPEN_THRESHOLD)
+        }
+    }
+}
+
+/// A fit mode and the viewport size in pixels that a page was scaled for.
+pub type ScaleKey = (PageFit, (u32, u32));
+
+/// Scales a page for `fit` against a viewport of `viewport` pixels. Fit-width matches the
+/// viewport's width and fit-height its height, keeping proportions, so the other side may
+/// overflow. `PageFit::Whole` is left to ratatui-image and returns the page unchanged.
+pub fn scale_page(page: &DynamicImage, fit: PageFit, viewport: (u32, u32), filter: FilterType) -> DynamicImage {
+    let (vw, vh) = (viewport.0.max(1), viewport.1.max(1));
+    let (pw, ph) = (page.width().max(1) as u64, page.height().max(1) as u64);
+    let (w, h) = match fit {
+        PageFit::Whole => return page.clone(),
+        PageFit::Width => (vw, ((ph * vw as u64) / pw).max(1) as u32),
+        PageFit::Height => (((pw * vh as u64) / ph).max(1) as u32, vh),
+    };
+    page.resize_exact(w, h, filter)
+}
+
+/// Cuts the viewport-sized window `scroll` pixels along the overflowing side of a scaled
+/// page. Returns the window and the largest useful scroll offset.
+pub fn crop_view(scaled: &DynamicImage, fit: PageFit, viewport: (u32, u32), scroll: u32) -> (DynamicImage, u32) {
+    let (vw, vh) = viewport;
+    match fit {
+        PageFit::Width if scaled.height() > vh => {
+            let max = scaled.height() - vh;
+            (scaled.crop_imm(0, scroll.min(max), scaled.width(), vh), max)
+        }
+        PageFit::Height if scaled.width() > vw => {
+            let max = scaled.width() - vw;
+            (scaled.crop_imm(scroll.min(max), 0, vw, scaled.height()), max)
+        }
+        _ => (scaled.clone(), 0),
+    }
+}
+
+/// Pixels moved per scroll step: a third of the viewport along the scrolling side.
+pub fn scroll_step(fit: PageFit, viewport: (u32, u32)) -> u32 {
+    let side = match fit {
+        PageFit::Height => viewport.0,
+        _ => viewport.1,
+    };
+    (side / 3).max(1)
+}
+
+/// The scroll position one step on from `steps`, for a page that scrolls up to `end`
+/// steps. `None` when that runs off the page, so the reader moves to the next one.
+pub fn scroll_within(steps: u32, end: u32, forward: bool) -> Option<u32> {
+    if forward {
+        (steps < end).then_some(steps + 1)
+    } else {
+        steps.checked_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_width_scrolls_vertically() {
+        let page = DynamicImage::new_rgb8(100, 300);
+        let viewport = (200, 400);
+
+        let scaled = scale_page(&page, PageFit::Width, viewport, FilterType::Nearest);
+        assert_eq!((scaled.width(), scaled.height()), (200, 600));
+
+        let (view, max) = crop_view(&scaled, PageFit::Width, viewport, 1000);
+        assert_eq!((view.width(), view.height()), (200, 400));
+        assert_eq!(max, 200);
+
+        // A wide spread fits in height, so only fit-height has to scroll it sideways
+        let spread = DynamicImage::new_rgb8(400, 200);
+        let scaled = scale_page(&spread, PageFit::Height, viewport, FilterType::Nearest);
+        let (view, max) = crop_view(&scaled, PageFit::Height, viewport, 0);
+        assert_eq!((view.width(), view.height()), (200, 400));
+        assert_eq!(max, 600);
+    }
+
+    #[test]
+    fn test_scrolling_runs_off_either_end() {
+        assert_eq!(scroll_within(0, 2, true), Some(1));
+        assert_eq!(scroll_within(2, 2, true), None);
+        assert_eq!(scroll_within(2, 2, false), Some(1));
+        assert_eq!(scroll_within(0, 2, false), None);
+        // A page that fits has nowhere to scroll
+        assert_eq!(scroll_within(0, 0, true), None);
+    }
+
+    #[test]
+    fn test_upscale_only_enlarges_small_pages() {
+        let small = enhance_page(DynamicImage::new_rgb8(400, 600), PageEnhance::Upscale);
+        assert_eq!((small.width(), small.height()), (800, 1200));
+
+        let large = enhance_page(DynamicImage::new_rgb8(1200, 1800), PageEnhance::Upscale);
+        assert_eq!((large.width(), large.height()), (1200, 1800));
+
+        let sharpened = enhance_page(DynamicImage::new_rgb8(400, 600), PageEnhance::Sharpen);
+        assert_eq!((sharpened.width(), sharpened.height()), (400, 600));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::ui::ui::View;
+
+/// An action the command palette can run. Each one goes through the same code as its key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    GoHome,
+    GoBookmarks,
+    GoSearch,
+    GoLocal,
+    ResumeReading,
+    Refresh,
+    OpenStats,
+    ExportStats,
+    DownloadChapters,
+    ReportBrokenChapter,
+    ClearCache,
+    ShowSettings,
+    Quit,
+}
+
+const COMMANDS: [Command; 13] = [
+    Command::GoHome,
+    Command::GoBookmarks,
+    Command::GoSearch,
+    Command::GoLocal,
+    Command::ResumeReading,
+    Command::Refresh,
+    Command::OpenStats,
+    Command::ExportStats,
+    Command::DownloadChapters,
+    Command::ReportBrokenChapter,
+    Command::ClearCache,
+    Command::ShowSettings,
+    Command::Quit,
+];
+
+impl Command {
+    pub fn label(self) -> &'static str {
+        match self {
+            Command::GoHome => "Go to Home",
+            Command::GoBookmarks => "Go to Bookmarks",
+            Command::GoSearch => "Go to Search",
+            Command::GoLocal => "Go to Local library",
+            Command::ResumeReading => "Resume last read chapter",
+            Command::Refresh => "Refresh",
+            Command::OpenStats => "Open reading stats",
+            Command::ExportStats => "Export reading stats",
+            Command::DownloadChapters => "Download selected chapters",
+            Command::ReportBrokenChapter => "Report broken chapter",
+            Command::ClearCache => "Clear cached pages, covers and API responses",
+            Command::ShowSettings => "Settings: show config file",
+            Command::Quit => "Quit",
+        }
+    }
+
+    /// Whether the command does anything from `view`.
+    fn available(self, view: View) -> bool {
+        match self {
+            Command::Refresh => matches!(view, View::Home | View::MangaDetail),
+            Command::DownloadChapters => view == View::MangaDetail,
+            Command::ReportBrokenChapter => view == View::Reader,
+            _ => true,
+        }
+    }
+}
+
+/// The open palette: what's been typed and which match is highlighted.
+#[derive(Debug, Default)]
+pub struct CommandPalette {
+    pub query: String,
+    pub selected: usize,
+}
+
+impl CommandPalette {
+    /// Commands available from `view` that fuzzily match the query, best first.
+    pub fn matches(&self, view: View) -> Vec<Command> {
+        let mut scored: Vec<(usize, Command)> = COMMANDS
+            .iter()
+            .filter(|c| c.available(view))
+            .filter_map(|&c| Some((fuzzy_score(c.label(), &self.query)?, c)))
+            .collect();
+        scored.sort_by_key(|(score, _)| *score);
+        scored.into_iter().map(|(_, c)| c).collect()
+    }
+
+    /// The highlighted command, if anything matches.
+    pub fn selected_command(&self, view: View) -> Option<Command> {
+        let matches = self.matches(view);
+        matches.get(self.selected.min(matches.len().saturating_sub(1))).copied()
+    }
+}
+
+/// How well `query` matches `label` when its characters appear in order, ignoring case:
+/// the number of characters skipped between them, so lower is better. `None` if they don't
+/// all appear.
+fn fuzzy_score(label: &str, query: &str) -> Option<usize> {
+    let label: Vec<char> = label.to_lowercase().chars().collect();
+    let mut pos = 0;
+    let mut skipped = 0;
+    let mut started = false;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = label[pos..].iter().position(|&c| c == q)?;
+        // Where the match starts doesn't count, only gaps within it
+        if started {
+            skipped += found;
+        }
+        started = true;
+        pos += found + 1;
+    }
+    Some(skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_matches_in_order() {
+        assert_eq!(fuzzy_score("Go to Bookmarks", ""), Some(0));
+        assert_eq!(fuzzy_score("Go to Bookmarks", "book"), Some(0));
+        assert_eq!(fuzzy_score("Go to Bookmarks", "BMK"), Some(5));
+        assert_eq!(fuzzy_score("Go to Bookmarks", "kb"), None);
+
+        let palette = CommandPalette { query: "stats".to_string(), selected: 0 };
+        assert_eq!(palette.matches(View::Home), [Command::OpenStats, Command::ExportStats]);
+
+        // Commands that need another view are left out
+        let palette = CommandPalette { query: "download".to_string(), selected: 0 };
+        assert!(palette.matches(View::Home).is_empty());
+        assert_eq!(palette.selected_command(View::MangaDetail), Some(Command::DownloadChapters));
+    }
+}
@@ -0,0 +1,141 @@
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// The longest prefix of `text` that fits in `width` terminal columns.
+fn take_width(text: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, c) in text.char_indices() {
+        used += c.width().unwrap_or(0);
+        if used > width {
+            return &text[..i];
+        }
+    }
+    text
+}
+
+/// Shortens `text` to `max_width` columns, ending in "..." when it was cut. CJK and
+/// emoji count as the two columns they take on screen.
+pub fn truncate_text(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        text.to_string()
+    } else {
+        format!("{}...", take_width(text, max_width.saturating_sub(3)))
+    }
+}
+
+/// Wraps `text` at word boundaries into at most `max_lines` lines of `width` columns,
+/// ending the last line in "..." if it doesn't all fit. A word wider than a line, such
+/// as a Japanese title without spaces, is broken across lines.
+pub fn wrap_text(text: &str, width: usize, max_lines: usize) -> Vec<String> {
+    if width == 0 || max_lines == 0 {
+        return vec![];
+    }
+
+    let mut lines = Vec::new();
+    let mut current_line = String::new();
+
+    for mut word in text.split_whitespace() {
+        loop {
+            let needed = if current_line.is_empty() {
+                word.width()
+            } else {
+                current_line.width() + 1 + word.width()
+            };
+            if needed <= width {
+                if !current_line.is_empty() {
+                    current_line.push(' ');
+                }
+                current_line.push_str(word);
+                break;
+            }
+
+            if current_line.is_empty() {
+                let mut head = take_width(word, width);
+                if head.is_empty() {
+                    // A double-width character on a one-column line still has to go somewhere
+                    head = &word[..word.chars().next().map_or(0, char::len_utf8)];
+                }
+                current_line.push_str(head);
+                word = &word[head.len()..];
+            }
+
+            lines.push(std::mem::take(&mut current_line));
+            if lines.len() >= max_lines {
+                if let Some(last) = lines.last_mut() {
+                    let last_width = last.width();
+                    if last_width > 3 {
+                        *last = format!("{}...", take_width(last, last_width - 3));
+                    }
+                }
+                return lines;
+            }
+        }
+    }
+
+    if !current_line.is_empty() && lines.len() < max_lines {
+        lines.push(current_line);
+    }
+
+    lines
+}
+
+/// Index of the next title after `current` that starts with `letter`, ignoring case and
+/// wrapping past the end, so repeating a letter steps through every title under it.
+pub fn next_with_initial<'a>(
+    titles: impl IntoIterator<Item = &'a str>,
+    current: usize,
+    letter: char,
+) -> Option<usize> {
+    let matches: Vec<usize> = titles
+        .into_iter()
+        .enumerate()
+        .filter(|(_, title)| {
+            title
+                .trim_start()
+                .chars()
+                .next()
+                .is_some_and(|c| c.to_lowercase().eq(letter.to_lowercase()))
+        })
+        .map(|(i, _)| i)
+        .collect();
+    matches.iter().copied().find(|&i| i > current).or(matches.first().copied())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_counts_display_width() {
+        assert_eq!(truncate_text("One Piece", 9), "One Piece");
+        // Seven double-width characters take fourteen columns
+        let title = "進撃の巨人です";
+        assert_eq!(truncate_text(title, 14), title);
+        let cut = truncate_text(title, 10);
+        assert_eq!(cut, "進撃の...");
+        assert!(cut.width() <= 10);
+        assert_eq!(truncate_text("Ch.1 チェンソーマン", 10), "Ch.1 チ...");
+    }
+
+    #[test]
+    fn test_wrap_mixed_width_text() {
+        let lines = wrap_text("Tokyo 東京喰種 Ghoul", 8, 3);
+        assert_eq!(lines, ["Tokyo", "東京喰種", "Ghoul"]);
+        assert!(lines.iter().all(|l| l.width() <= 8));
+
+        // Unspaced CJK is split on width rather than overflowing the card
+        let lines = wrap_text("ワンパンマンワンパンマン", 10, 2);
+        assert_eq!(lines[0], "ワンパンマ");
+        assert_eq!(lines[1], "ンワン...");
+        assert!(lines.iter().all(|l| l.width() <= 10));
+    }
+
+    #[test]
+    fn test_next_with_initial_wraps_through_matches() {
+        let titles = ["Berserk", "akira", "Blame!", "Claymore", "Bleach"];
+        assert_eq!(next_with_initial(titles, 0, 'a'), Some(1));
+        assert_eq!(next_with_initial(titles, 0, 'b'), Some(2));
+        assert_eq!(next_with_initial(titles, 2, 'B'), Some(4));
+        assert_eq!(next_with_initial(titles, 4, 'b'), Some(0));
+        assert_eq!(next_with_initial(titles, 0, 'z'), None);
+    }
+}
@@ -3,14 +3,37 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, ListState, Paragraph, Tabs},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs, Wrap},
     Frame,
 };
-use ratatui_image::{picker::Picker, protocol::StatefulProtocol, Resize, StatefulImage};
-use std::collections::HashMap;
-
-use crate::backend::bookmarks::Bookmarks;
-use crate::backend::mangadex::{Chapter, Manga};
+use ratatui_image::picker::cap_parser::QueryStdioOptions;
+use ratatui_image::picker::{Picker, ProtocolType};
+use ratatui_image::{protocol::StatefulProtocol, FilterType, Resize, StatefulImage};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use unicode_width::UnicodeWidthStr;
+
+use crate::backend::bookmarks::{Bookmarks, ReadingStatus};
+use crate::backend::config::{app_data_dir, Config, ImageProtocol};
+use crate::backend::downloads::PendingDownload;
+use crate::backend::log_file;
+use crate::backend::mangadex::{
+    bytes_downloaded, is_verbose_network, request_count, Chapter, Manga, SearchOrder, Tag,
+};
+use crate::backend::progress::{chapter_finished, GridPosition, PageFit, ProgressStore};
+use crate::backend::source::{self, Source};
+use crate::ui::chapter_grid::{
+    self, build_rows, sorted_order, volume_key, volume_label, ChapterSort, GridItem, GridMove, GridRow,
+    VolumeKey,
+};
+use crate::ui::clipboard;
+use crate::ui::lru::LruMap;
+use crate::ui::markdown::{markdown_to_plain, markdown_to_text};
+use crate::ui::page_fit;
+use crate::ui::palette::CommandPalette;
+use crate::ui::text::{truncate_text, wrap_text};
 
 #[derive(Clone, Copy, PartialEq, Eq, Default)]
 pub enum Tab {
@@ -18,12 +41,16 @@ pub enum Tab {
     Home,
     Bookmarks,
     Search,
+    /// Manga read from the `local_library` folder
+    Local,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Default)]
 pub enum Focus {
     #[default]
     Header,
+    /// The Continue Reading row, only shown when something is in progress
+    Continue,
     Recent,
     Popular,
 }
@@ -33,6 +60,7 @@ pub enum AppState {
     #[default]
     Loading,
     Ready,
+    Offline,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Default)]
@@ -41,6 +69,48 @@ pub enum View {
     Home,
     MangaDetail,
     Reader,
+    Stats,
+}
+
+/// A running whole-chapter prefetch, aborted when the reader leaves the chapter.
+pub struct ChapterPrefetch {
+    pub chapter_id: String,
+    /// Pages now in the cache
+    pub done: usize,
+    /// Pages that couldn't be fetched
+    pub failed: usize,
+    pub total: usize,
+    pub handle: tokio::task::AbortHandle,
+}
+
+/// A run of chapters being downloaded as CBZ files one after another.
+pub struct BatchDownload {
+    pub done: usize,
+    pub total: usize,
+    pub handle: tokio::task::AbortHandle,
+    /// Set on cancel, so the task discards the download as it stops
+    pub cancelled: Arc<AtomicBool>,
+}
+
+impl BatchDownload {
+    /// Stops the download for good: it won't be offered for resuming at the next launch.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.handle.abort();
+    }
+}
+
+/// Where scrolling a page took the reader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageScroll {
+    /// Nothing to scroll, or past the end of the last chapter or start of the first
+    Stopped,
+    /// Moved within the page
+    Scrolled,
+    /// Ran off the page onto the neighbouring one, which needs loading
+    Page,
+    /// Ran off the chapter into the neighbouring one, whose page list needs loading
+    Chapter,
 }
 
 #[derive(Default)]
@@ -51,11 +121,57 @@ pub struct ReaderState {
     pub page_urls: Vec<String>,
     pub current_page: usize,
     pub page_image: Option<StatefulProtocol>,
+    /// The decoded current page, kept so the protocol can be rebuilt when toggling inversion
+    pub page_source: Option<DynamicImage>,
+    /// Show pages with inverted colors
+    pub invert: bool,
     pub loading: bool,
     pub error: Option<String>,
+    pub show_thumbnails: bool,
+    /// Chapter context shown beside the page
+    pub show_sidebar: bool,
+    pub page_thumbnails: HashMap<String, StatefulProtocol>,
+    pub page_jump_input: String,
+    pub prefetch: Option<ChapterPrefetch>,
+    /// Page URL and retry count of the download currently being retried
+    pub retry_status: Option<(String, u32, u32)>,
+    /// Page to open once the page list arrives, instead of the first
+    pub resume_page: Option<usize>,
+    /// Pages of the chapter known to be in the page cache, for `show_page_cache_status`
+    pub cached_pages: HashSet<String>,
+    /// Pages of the chapter that couldn't be loaded even after retrying, for `b` reports
+    pub failed_pages: Vec<String>,
+    /// Open chapter-switcher popup and its highlighted row
+    pub chapter_list: Option<ListState>,
+    /// When auto-scroll started timing the current page, while it's running
+    pub auto_scroll: Option<std::time::Instant>,
+    pub fit: PageFit,
+    /// Scroll position for fit-width and fit-height, in steps of a third of the window
+    pub scroll_steps: u32,
+    /// Furthest `scroll_steps` goes on the page as last drawn; scrolling past it or back
+    /// past the top moves to the neighbouring page
+    pub scroll_end: u32,
+    /// Show the page being loaded scrolled to its end, after scrolling back onto it
+    pub scroll_from_end: bool,
+    /// `page_source` scaled for the current fit and window size
+    pub page_scaled: Option<(page_fit::ScaleKey, DynamicImage)>,
+    /// Fit, window size in pixels and scroll that `page_image` was built for
+    pub page_view: Option<(PageFit, (u32, u32), u32)>,
+    /// When the reader moved to the current page, for the reading-time stat
+    pub page_since: Option<std::time::Instant>,
+}
+
+impl ReaderState {
+    pub fn cancel_prefetch(&mut self) {
+        if let Some(prefetch) = self.prefetch.take() {
+            prefetch.handle.abort();
+        }
+    }
 }
 
 pub struct App {
+    /// Where the home lists and search results come from
+    pub source: &'static dyn Source,
     pub state: AppState,
     pub view: View,
     pub loading_message: String,
@@ -64,18 +180,63 @@ pub struct App {
     pub search_query: String,
     pub search_results: Vec<Manga>,
     pub search_offset: usize,
+    pub search_order: SearchOrder,
+    /// Only search among manga with this tag
+    pub search_tag: Option<Tag>,
+    /// Answer to "Show explicit content?", once asked this session
+    pub explicit_consent: Option<bool>,
+    pub explicit_prompt: bool,
+    /// "Quit anyway?" is open, after `q` was pressed
+    pub quit_prompt: bool,
+    /// Download left unfinished by the last run, until the user says whether to resume it
+    pub resume_download_prompt: Option<PendingDownload>,
+    /// Command palette opened with `:` or Ctrl-P
+    pub command_palette: Option<CommandPalette>,
+    /// Original covers of pornographic manga that loaded while the prompt was unanswered
+    pub withheld_covers: HashMap<String, (DynamicImage, bool)>,
+    /// Tag highlighted on the details page, which Enter searches for
+    pub selected_tag: Option<usize>,
+    /// The details page's cover is shown full-screen
+    pub cover_zoom: bool,
+    /// Search results picked with `p` to compare, oldest first
+    pub compare_pins: Vec<Manga>,
+    /// The two pinned manga are shown side by side
+    pub comparing: bool,
+    /// When a card row last moved, to tell quick scrolling apart
+    pub last_card_step: Option<std::time::Instant>,
     pub searching: bool,
     pub last_search_query: String,
     pub search_debounce: Option<std::time::Instant>,
+    pub toast: Option<(String, std::time::Instant)>,
+    pub continue_offset: usize,
     pub recent_offset: usize,
     pub popular_offset: usize,
     pub bookmark_offset: usize,
+    /// Manga found in the local library folder
+    pub local_library: Vec<Manga>,
+    pub local_offset: usize,
+    pub local_scanning: bool,
+    pub bookmark_filter: String,
+    pub bookmark_filter_active: bool,
+    /// `g` was pressed in the bookmarks and the next letter jumps to a title
+    pub bookmark_jump_active: bool,
+    /// Whether bookmark metadata has been refreshed from MangaDex this session
+    pub bookmarks_refreshed: bool,
+    /// A refresh started with `R`/`F5` is waiting on the network
+    pub refreshing: bool,
+    /// The home lists have loaded once, and the work waiting on them has started
+    pub started: bool,
     pub recently_updated: Vec<Manga>,
     pub popular_now: Vec<Manga>,
-    pub picker: Option<Picker>,
-    pub cover_images: HashMap<String, DynamicImage>,
-    pub image_states: HashMap<String, StatefulProtocol>,
+    /// Falls back to halfblocks when the terminal has no graphics protocol
+    pub picker: Picker,
+    /// Cover protocols by manga id, capped so long browsing sessions don't grow without bound
+    pub image_states: LruMap<StatefulProtocol>,
+    /// Explicit covers unblurred this session
+    pub revealed_covers: HashSet<String>,
     pub bookmarks: Bookmarks,
+    pub config: Config,
+    pub progress: ProgressStore,
     
     // Manga detail view
     pub selected_manga: Option<Manga>,
@@ -84,24 +245,76 @@ pub struct App {
     pub chapter_selected: usize,      // Currently selected chapter index
     pub chapter_scroll_row: usize,    // First visible row
     pub chapter_grid_cols: usize,     // Columns in grid (calculated from width)
-    pub chapter_thumbnails: HashMap<String, StatefulProtocol>,
-    pub chapter_thumbnail_images: HashMap<String, DynamicImage>,
+    pub chapter_thumbnails: LruMap<StatefulProtocol>,
+    /// Chapters of the open manga that appeared since it was last opened
+    pub new_chapters: HashSet<String>,
+    /// Chapters of the open manga whose page lists have been prefetched or are on the way
+    pub chapter_url_prefetches: HashSet<String>,
+    /// Runtime toggle for chapter card thumbnails, seeded from the config
+    pub show_chapter_thumbnails: bool,
+    /// Show chapters under volume headers, seeded from the config
+    pub group_by_volume: bool,
+    pub chapter_sort: ChapterSort,
+    /// Chapter where a range selection for bulk download starts
+    pub chapter_selection_anchor: Option<usize>,
+    pub batch_download: Option<BatchDownload>,
+    pub collapsed_volumes: HashSet<VolumeKey>,
+    /// Set when the grid cursor is on a volume header rather than a chapter
+    pub selected_volume_header: Option<VolumeKey>,
     
     // Reader view
     pub reader: ReaderState,
+    /// Chapter and page to reopen once the selected manga's chapters load
+    pub pending_resume: Option<(String, usize)>,
+    /// Bookmarked manga whose chapters are loading so its latest chapter can open directly
+    pub quick_open: Option<String>,
 }
 
-impl Default for App {
-    fn default() -> Self {
-        Self::new()
+/// Queries the terminal for its graphics support, then applies any forced protocol.
+/// Terminals without one (plain SSH, some tmux setups) or that don't answer within
+/// `timeout_ms` get Unicode halfblocks, along with a warning to show once the app is up.
+fn build_picker(protocol: ImageProtocol, timeout_ms: u64) -> (Picker, Option<String>) {
+    let options = QueryStdioOptions {
+        timeout: std::time::Duration::from_millis(timeout_ms.max(1)),
+        ..Default::default()
+    };
+    // A terminal that never answers still yields a halfblocks picker rather than an error
+    let mut picker = Picker::from_query_stdio_with_options(options).unwrap_or_else(|e| {
+        log::warn!("Terminal graphics query failed ({}), falling back to halfblocks", e);
+        Picker::halfblocks()
+    });
+    log::info!(
+        "Detected graphics protocol {:?}, font size {:?}",
+        picker.protocol_type(),
+        picker.font_size()
+    );
+
+    if let Some(protocol_type) = protocol.protocol_type() {
+        log::info!("Forcing graphics protocol {:?}", protocol_type);
+        picker.set_protocol_type(protocol_type);
+        return (picker, None);
     }
+    let warning = (picker.protocol_type() == ProtocolType::Halfblocks).then(|| {
+        "No terminal graphics detected, so images are low-res halfblocks. \
+         Set image_protocol in config.json to force kitty, sixel or iterm2"
+            .to_string()
+    });
+    (picker, warning)
 }
 
 impl App {
-    pub fn new() -> Self {
-        let picker = Picker::from_query_stdio().ok();
-
-        Self {
+    /// Builds the app from a loaded config whose directories are already set up.
+    /// `warnings` are shown as toasts at startup.
+    pub fn new(config: Config, mut warnings: Vec<String>) -> Self {
+        let (picker, picker_warning) = build_picker(config.image_protocol, config.terminal_query_timeout_ms);
+        warnings.extend(picker_warning);
+
+        let mut bookmarks = Bookmarks::load();
+        bookmarks.track_sync = config.sync_account().is_some();
+        let reader = ReaderState { show_sidebar: config.reader_sidebar, ..ReaderState::default() };
+
+        let mut app = Self {
+            source: source::mangadex(),
             state: AppState::Loading,
             view: View::Home,
             loading_message: "Initializing...".to_string(),
@@ -110,34 +323,120 @@ impl App {
             search_query: String::new(),
             search_results: Vec::new(),
             search_offset: 0,
+            search_order: SearchOrder::default(),
+            search_tag: None,
+            explicit_consent: None,
+            explicit_prompt: false,
+            quit_prompt: false,
+            resume_download_prompt: None,
+            command_palette: None,
+            withheld_covers: HashMap::new(),
+            selected_tag: None,
+            cover_zoom: false,
+            compare_pins: Vec::new(),
+            comparing: false,
+            last_card_step: None,
             searching: false,
             last_search_query: String::new(),
             search_debounce: None,
+            toast: None,
+            continue_offset: 0,
             recent_offset: 0,
             popular_offset: 0,
             bookmark_offset: 0,
+            local_library: Vec::new(),
+            local_offset: 0,
+            local_scanning: false,
+            bookmark_filter: String::new(),
+            bookmark_filter_active: false,
+            bookmark_jump_active: false,
+            bookmarks_refreshed: false,
+            refreshing: false,
+            started: false,
             recently_updated: Vec::new(),
             popular_now: Vec::new(),
             picker,
-            cover_images: HashMap::new(),
-            image_states: HashMap::new(),
-            bookmarks: Bookmarks::load(),
+            image_states: LruMap::new(MAX_COVER_PROTOCOLS),
+            revealed_covers: HashSet::new(),
+            bookmarks,
+            show_chapter_thumbnails: config.show_chapter_thumbnails,
+            group_by_volume: config.group_by_volume,
+            chapter_sort: ChapterSort::default(),
+            chapter_selection_anchor: None,
+            batch_download: None,
+            collapsed_volumes: HashSet::new(),
+            selected_volume_header: None,
+            config,
+            progress: ProgressStore::load(),
             selected_manga: None,
             chapters: Vec::new(),
             chapter_list_state: ListState::default(),
             chapter_selected: 0,
             chapter_scroll_row: 0,
             chapter_grid_cols: 1,
-            chapter_thumbnails: HashMap::new(),
-            chapter_thumbnail_images: HashMap::new(),
-            reader: ReaderState::default(),
+            chapter_thumbnails: LruMap::new(MAX_CHAPTER_THUMBNAILS),
+            new_chapters: HashSet::new(),
+            chapter_url_prefetches: HashSet::new(),
+            reader,
+            pending_resume: None,
+            quick_open: None,
+        };
+
+        if !warnings.is_empty() {
+            app.show_toast(&warnings.join("; "));
         }
+        app
     }
 
-    pub fn toggle_bookmark(&mut self) {
-        if let Some(ref manga) = self.selected_manga {
-            self.bookmarks.toggle(manga);
+    /// Rows needed to show a cover `width` cells wide at its real proportions,
+    /// based on the terminal font size reported by the picker.
+    pub fn cover_rows(&self, width: u16) -> u16 {
+        if let Some(rows) = self.config.cover_rows {
+            return rows;
         }
+
+        let (font_w, font_h) = self.picker.font_size();
+        let rows = width as f32 * font_w as f32 * COVER_ASPECT / font_h.max(1) as f32;
+        rows.round() as u16
+    }
+
+    /// Bookmarks or unbookmarks the open manga. Returns whether it is now bookmarked.
+    pub fn toggle_bookmark(&mut self) -> Option<bool> {
+        let manga = self.selected_manga.as_ref()?;
+        Some(self.bookmarks.toggle(manga))
+    }
+
+    /// Highlights the next tag of the open manga, going back to none after the last.
+    pub fn cycle_selected_tag(&mut self) {
+        let count = self.selected_manga.as_ref().map_or(0, |m| m.tags.len());
+        self.selected_tag = match self.selected_tag {
+            None if count > 0 => Some(0),
+            Some(i) if i + 1 < count => Some(i + 1),
+            _ => None,
+        };
+    }
+
+    /// Leaves the details page for the Search tab, filtered to the highlighted tag. The
+    /// caller starts the search.
+    pub fn search_by_selected_tag(&mut self) -> Option<Tag> {
+        let tag = self.selected_manga.as_ref()?.tags.get(self.selected_tag?)?.clone();
+        self.go_back();
+        self.tab = Tab::Search;
+        self.focus = Focus::Recent;
+        self.search_query.clear();
+        self.search_results.clear();
+        self.search_offset = 0;
+        self.search_tag = Some(tag.clone());
+        Some(tag)
+    }
+
+    /// Cycles the reading status of a card's manga and says what it is now.
+    pub fn cycle_reading_status(&mut self, manga_id: &str) {
+        let message = match self.bookmarks.cycle_status(manga_id) {
+            Some(status) => format!("Marked as {}", status.label()),
+            None => "Cleared the reading status".to_string(),
+        };
+        self.show_toast(&message);
     }
 
     pub fn is_current_bookmarked(&self) -> bool {
@@ -148,6 +447,49 @@ impl App {
         }
     }
 
+    pub fn show_toast(&mut self, message: &str) {
+        self.toast = Some((message.to_string(), std::time::Instant::now()));
+    }
+
+    /// The card under the cursor in the current tab, unless the tab bar has focus.
+    pub fn focused_card(&self) -> Option<Manga> {
+        if self.focus == Focus::Header {
+            return None;
+        }
+        match self.tab {
+            Tab::Home => match self.focus {
+                Focus::Continue => self.continue_reading_manga().get(self.continue_offset).cloned(),
+                Focus::Recent => self.recently_updated.get(self.recent_offset).cloned(),
+                Focus::Popular => self.popular_now.get(self.popular_offset).cloned(),
+                Focus::Header => None,
+            },
+            Tab::Bookmarks => self.filtered_bookmarks().get(self.bookmark_offset).cloned(),
+            Tab::Search => self.search_results.get(self.search_offset).cloned(),
+            Tab::Local => self.local_library.get(self.local_offset).cloned(),
+        }
+    }
+
+    /// Bookmarked manga narrowed by the bookmark filter (case-insensitive match on
+    /// the title or any alt title).
+    pub fn filtered_bookmarks(&self) -> Vec<Manga> {
+        let needle = self.bookmark_filter.to_lowercase();
+        self.bookmarks
+            .get_bookmarked_manga(self.config.bookmark_sort)
+            .into_iter()
+            .filter(|m| {
+                needle.is_empty()
+                    || std::iter::once(&m.title)
+                        .chain(m.alt_titles.iter())
+                        .any(|t| t.to_lowercase().contains(&needle))
+            })
+            .collect()
+    }
+
+    /// True while a text field is capturing keys, so single-letter shortcuts shouldn't fire.
+    pub fn is_text_input_active(&self) -> bool {
+        self.bookmark_filter_active || self.bookmark_jump_active || self.command_palette.is_some()
+    }
+
     pub fn set_loading(&mut self, message: &str) {
         self.state = AppState::Loading;
         self.loading_message = message.to_string();
@@ -157,16 +499,137 @@ impl App {
         self.state = AppState::Ready;
     }
 
-    pub fn add_cover_image(&mut self, manga_id: &str, image: DynamicImage) {
-        self.cover_images.insert(manga_id.to_string(), image.clone());
+    pub fn set_offline(&mut self, error: &str) {
+        self.state = AppState::Offline;
+        self.loading_message = error.to_string();
+    }
+
+    /// Manga for the Continue Reading row, in the same order as `progress.continue_reading()`.
+    pub fn continue_reading_manga(&self) -> Vec<Manga> {
+        self.progress
+            .continue_reading()
+            .into_iter()
+            .map(|e| Manga::from(&e.manga))
+            .collect()
+    }
+
+    /// Whether a manga's cover should be blurred under the `blur_covers` setting.
+    pub fn cover_hidden(&self, manga_id: &str, explicit: bool) -> bool {
+        self.config.blur_covers && explicit && !self.revealed_covers.contains(manga_id)
+    }
+
+    /// Unblurs a cover for the rest of the session. Returns true if the caller needs to
+    /// fetch it again, since only the blurred version was kept.
+    pub fn reveal_cover(&mut self, manga: &Manga) -> bool {
+        if !self.cover_hidden(&manga.id, manga.is_explicit()) {
+            return false;
+        }
+        self.revealed_covers.insert(manga.id.clone());
+        self.image_states.remove(&manga.id);
+        true
+    }
 
-        if let Some(ref picker) = self.picker {
-            let protocol = picker.new_resize_protocol(image);
-            self.image_states.insert(manga_id.to_string(), protocol);
+    /// Whether pornographic content may be shown. Asks once per session: the first time
+    /// this is checked, the confirmation prompt opens and the content stays hidden until
+    /// it's answered.
+    pub fn explicit_allowed(&mut self) -> bool {
+        if self.explicit_consent.is_none() {
+            self.explicit_prompt = true;
         }
+        self.explicit_consent == Some(true)
+    }
+
+    /// How many cards (ahead of, behind) the selected one to preload covers for after a step
+    /// along a row. Quick repeated steps, like a held arrow key, double the lookahead in
+    /// the direction of travel.
+    pub fn cover_preload_span(&mut self, forward: bool) -> (usize, usize) {
+        let now = std::time::Instant::now();
+        let fast = self
+            .last_card_step
+            .is_some_and(|last| now.duration_since(last) < FAST_SCROLL_INTERVAL);
+        self.last_card_step = Some(now);
+
+        let ahead = self.config.cover_preload_ahead;
+        let horizon = if fast { ahead * 2 } else { ahead };
+        if forward { (horizon, 0) } else { (ahead, horizon) }
+    }
+
+    /// Pins the selected search result for comparison, or unpins it. Pinning a third
+    /// drops the oldest pin.
+    pub fn toggle_compare_pin(&mut self) {
+        let Some(manga) = self.search_results.get(self.search_offset).cloned() else {
+            return;
+        };
+        if let Some(idx) = self.compare_pins.iter().position(|m| m.id == manga.id) {
+            self.compare_pins.remove(idx);
+            self.show_toast(&format!("Unpinned {}", manga.title));
+            return;
+        }
+        self.compare_pins.push(manga);
+        if self.compare_pins.len() > 2 {
+            self.compare_pins.remove(0);
+        }
+        let hint = if self.compare_pins.len() == 2 { "; press c to compare" } else { "" };
+        self.show_toast(&format!("Pinned {} of 2{}", self.compare_pins.len(), hint));
+    }
+
+    pub fn is_compare_pinned(&self, manga_id: &str) -> bool {
+        self.compare_pins.iter().any(|m| m.id == manga_id)
+    }
+
+    /// Whether a chapter download or prefetch would be abandoned by quitting now.
+    pub fn downloads_active(&self) -> bool {
+        self.batch_download.is_some() || self.reader.prefetch.is_some()
+    }
+
+    /// Handles `q`: returns true to quit right away, or opens the quit prompt when
+    /// downloads are running or `confirm_quit` is set.
+    pub fn request_quit(&mut self) -> bool {
+        if self.downloads_active() || self.config.confirm_quit {
+            self.quit_prompt = true;
+            false
+        } else {
+            true
+        }
+    }
+
+    pub fn answer_explicit_prompt(&mut self, allow: bool) {
+        self.explicit_prompt = false;
+        self.explicit_consent = Some(allow);
+        let withheld = std::mem::take(&mut self.withheld_covers);
+        if allow {
+            for (manga_id, (image, explicit)) in withheld {
+                self.add_cover_image(&manga_id, image, explicit, true);
+            }
+        }
+    }
+
+    pub fn add_cover_image(&mut self, manga_id: &str, image: DynamicImage, explicit: bool, pornographic: bool) {
+        let withhold = pornographic && !self.explicit_allowed();
+        if withhold && self.explicit_consent.is_none() {
+            // Kept so the cover can be shown straight away if the prompt is accepted
+            self.withheld_covers.insert(manga_id.to_string(), (image.clone(), explicit));
+        }
+        let image = if withhold || self.cover_hidden(manga_id, explicit) {
+            // Blurring a small copy is cheap, and it gets scaled up to a smear anyway
+            image.thumbnail(48, 68).blur(3.0)
+        } else {
+            image
+        };
+        let protocol = self.picker.new_resize_protocol(image);
+        self.image_states.insert(manga_id.to_string(), protocol);
+    }
+
+    /// Drops the covers and chapter thumbnails ready to draw, so they're fetched again.
+    pub fn clear_image_caches(&mut self) {
+        self.image_states.clear();
+        self.chapter_thumbnails.clear();
     }
 
     pub fn open_manga(&mut self, manga: Manga) {
+        self.bookmarks.clear_update(&manga.id);
+        self.selected_tag = None;
+        self.chapter_selection_anchor = None;
         self.selected_manga = Some(manga);
         self.view = View::MangaDetail;
         self.chapters.clear();
@@ -174,117 +637,835 @@ impl App {
         self.chapter_selected = 0;
         self.chapter_scroll_row = 0;
         self.chapter_thumbnails.clear();
-        self.chapter_thumbnail_images.clear();
+        self.new_chapters.clear();
+        self.chapter_url_prefetches.clear();
+        self.collapsed_volumes.clear();
+        self.selected_volume_header = None;
+    }
+
+    /// Indices into `chapters` from the selection anchor to the cursor, inclusive, in the
+    /// order the grid shows them.
+    pub fn chapter_selection(&self) -> Option<Vec<usize>> {
+        let anchor = self.chapter_selection_anchor?;
+        let order = sorted_order(&self.chapters, self.chapter_sort, |c| self.progress.is_read(&c.id));
+        let display = chapter_grid::display_order(&self.chapters, &order, self.group_by_volume);
+        Some(chapter_grid::range_between(&display, anchor, self.chapter_selected))
+    }
+
+    /// Marks the selected chapter, or the marked range, unread if all of it is read and
+    /// read otherwise. External chapters are left alone.
+    pub fn toggle_selection_read(&mut self) {
+        let Some(manga) = self.selected_manga.clone() else {
+            return;
+        };
+        if self.chapters.is_empty() || self.selected_volume_header.is_some() {
+            return;
+        }
+        let selection = self
+            .chapter_selection()
+            .unwrap_or_else(|| vec![self.chapter_selected]);
+        let chapter_ids: Vec<String> = selection
+            .iter()
+            .filter_map(|&idx| self.chapters.get(idx))
+            .filter(|c| c.external_url.is_none())
+            .map(|c| c.id.clone())
+            .collect();
+        if chapter_ids.is_empty() {
+            return;
+        }
+        self.chapter_selection_anchor = None;
+
+        let noun = if chapter_ids.len() == 1 { "chapter" } else { "chapters" };
+        if chapter_ids.iter().all(|id| self.progress.is_read(id)) {
+            self.progress.mark_unread(&manga.id, &chapter_ids);
+            self.show_toast(&format!("Marked {} {} unread", chapter_ids.len(), noun));
+        } else {
+            for chapter_id in &chapter_ids {
+                self.progress.mark_read(&manga, chapter_id);
+            }
+            self.progress.update_completion(&manga.id, &self.chapters);
+            self.show_toast(&format!("Marked {} {} read", chapter_ids.len(), noun));
+        }
+    }
+
+    /// Marks the start of a chapter range at the cursor, or clears the mark if it's already set.
+    pub fn toggle_selection_anchor(&mut self) {
+        if self.chapter_selection_anchor.take().is_none() && self.selected_volume_header.is_none() {
+            self.chapter_selection_anchor = Some(self.chapter_selected);
+            self.show_toast("Range start marked; move to the end and press D to download or u to mark read");
+        }
+    }
+
+    pub fn chapter_grid_rows(&self) -> Vec<GridRow> {
+        let order = sorted_order(&self.chapters, self.chapter_sort, |c| self.progress.is_read(&c.id));
+        build_rows(&self.chapters, &order, self.group_by_volume, &self.collapsed_volumes, self.chapter_grid_cols)
+    }
+
+    fn grid_cursor(&self) -> GridItem {
+        match &self.selected_volume_header {
+            Some(volume) => GridItem::Header(volume.clone()),
+            None => GridItem::Chapter(self.chapter_selected),
+        }
+    }
+
+    /// Row holding the cursor, falling back to the header of a collapsed volume it's hidden in.
+    pub fn selected_grid_row(&self, rows: &[GridRow]) -> usize {
+        let cursor = self.grid_cursor();
+        if let Some((row, _)) = chapter_grid::locate(rows, &cursor) {
+            return row;
+        }
+        self.chapters
+            .get(self.chapter_selected)
+            .and_then(|c| chapter_grid::locate(rows, &GridItem::Header(volume_key(c))))
+            .map(|(row, _)| row)
+            .unwrap_or(0)
+    }
+
+    /// Moves the grid cursor. Returns true if it landed on a different chapter or header.
+    pub fn move_chapter_cursor(&mut self, mv: GridMove) -> bool {
+        let rows = self.chapter_grid_rows();
+        let mut cursor = self.grid_cursor();
+        if chapter_grid::locate(&rows, &cursor).is_none() {
+            // The selected chapter is inside a collapsed volume; start from its header
+            match self.chapters.get(self.chapter_selected) {
+                Some(c) => cursor = GridItem::Header(volume_key(c)),
+                None => return false,
+            }
+        }
+
+        match chapter_grid::step(&rows, &cursor, mv, self.config.wrap_navigation) {
+            Some(GridItem::Header(volume)) => self.selected_volume_header = Some(volume),
+            Some(GridItem::Chapter(idx)) => {
+                self.selected_volume_header = None;
+                self.chapter_selected = idx;
+            }
+            None => return false,
+        }
+        true
+    }
+
+    pub fn toggle_volume_collapsed(&mut self, volume: VolumeKey) {
+        if !self.collapsed_volumes.remove(&volume) {
+            self.collapsed_volumes.insert(volume);
+        }
+    }
+
+    pub fn toggle_group_by_volume(&mut self) {
+        self.group_by_volume = !self.group_by_volume;
+        self.selected_volume_header = None;
+        self.chapter_scroll_row = 0;
+    }
+
+    /// Indices of up to `count` chapters around the cursor, nearest first, going by where
+    /// the grid shows them rather than their place in the feed.
+    pub fn chapters_near_cursor(&self, count: usize) -> Vec<usize> {
+        let order = sorted_order(&self.chapters, self.chapter_sort, |c| self.progress.is_read(&c.id));
+        let display = chapter_grid::display_order(&self.chapters, &order, self.group_by_volume);
+        chapter_grid::nearest_first(&display, self.chapter_selected, count)
+    }
+
+    /// Chapters whose thumbnails to preload: the cards nearest the focused one on screen,
+    /// few enough that the thumbnail cache keeps them all.
+    pub fn thumbnail_preload_chapters(&self) -> Vec<Chapter> {
+        self.chapters_near_cursor(THUMBNAIL_PRELOAD_WINDOW)
+            .into_iter()
+            .map(|idx| &self.chapters[idx])
+            .filter(|c| c.external_url.is_none() && !self.chapter_thumbnails.contains_key(&c.id))
+            .cloned()
+            .collect()
     }
 
     pub fn add_chapter_thumbnail(&mut self, chapter_id: &str, image: DynamicImage) {
-        self.chapter_thumbnail_images.insert(chapter_id.to_string(), image.clone());
-        if let Some(ref picker) = self.picker {
-            let protocol = picker.new_resize_protocol(image);
-            self.chapter_thumbnails.insert(chapter_id.to_string(), protocol);
+        let protocol = self.picker.new_resize_protocol(image);
+        self.chapter_thumbnails.insert(chapter_id.to_string(), protocol);
+    }
+
+    /// Index of the lowest-numbered readable chapter that hasn't been read yet.
+    pub fn next_unread_chapter(&self) -> Option<usize> {
+        self.chapters
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.external_url.is_none() && !self.progress.is_read(&c.id))
+            .min_by(|(_, a), (_, b)| a.sort_key().total_cmp(&b.sort_key()))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Index of an unread readable chapter picked at random, for dipping into a long series.
+    pub fn random_unread_chapter(&self) -> Option<usize> {
+        let unread: Vec<usize> = self
+            .chapters
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.external_url.is_none() && !self.progress.is_read(&c.id))
+            .map(|(idx, _)| idx)
+            .collect();
+        if unread.is_empty() {
+            return None;
         }
+        // Good enough randomness for a shuffle, without pulling in a crate
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos() as usize)
+            .unwrap_or_default();
+        Some(unread[nanos % unread.len()])
+    }
+
+    /// Index of the highest-numbered readable chapter.
+    pub fn latest_chapter(&self) -> Option<usize> {
+        self.chapters
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.external_url.is_none())
+            .max_by(|(_, a), (_, b)| a.sort_key().total_cmp(&b.sort_key()))
+            .map(|(idx, _)| idx)
     }
 
     pub fn open_reader(&mut self, chapter_idx: usize) {
+        self.selected_volume_header = None;
+        self.reader.cancel_prefetch();
+        self.reader.resume_page = None;
         self.reader.current_chapter_idx = chapter_idx;
         self.reader.manga = self.selected_manga.clone();
         self.reader.chapters = self.chapters.clone();
         self.reader.current_page = 0;
         self.reader.page_urls.clear();
+        self.reader.cached_pages.clear();
+        self.reader.failed_pages.clear();
         self.reader.page_image = None;
+        self.reader.page_source = None;
+        self.reader.invert = self
+            .selected_manga
+            .as_ref()
+            .is_some_and(|m| self.progress.is_inverted(&m.id));
+        self.reader.fit = self.progress.reader_fit;
+        if self.reader.manga.as_ref().is_some_and(Manga::is_pornographic) {
+            // Opens the prompt if it hasn't been answered yet
+            self.explicit_allowed();
+        }
+        self.reader.page_thumbnails.clear();
+        self.reader.page_jump_input.clear();
+        self.reader.chapter_list = None;
+        self.reader.auto_scroll = None;
         self.reader.loading = true;
         self.view = View::Reader;
     }
 
+    /// The protocol is built on the next draw, once the window size is known.
     pub fn set_page_image(&mut self, image: DynamicImage) {
-        if let Some(ref picker) = self.picker {
-            self.reader.page_image = Some(picker.new_resize_protocol(image));
-        }
+        self.reader.page_image = None;
+        // The cache keeps the downloaded file; only the displayed copy is processed
+        self.reader.page_source = Some(page_fit::enhance_page(image, self.config.page_enhance));
+        self.reader.page_scaled = None;
+        self.reader.page_view = None;
+        // Clamped to the end of the page on the next draw
+        self.reader.scroll_steps = if std::mem::take(&mut self.reader.scroll_from_end) {
+            u32::MAX
+        } else {
+            0
+        };
         self.reader.loading = false;
         self.reader.error = None;
     }
 
-    pub fn set_page_load_error(&mut self, error: String) {
-        self.reader.loading = false;
-        self.reader.error = Some(error);
+    /// Rebuilds `page_image` for an `area` of the terminal if the fit, size or scroll
+    /// changed since it was last built.
+    fn refresh_page_view(&mut self, area: Rect) {
+        let Some(source) = self.reader.page_source.as_ref() else {
+            return;
+        };
+        let fit = self.reader.fit;
+        let (font_w, font_h) = self.picker.font_size();
+        let viewport = (area.width as u32 * font_w as u32, area.height as u32 * font_h as u32);
+        if self.reader.page_view == Some((fit, viewport, self.reader.scroll_steps)) && self.reader.page_image.is_some() {
+            return;
+        }
+
+        let image = if fit == PageFit::Whole {
+            source.clone()
+        } else {
+            let filter = self.config.resize_filter.filter_type().unwrap_or(FilterType::Nearest);
+            if self.reader.page_scaled.as_ref().map(|(key, _)| *key) != Some((fit, viewport)) {
+                let scaled = page_fit::scale_page(source, fit, viewport, filter);
+                self.reader.page_scaled = Some(((fit, viewport), scaled));
+            }
+            let Some((_, scaled)) = self.reader.page_scaled.as_ref() else {
+                return;
+            };
+            let step = page_fit::scroll_step(fit, viewport);
+            let (view, max_scroll) = page_fit::crop_view(
+                scaled,
+                fit,
+                viewport,
+                self.reader.scroll_steps.saturating_mul(step),
+            );
+            // Don't let scrolling run on past the end of the page
+            self.reader.scroll_end = max_scroll.div_ceil(step);
+            self.reader.scroll_steps = self.reader.scroll_steps.min(self.reader.scroll_end);
+            view
+        };
+
+        self.reader.page_view = Some((fit, viewport, self.reader.scroll_steps));
+        self.reader.page_image = Some(self.page_protocol(&image));
     }
 
-    pub fn next_page(&mut self) -> bool {
-        if self.reader.current_page + 1 < self.reader.page_urls.len() {
-            self.reader.current_page += 1;
-            self.reader.loading = true;
-            self.reader.page_image = None;
-            self.reader.error = None;
-            true
+    pub fn cycle_page_fit(&mut self) {
+        self.reader.fit = self.reader.fit.next();
+        self.reader.scroll_steps = 0;
+        self.reader.page_scaled = None;
+        self.reader.page_view = None;
+        self.progress.set_reader_fit(self.reader.fit);
+        self.show_toast(&format!("Pages: {}", self.reader.fit.label()));
+    }
+
+    /// Scrolls a fit-width or fit-height page. Scrolling on past its end moves to the next
+    /// page, and from the last page into the next chapter; scrolling back past its top
+    /// moves to the end of the previous page, or of the previous chapter's last page.
+    pub fn scroll_page(&mut self, forward: bool) -> PageScroll {
+        if self.reader.fit == PageFit::Whole
+            || self.reader.loading
+            || self.reader.page_source.is_none()
+        {
+            return PageScroll::Stopped;
+        }
+        if let Some(steps) =
+            page_fit::scroll_within(self.reader.scroll_steps, self.reader.scroll_end, forward)
+        {
+            self.reader.scroll_steps = steps;
+            return PageScroll::Scrolled;
+        }
+
+        let moved = if forward {
+            if self.next_page_by(1) {
+                PageScroll::Page
+            } else if self.next_chapter() {
+                PageScroll::Chapter
+            } else {
+                PageScroll::Stopped
+            }
+        } else if self.prev_page_by(1) {
+            PageScroll::Page
+        } else if self.prev_chapter() {
+            // Clamped to the last page once the page list arrives
+            self.reader.resume_page = Some(usize::MAX);
+            PageScroll::Chapter
         } else {
-            false
+            PageScroll::Stopped
+        };
+        self.reader.scroll_from_end = !forward && moved != PageScroll::Stopped;
+        moved
+    }
+
+    /// Inverts on a copy so the cached original stays untouched.
+    fn page_protocol(&mut self, image: &DynamicImage) -> StatefulProtocol {
+        let mut image = image.clone();
+        if self.reader.invert {
+            image.invert();
         }
+        self.picker.new_resize_protocol(image)
     }
 
-    pub fn prev_page(&mut self) -> bool {
-        if self.reader.current_page > 0 {
-            self.reader.current_page -= 1;
+    /// Flips color inversion for the reader and remembers the choice for this manga.
+    pub fn toggle_invert(&mut self) {
+        self.reader.invert = !self.reader.invert;
+        if let Some(manga) = &self.reader.manga {
+            self.progress.set_inverted(&manga.id, self.reader.invert);
+        }
+        // Rebuilt with the new colors on the next draw
+        self.reader.page_view = None;
+    }
+
+    pub fn add_page_thumbnail(&mut self, page_url: &str, image: DynamicImage) {
+        let protocol = self.picker.new_resize_protocol(image);
+        self.reader.page_thumbnails.insert(page_url.to_string(), protocol);
+    }
+
+    pub fn jump_to_page(&mut self, page: usize) -> bool {
+        if page < self.reader.page_urls.len() && page != self.reader.current_page {
+            self.reader.current_page = page;
+            self.reader.scroll_from_end = false;
             self.reader.loading = true;
             self.reader.page_image = None;
+            self.reader.page_source = None;
             self.reader.error = None;
+            self.record_page_time();
+            self.track_read_progress();
             true
         } else {
             false
         }
     }
 
+    /// Saves the reading position and marks the current chapter read once the reader has
+    /// reached `auto_mark_read_ratio` of its pages (the last page, by default).
+    pub fn track_read_progress(&mut self) {
+        let page_count = self.reader.page_urls.len();
+        if page_count == 0 {
+            return;
+        }
+
+        let (Some(manga), Some(chapter)) = (
+            self.reader.manga.as_ref(),
+            self.reader.chapters.get(self.reader.current_chapter_idx),
+        ) else {
+            return;
+        };
+        self.reader.page_since = Some(std::time::Instant::now());
+        self.progress.record_position(manga, &chapter.id, self.reader.current_page);
+
+        if chapter_finished(self.reader.current_page, page_count, self.config.auto_mark_read_ratio, false) {
+            self.mark_reading_chapter_read();
+        }
+    }
+
+    fn mark_reading_chapter_read(&mut self) {
+        let (Some(manga), Some(chapter)) = (
+            self.reader.manga.as_ref(),
+            self.reader.chapters.get(self.reader.current_chapter_idx),
+        ) else {
+            return;
+        };
+        self.progress.mark_read(manga, &chapter.id);
+        self.progress.update_completion(&manga.id, &self.reader.chapters);
+    }
+
+    /// Credits the time spent on the page being left to the reading-time stat. A page
+    /// left open while away counts for at most `MAX_PAGE_READING_SECS`.
+    fn record_page_time(&mut self) {
+        if let Some(since) = self.reader.page_since.take() {
+            self.progress.add_reading_time(since.elapsed().as_secs().min(MAX_PAGE_READING_SECS));
+        }
+    }
+
+    pub fn open_stats(&mut self) {
+        self.view = View::Stats;
+    }
+
+    pub fn export_stats(&mut self) {
+        match self.progress.export_stats() {
+            Ok(path) => self.show_toast(&format!("Stats exported to {}", path.display())),
+            Err(e) => self.show_toast(&format!("Couldn't export stats: {}", e)),
+        }
+    }
+
+    /// Gathers what's known about the chapter being read into a report for MangaDex or
+    /// the issue tracker, then copies it and saves it to the data directory.
+    pub fn report_broken_chapter(&mut self) {
+        let Some(chapter) = self.reader.chapters.get(self.reader.current_chapter_idx) else {
+            return;
+        };
+
+        let mut report = vec!["Broken chapter report".to_string()];
+        if let Some(manga) = &self.reader.manga {
+            report.push(format!("Manga: {} ({})", manga.title, manga.id));
+        }
+        report.push(format!("Chapter: {} - {} ({})", chapter.long_label(), chapter.title, chapter.id));
+        if let Some(url) = source::for_id(&chapter.id).chapter_url(&chapter.id) {
+            report.push(format!("Web page: {}", url));
+        }
+        report.push(format!("Pages listed: {}", self.reader.page_urls.len()));
+        report.push(format!("Last error: {}", self.reader.error.as_deref().unwrap_or("none")));
+        if !self.reader.failed_pages.is_empty() {
+            report.push("Failed pages:".to_string());
+            report.extend(self.reader.failed_pages.iter().map(|url| format!("  {}", url)));
+        }
+        let log = log_file::recent_lines(&log_file::log_path(), BROKEN_CHAPTER_LOG_LINES);
+        if !log.is_empty() {
+            report.push("Recent log:".to_string());
+            report.extend(log.into_iter().map(|line| format!("  {}", line)));
+        }
+        let report = report.join("\n");
+
+        // Local chapter IDs are `local:` plus a path, which can't go into a file name as is
+        let file_id: String = chapter
+            .id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+            .collect();
+        let path = app_data_dir().join(format!("chapter-report-{}.txt", file_id));
+        let saved = fs::write(&path, &report);
+        let copied = clipboard::copy(&report);
+        let message = match (saved, copied) {
+            (Ok(()), Ok(())) => format!("Report copied and saved to {}", path.display()),
+            (Ok(()), Err(_)) => format!("Report saved to {}", path.display()),
+            (Err(_), Ok(())) => "Report copied to the clipboard".to_string(),
+            (Err(e), Err(_)) => format!("Couldn't save the report: {}", e),
+        };
+        self.show_toast(&message);
+    }
+
+    pub fn set_page_load_error(&mut self, error: String) {
+        self.reader.loading = false;
+        self.reader.error = Some(error);
+    }
+
+    pub fn next_page(&mut self) -> bool {
+        self.next_page_by(self.config.page_step)
+    }
+
+    pub fn prev_page(&mut self) -> bool {
+        self.prev_page_by(self.config.page_step)
+    }
+
+    /// Advances up to `step` pages, stopping at the last page of the chapter.
+    pub fn next_page_by(&mut self, step: usize) -> bool {
+        let last = self.reader.page_urls.len().saturating_sub(1);
+        let target = (self.reader.current_page + step.max(1)).min(last);
+        self.jump_to_page(target)
+    }
+
+    /// Goes back up to `step` pages, stopping at the first page of the chapter.
+    pub fn prev_page_by(&mut self, step: usize) -> bool {
+        let target = self.reader.current_page.saturating_sub(step.max(1));
+        self.jump_to_page(target)
+    }
+
+    /// Opens the next chapter, marking the one being left read.
     pub fn next_chapter(&mut self) -> bool {
-        if self.reader.current_chapter_idx + 1 < self.reader.chapters.len() {
-            self.reader.current_chapter_idx += 1;
-            self.reader.current_page = 0;
-            self.reader.page_urls.clear();
-            self.reader.page_image = None;
-            self.reader.loading = true;
-            self.reader.error = None;
-            true
-        } else {
-            false
+        let next = self.reader.current_chapter_idx + 1;
+        if next >= self.reader.chapters.len() {
+            return false;
+        }
+        let (page, page_count) = (self.reader.current_page, self.reader.page_urls.len());
+        if chapter_finished(page, page_count, self.config.auto_mark_read_ratio, true) {
+            self.mark_reading_chapter_read();
+        }
+        self.go_to_chapter(next)
+    }
+
+    /// With `auto_advance_chapter`, paging on from the last page opens the next chapter.
+    pub fn advance_past_chapter_end(&mut self) -> bool {
+        self.config.auto_advance_chapter && !self.reader.page_urls.is_empty() && self.next_chapter()
+    }
+
+    /// With `auto_advance_chapter`, paging back from the first page opens the previous
+    /// chapter at its last page.
+    pub fn retreat_past_chapter_start(&mut self) -> bool {
+        if !self.config.auto_advance_chapter || self.reader.page_urls.is_empty() || !self.prev_chapter() {
+            return false;
+        }
+        // Clamped to the last page once the page list arrives
+        self.reader.resume_page = Some(usize::MAX);
+        true
+    }
+
+    /// What `n` says on the last chapter: a send-off for finished series, and a nudge to
+    /// bookmark ongoing ones that aren't followed yet.
+    pub fn last_chapter_message(&self) -> &'static str {
+        match &self.reader.manga {
+            Some(manga) if manga.status.eq_ignore_ascii_case("completed") => {
+                "🎉 Series complete! That was the final chapter"
+            }
+            Some(manga) if !self.bookmarks.is_bookmarked(&manga.id) => {
+                "You've reached the latest chapter. Bookmark it (b on its page) to hear about new ones"
+            }
+            _ => "You've reached the latest chapter",
         }
     }
 
     pub fn prev_chapter(&mut self) -> bool {
-        if self.reader.current_chapter_idx > 0 {
-            self.reader.current_chapter_idx -= 1;
-            self.reader.current_page = 0;
-            self.reader.page_urls.clear();
-            self.reader.page_image = None;
-            self.reader.loading = true;
-            self.reader.error = None;
-            true
+        match self.reader.current_chapter_idx.checked_sub(1) {
+            Some(idx) => self.go_to_chapter(idx),
+            None => false,
+        }
+    }
+
+    /// Switches the reader to another chapter of the same manga. Partially read chapters
+    /// resume at their saved page; others start at the first page.
+    pub fn go_to_chapter(&mut self, chapter_idx: usize) -> bool {
+        if chapter_idx >= self.reader.chapters.len() || chapter_idx == self.reader.current_chapter_idx {
+            return false;
+        }
+
+        let chapter_id = &self.reader.chapters[chapter_idx].id;
+        self.reader.resume_page = if self.progress.is_read(chapter_id) {
+            None
         } else {
-            false
+            self.progress.saved_page(chapter_id)
+        };
+
+        self.record_page_time();
+        self.progress.flush();
+        self.reader.cancel_prefetch();
+        self.reader.current_chapter_idx = chapter_idx;
+        self.reader.current_page = 0;
+        self.reader.scroll_from_end = false;
+        self.reader.page_urls.clear();
+        self.reader.cached_pages.clear();
+        self.reader.failed_pages.clear();
+        self.reader.page_image = None;
+        self.reader.page_source = None;
+        self.reader.page_thumbnails.clear();
+        self.reader.page_jump_input.clear();
+        self.reader.loading = true;
+        self.reader.error = None;
+        true
+    }
+
+    pub fn toggle_chapter_list(&mut self) {
+        if self.reader.chapter_list.take().is_none() {
+            let mut state = ListState::default();
+            state.select(Some(self.reader.current_chapter_idx));
+            self.reader.chapter_list = Some(state);
+        }
+    }
+
+    pub fn toggle_auto_scroll(&mut self) {
+        if self.reader.auto_scroll.take().is_some() {
+            self.show_toast("Auto-scroll paused");
+        } else {
+            self.reader.auto_scroll = Some(std::time::Instant::now());
+            self.show_toast(&format!("Auto-scroll every {}s", self.config.auto_scroll_secs.max(1)));
+        }
+    }
+
+    /// Whether auto-scroll has shown the current page for long enough. The timer only
+    /// runs once the page has loaded, so slow downloads don't get skipped past.
+    pub fn auto_scroll_due(&mut self) -> bool {
+        let loaded = !self.reader.loading && self.reader.page_source.is_some();
+        match self.reader.auto_scroll.as_mut() {
+            Some(started) if !loaded => {
+                *started = std::time::Instant::now();
+                false
+            }
+            Some(started) => started.elapsed().as_secs() >= self.config.auto_scroll_secs.max(1),
+            None => false,
+        }
+    }
+
+    fn remember_grid_position(&mut self) {
+        let (Some(manga), Some(chapter)) = (&self.selected_manga, self.chapters.get(self.chapter_selected)) else {
+            return;
+        };
+        let position = GridPosition { chapter_id: chapter.id.clone(), scroll_row: self.chapter_scroll_row };
+        self.progress.set_grid_position(&manga.id, position);
+    }
+
+    /// Moves the grid cursor back to where it was left on this manga, or to the chapter
+    /// last read. Called once a manga's chapters have loaded.
+    pub fn restore_grid_position(&mut self) {
+        let Some(manga) = &self.selected_manga else {
+            return;
+        };
+        let Some((chapter_id, scroll_row)) = self.progress.grid_anchor(&manga.id) else {
+            return;
+        };
+        if let Some(idx) = self.chapters.iter().position(|c| c.id == chapter_id) {
+            self.chapter_selected = idx;
+            // Drawing scrolls further if the chapter isn't on screen from here
+            self.chapter_scroll_row = scroll_row.unwrap_or(0);
         }
     }
 
     pub fn go_back(&mut self) {
         match self.view {
-            View::Reader => self.view = View::MangaDetail,
+            View::Reader => {
+                self.reader.cancel_prefetch();
+                self.reader.auto_scroll = None;
+                self.record_page_time();
+                self.progress.flush();
+                self.view = View::MangaDetail;
+            }
             View::MangaDetail => {
+                self.remember_grid_position();
+                // Chapters stop being new once their page has been left
+                if let Some(manga) = &self.selected_manga
+                    && self.bookmarks.is_bookmarked(&manga.id)
+                {
+                    self.progress.mark_chapters_seen(&manga.id, &self.chapters);
+                }
+                self.chapter_selection_anchor = None;
+                // A pending chapter refresh would be ignored once the page is closed
+                self.refreshing = false;
                 self.view = View::Home;
                 self.selected_manga = None;
                 self.chapters.clear();
             }
+            View::Stats => self.view = View::Home,
             View::Home => {}
         }
     }
 }
 
 const CARD_WIDTH: u16 = 35;
+/// Height / width of a typical MangaDex cover
+const COVER_ASPECT: f32 = 1.42;
+const TOAST_DURATION_MS: u128 = 3000;
+/// Row steps closer together than this count as scrolling fast
+const FAST_SCROLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+/// Longest time one page adds to the reading-time stat
+const MAX_PAGE_READING_SECS: u64 = 5 * 60;
+/// Covers kept ready to draw; evicted ones are fetched again when scrolled back into view
+pub const MAX_COVER_PROTOCOLS: usize = 96;
+/// Lines of the log file included in a broken chapter report
+const BROKEN_CHAPTER_LOG_LINES: usize = 40;
+const MAX_CHAPTER_THUMBNAILS: usize = 48;
+/// Thumbnails preloaded around the focused card, kept under `MAX_CHAPTER_THUMBNAILS` so
+/// the first ones aren't evicted before the last arrive
+const THUMBNAIL_PRELOAD_WINDOW: usize = 32;
+const VOLUME_HEADER_HEIGHT: u16 = 1;
 
 pub fn ui(f: &mut Frame, app: &mut App) {
     match app.state {
         AppState::Loading => draw_loading_screen(f, app),
+        AppState::Offline => draw_offline_screen(f, app),
         AppState::Ready => match app.view {
+            View::Home if app.comparing => draw_compare(f, app),
             View::Home => draw_main_ui(f, app),
+            View::MangaDetail if app.cover_zoom => draw_cover_zoom(f, app),
             View::MangaDetail => draw_manga_detail(f, app),
             View::Reader => draw_reader(f, app),
+            View::Stats => draw_stats(f, app),
         },
     }
+
+    if is_verbose_network() {
+        draw_request_counter(f);
+    }
+    if app.explicit_prompt {
+        draw_prompt(
+            f,
+            "Explicit content",
+            "Some manga here are rated pornographic.\nShow explicit content? (y/n)",
+        );
+    }
+    if app.quit_prompt {
+        let message = if app.downloads_active() {
+            "Downloads in progress.\nQuit anyway? (y/n)"
+        } else {
+            "Quit? (y/n)"
+        };
+        draw_prompt(f, "Quit", message);
+    }
+    if let Some(pending) = &app.resume_download_prompt {
+        let message = format!(
+            "Download stopped at {} of {} chapters.\nResume it? (y/n)",
+            pending.done,
+            pending.total()
+        );
+        draw_prompt(f, "Unfinished download", &message);
+    }
+    if let Some(palette) = &app.command_palette {
+        draw_command_palette(f, palette, app.view);
+    }
+    draw_toast(f, app);
+}
+
+fn draw_command_palette(f: &mut Frame, palette: &CommandPalette, view: View) {
+    let area = f.area();
+    let width = 50.min(area.width);
+    let height = 14.min(area.height);
+    // Nearer the top than the middle, like most palettes
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 4,
+        width,
+        height,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Commands")
+        .title_bottom(" ↑/↓: select | Enter: run | Esc: close ")
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(popup);
+    f.render_widget(Clear, popup);
+    f.render_widget(block, popup);
+    if inner.height < 2 {
+        return;
+    }
+
+    let input = Line::from(vec![
+        Span::styled("> ", Style::default().fg(Color::Yellow)),
+        Span::raw(palette.query.as_str()),
+        Span::raw("▌"),
+    ]);
+    f.render_widget(Paragraph::new(input), Rect::new(inner.x, inner.y, inner.width, 1));
+
+    let matches = palette.matches(view);
+    let items: Vec<ListItem> = if matches.is_empty() {
+        vec![ListItem::new("No matching commands").style(Style::default().fg(Color::DarkGray))]
+    } else {
+        matches.iter().map(|c| ListItem::new(c.label())).collect()
+    };
+    let mut state = ListState::default();
+    if !matches.is_empty() {
+        state.select(Some(palette.selected.min(matches.len() - 1)));
+    }
+    let list = List::new(items).highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+    let list_area = Rect::new(inner.x, inner.y + 1, inner.width, inner.height - 1);
+    f.render_stateful_widget(list, list_area, &mut state);
+}
+
+/// A centered yes/no question over the current view.
+fn draw_prompt(f: &mut Frame, title: &str, message: &str) {
+    let area = f.area();
+    let width = 44.min(area.width);
+    let height = 5.min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let prompt = Paragraph::new(message.to_string())
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title.to_string())
+                .border_style(Style::default().fg(Color::Red)),
+        );
+    f.render_widget(Clear, popup);
+    f.render_widget(prompt, popup);
+}
+
+fn draw_request_counter(f: &mut Frame) {
+    let area = f.area();
+    let text = format!(" {} requests ", request_count());
+    let width = (text.width() as u16).min(area.width);
+    let counter_area = Rect::new(area.x + area.width - width, area.y, width, 1);
+
+    let counter = Paragraph::new(text).style(Style::default().fg(Color::Black).bg(Color::Magenta));
+    f.render_widget(counter, counter_area);
+}
+
+fn draw_toast(f: &mut Frame, app: &mut App) {
+    let expired = match &app.toast {
+        Some((_, shown_at)) => shown_at.elapsed().as_millis() >= TOAST_DURATION_MS,
+        None => return,
+    };
+    if expired {
+        app.toast = None;
+        return;
+    }
+
+    let Some((message, _)) = &app.toast else {
+        return;
+    };
+
+    let area = f.area();
+    let width = (message.width() as u16 + 4).min(area.width);
+    let height = 3.min(area.height);
+    // Sit just above the footer, centered horizontally
+    let toast_area = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + area.height.saturating_sub(height + 3),
+        width,
+        height,
+    );
+
+    let toast = Paragraph::new(message.as_str())
+        .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+    f.render_widget(Clear, toast_area);
+    f.render_widget(toast, toast_area);
 }
 
 fn draw_loading_screen(f: &mut Frame, app: &App) {
@@ -342,6 +1523,147 @@ fn draw_loading_screen(f: &mut Frame, app: &App) {
     f.render_widget(message, center_layout[2]);
 }
 
+/// The open manga's cover filling the screen, reusing the protocol from the details page.
+fn draw_cover_zoom(f: &mut Frame, app: &mut App) {
+    let Some(manga) = app.selected_manga.as_ref() else {
+        return;
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("{} | z/Esc: close", manga.title))
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(f.area());
+    f.render_widget(block, f.area());
+
+    let filter = app.config.resize_filter.filter_type();
+    if let Some(state) = app.image_states.get_mut(&manga.id) {
+        // Scale rather than Fit, so the small cover thumbnail is enlarged to fill the screen
+        f.render_stateful_widget(StatefulImage::new().resize(Resize::Scale(filter)), inner, state);
+    } else {
+        let placeholder = Paragraph::new("📚 Loading cover...")
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(placeholder, inner);
+    }
+}
+
+/// The two manga pinned in Search, side by side.
+fn draw_compare(f: &mut Frame, app: &mut App) {
+    let filter = app.config.resize_filter.filter_type();
+    let root = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // header
+            Constraint::Min(10),   // panels
+            Constraint::Length(3), // footer
+        ])
+        .split(f.area());
+
+    let header = Paragraph::new("Compare")
+        .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)));
+    f.render_widget(header, root[0]);
+
+    let panels = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(root[1]);
+    for (i, (manga, area)) in app.compare_pins.iter().zip(panels.iter()).enumerate() {
+        let title = format!("{}: {}", i + 1, manga.title);
+        draw_manga_info(f, *area, &title, manga, app.image_states.get_mut(&manga.id), None, filter);
+    }
+
+    draw_footer(f, root[2], "1/2: open | Esc: back | q: quit");
+}
+
+fn draw_stats(f: &mut Frame, app: &App) {
+    let root = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(8), Constraint::Length(3)])
+        .split(f.area());
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Reading Stats")
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let stats = app.progress.stats_summary();
+    let label = |text: &'static str| Span::styled(text, Style::default().fg(Color::Yellow));
+    let mut lines = vec![
+        Line::from(vec![label("Chapters read:   "), Span::raw(stats.chapters_read.to_string())]),
+        Line::from(vec![label("Pages read:      "), Span::raw(stats.pages_read.to_string())]),
+        Line::from(vec![label("Manga completed: "), Span::raw(stats.manga_completed.to_string())]),
+        Line::from(vec![label("Reading time:    "), Span::raw(format_duration(stats.reading_secs))]),
+        Line::from(""),
+        Line::from(Span::styled("Most read", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))),
+    ];
+    if stats.most_read.is_empty() {
+        lines.push(Line::from(Span::styled("Nothing read yet", Style::default().fg(Color::DarkGray))));
+    }
+    for (i, series) in stats.most_read.iter().enumerate() {
+        lines.push(Line::from(vec![
+            Span::raw(format!("{}. {} ", i + 1, series.title)),
+            Span::styled(format!("({} chapters)", series.chapters_read), Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+
+    f.render_widget(Paragraph::new(lines).block(block), root[0]);
+    draw_footer(f, root[1], "e: export to JSON | Esc: back | q: quit");
+}
+
+/// Hours and minutes, e.g. "3h 12m".
+fn format_duration(secs: u64) -> String {
+    let minutes = secs / 60;
+    if minutes >= 60 {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn draw_offline_screen(f: &mut Frame, app: &App) {
+    let area = f.area();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Manga Reader")
+        .border_style(Style::default().fg(Color::Red));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let center_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(40),
+            Constraint::Length(2),
+            Constraint::Length(2),
+            Constraint::Length(2),
+            Constraint::Percentage(40),
+        ])
+        .split(inner);
+
+    let title = Paragraph::new("Couldn't reach MangaDex")
+        .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center);
+    f.render_widget(title, center_layout[1]);
+
+    let error = Paragraph::new(truncate_text(&app.loading_message, inner.width as usize))
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+    f.render_widget(error, center_layout[2]);
+
+    let hint = Line::from(vec![
+        Span::raw("Press "),
+        Span::styled("r", Style::default().fg(Color::Yellow)),
+        Span::raw(" to retry or "),
+        Span::styled("q", Style::default().fg(Color::Yellow)),
+        Span::raw(" to quit"),
+    ]);
+    f.render_widget(Paragraph::new(hint).alignment(Alignment::Center), center_layout[3]);
+}
+
 fn draw_main_ui(f: &mut Frame, app: &mut App) {
     let area = f.area();
 
@@ -360,58 +1682,105 @@ fn draw_main_ui(f: &mut Frame, app: &mut App) {
         Tab::Home => draw_home_content(f, root[1], app),
         Tab::Bookmarks => draw_bookmarks_content(f, root[1], app),
         Tab::Search => draw_search_content(f, root[1], app),
+        Tab::Local => draw_local_content(f, root[1], app),
     }
 
     let footer_text = match app.tab {
-        Tab::Home => "Tab: section | ←/→: scroll | ↑/↓: focus | Enter: select | q: quit",
-        Tab::Bookmarks => "←/→: scroll | Enter: select | q: quit",
-        Tab::Search => "Type to search | Enter: search | ←/→: scroll results | q: quit",
+        Tab::Home => "Tab: section | ←/→: scroll | ↑/↓: focus | Enter: select | t: status | R: refresh | q: quit",
+        Tab::Bookmarks if app.bookmark_filter_active => "Type to filter | Enter: done | Esc: clear",
+        Tab::Bookmarks if app.bookmark_jump_active => "Press a letter to jump to titles starting with it | Esc: cancel",
+        Tab::Bookmarks => "←/→: scroll | Enter: select | l: read latest | p: pin | t: status | g: jump to letter | /: filter | q: quit",
+        Tab::Search => "Type to search | Enter: search | ←/→: scroll results | s: sort results | p: pin to compare | c: compare | t: status | q: quit",
+        Tab::Local => "←/→: scroll | Enter: select | r: rescan | t: status | q: quit",
     };
     draw_footer(f, root[2], footer_text);
 }
 
 fn draw_home_content(f: &mut Frame, area: Rect, app: &mut App) {
+    let cover_rows = app.cover_rows(CARD_WIDTH - 2);
+    let continuing = app.continue_reading_manga();
+
+    let mut constraints = vec![
+        Constraint::Ratio(1, 2), // recently updated
+        Constraint::Ratio(1, 2), // popular now
+    ];
+    if !continuing.is_empty() {
+        constraints = vec![Constraint::Ratio(1, 3); 3];
+    }
     let content_layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage(50), // recently updated
-            Constraint::Percentage(50), // popular now
-        ])
+        .constraints(constraints)
         .split(area);
+    let mut sections = content_layout.iter().copied();
 
+    if !continuing.is_empty() {
+        draw_manga_section(
+            f,
+            sections.next().unwrap_or(area),
+            "Continue Reading",
+            &continuing,
+            &mut app.continue_offset,
+            app.focus == Focus::Continue,
+            &mut app.image_states,
+            &app.progress,
+            &app.bookmarks,
+            app.config.resize_filter.filter_type(),
+            cover_rows,
+        );
+    }
     draw_manga_section(
         f,
-        content_layout[0],
+        sections.next().unwrap_or(area),
         "Recently Updated",
         &app.recently_updated,
         &mut app.recent_offset,
         app.focus == Focus::Recent,
         &mut app.image_states,
+        &app.progress,
+        &app.bookmarks,
+        app.config.resize_filter.filter_type(),
+        cover_rows,
     );
     draw_manga_section(
         f,
-        content_layout[1],
+        sections.next().unwrap_or(area),
         "Popular Now",
         &app.popular_now,
         &mut app.popular_offset,
         app.focus == Focus::Popular,
         &mut app.image_states,
+        &app.progress,
+        &app.bookmarks,
+        app.config.resize_filter.filter_type(),
+        cover_rows,
     );
 }
 
 fn draw_bookmarks_content(f: &mut Frame, area: Rect, app: &mut App) {
-    let bookmarked = app.bookmarks.get_bookmarked_manga();
+    let bookmarked = app.filtered_bookmarks();
+
+    let title = if app.bookmark_filter_active || !app.bookmark_filter.is_empty() {
+        let cursor = if app.bookmark_filter_active { "▌" } else { "" };
+        format!("Bookmarks ({}) | Filter: {}{}", bookmarked.len(), app.bookmark_filter, cursor)
+    } else {
+        format!("Bookmarks ({})", bookmarked.len())
+    };
     
     let block = Block::default()
         .borders(Borders::ALL)
-        .title(format!("Bookmarks ({})", bookmarked.len()))
+        .title(title)
         .border_style(Style::default().fg(Color::Yellow));
 
     let inner = block.inner(area);
     f.render_widget(block, area);
 
     if bookmarked.is_empty() {
-        let empty_msg = Paragraph::new("No bookmarks yet. Press 'b' on a manga to bookmark it.")
+        let msg = if app.bookmark_filter.is_empty() {
+            "No bookmarks yet. Press 'b' on a manga to bookmark it.".to_string()
+        } else {
+            format!("No bookmarks match \"{}\"", app.bookmark_filter)
+        };
+        let empty_msg = Paragraph::new(msg)
             .alignment(Alignment::Center)
             .style(Style::default().fg(Color::DarkGray));
         f.render_widget(empty_msg, inner);
@@ -426,6 +1795,7 @@ fn draw_bookmarks_content(f: &mut Frame, area: Rect, app: &mut App) {
 
     let available_width = inner.width as usize;
     let cards_visible = (available_width / CARD_WIDTH as usize).max(1);
+    let cover_rows = app.cover_rows(CARD_WIDTH - 2);
 
     let card_constraints: Vec<Constraint> = (0..cards_visible)
         .map(|_| Constraint::Length(CARD_WIDTH))
@@ -447,8 +1817,29 @@ fn draw_bookmarks_content(f: &mut Frame, area: Rect, app: &mut App) {
             *card_area,
             manga,
             i == 0,
+            app.progress.is_completed(&manga.id),
+            app.bookmarks.is_pinned(&manga.id),
+            app.bookmarks.status(&manga.id),
             app.image_states.get_mut(&manga.id),
+            app.config.resize_filter.filter_type(),
+            cover_rows,
         );
+
+        if app.bookmarks.has_updates(&manga.id) && card_area.height > 2 {
+            let badge = " NEW ";
+            let badge_area = Rect::new(card_area.x + card_area.width - badge.len() as u16 - 1, card_area.y, badge.len() as u16, 1);
+            let label = Paragraph::new(badge).style(Style::default().fg(Color::Black).bg(Color::Green).add_modifier(Modifier::BOLD));
+            f.render_widget(label, badge_area);
+        }
+
+        if app.quick_open.as_deref() == Some(manga.id.as_str()) && card_area.height > 2 {
+            let label_area = Rect::new(card_area.x + 1, card_area.y + card_area.height - 2, card_area.width - 2, 1);
+            f.render_widget(Clear, label_area);
+            let label = Paragraph::new("⏳ Opening latest...")
+                .style(Style::default().fg(Color::Black).bg(Color::Yellow))
+                .alignment(Alignment::Center);
+            f.render_widget(label, label_area);
+        }
     }
 
     // Scroll indicators
@@ -462,6 +1853,45 @@ fn draw_bookmarks_content(f: &mut Frame, area: Rect, app: &mut App) {
     }
 }
 
+fn draw_local_content(f: &mut Frame, area: Rect, app: &mut App) {
+    let message = match app.config.local_library_dir() {
+        None => Some("Set `local_library` in config.json to a folder of manga to read them here".to_string()),
+        Some(_) if app.local_scanning => Some("⏳ Scanning local library...".to_string()),
+        Some(dir) if app.local_library.is_empty() => {
+            Some(format!("No manga found in {}. Press r to rescan", dir.display()))
+        }
+        Some(_) => None,
+    };
+
+    if let Some(message) = message {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Local Library")
+            .border_style(Style::default().fg(Color::Yellow));
+        let empty = Paragraph::new(message)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let cover_rows = app.cover_rows(CARD_WIDTH - 2);
+    draw_manga_section(
+        f,
+        area,
+        &format!("Local Library ({})", app.local_library.len()),
+        &app.local_library,
+        &mut app.local_offset,
+        app.focus != Focus::Header,
+        &mut app.image_states,
+        &app.progress,
+        &app.bookmarks,
+        app.config.resize_filter.filter_type(),
+        cover_rows,
+    );
+}
+
 fn draw_search_content(f: &mut Frame, area: Rect, app: &mut App) {
     let layout = Layout::default()
         .direction(Direction::Vertical)
@@ -481,12 +1911,16 @@ fn draw_search_content(f: &mut Frame, area: Rect, app: &mut App) {
     let cursor = if app.focus == Focus::Header { "▌" } else { "" };
     let search_text = format!("🔍 {}{}", app.search_query, cursor);
     
+    let search_title = match &app.search_tag {
+        Some(tag) => format!("Search Manga | Tag: {} (Esc or Backspace to clear)", tag.name),
+        None => "Search Manga".to_string(),
+    };
     let search_input = Paragraph::new(search_text)
         .style(search_style)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Search Manga")
+                .title(search_title)
                 .border_style(Style::default().fg(Color::Cyan)),
         );
     f.render_widget(search_input, layout[0]);
@@ -497,7 +1931,11 @@ fn draw_search_content(f: &mut Frame, area: Rect, app: &mut App) {
         .title(if app.searching {
             "Searching...".to_string()
         } else {
-            format!("Results ({})", app.search_results.len())
+            format!(
+                "Results ({}) | Sorted by {}",
+                app.search_results.len(),
+                app.search_order.label()
+            )
         })
         .border_style(Style::default().fg(Color::Yellow));
 
@@ -520,7 +1958,7 @@ fn draw_search_content(f: &mut Frame, area: Rect, app: &mut App) {
     }
 
     if app.search_results.is_empty() {
-        let msg = if app.search_query.is_empty() {
+        let msg = if app.search_query.is_empty() && app.search_tag.is_none() {
             "Type a manga name and press Enter to search"
         } else {
             "No results found"
@@ -540,6 +1978,7 @@ fn draw_search_content(f: &mut Frame, area: Rect, app: &mut App) {
 
     let available_width = inner.width as usize;
     let cards_visible = (available_width / CARD_WIDTH as usize).max(1);
+    let cover_rows = app.cover_rows(CARD_WIDTH - 2);
 
     let card_constraints: Vec<Constraint> = (0..cards_visible)
         .map(|_| Constraint::Length(CARD_WIDTH))
@@ -561,7 +2000,12 @@ fn draw_search_content(f: &mut Frame, area: Rect, app: &mut App) {
             *card_area,
             manga,
             i == 0,
+            app.progress.is_completed(&manga.id),
+            app.is_compare_pinned(&manga.id),
+            app.bookmarks.status(&manga.id),
             app.image_states.get_mut(&manga.id),
+            app.config.resize_filter.filter_type(),
+            cover_rows,
         );
     }
 
@@ -578,6 +2022,7 @@ fn draw_search_content(f: &mut Frame, area: Rect, app: &mut App) {
 
 fn draw_manga_detail(f: &mut Frame, app: &mut App) {
     let area = f.area();
+    let filter = app.config.resize_filter.filter_type();
 
     let manga = match &app.selected_manga {
         Some(m) => m,
@@ -599,7 +2044,20 @@ fn draw_manga_detail(f: &mut Frame, app: &mut App) {
     } else {
         ""
     };
-    let header_text = format!("{}{}", manga.title, bookmark_indicator);
+    let completed_indicator = if app.progress.is_completed(&manga.id) {
+        " ✓ Completed"
+    } else {
+        ""
+    };
+    let download_indicator = app
+        .batch_download
+        .as_ref()
+        .map(|d| format!(" | Downloading {}/{} chapters", d.done, d.total))
+        .unwrap_or_default();
+    let header_text = format!(
+        "{}{}{}{}",
+        manga.title, bookmark_indicator, completed_indicator, download_indicator
+    );
     let header = Paragraph::new(header_text)
         .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center)
@@ -608,67 +2066,33 @@ fn draw_manga_detail(f: &mut Frame, app: &mut App) {
                 .borders(Borders::ALL)
                 .title("Manga Details")
                 .border_style(Style::default().fg(Color::Cyan)),
-        );
-    f.render_widget(header, root[0]);
-
-    // Content: manga info + chapters list
-    let content_layout = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Length(40), // manga info
-            Constraint::Min(20),    // chapters list
-        ])
-        .split(root[1]);
-
-    // Manga info panel
-    let info_block = Block::default()
-        .borders(Borders::ALL)
-        .title("Info")
-        .border_style(Style::default().fg(Color::Yellow));
-
-    let info_inner = info_block.inner(content_layout[0]);
-    f.render_widget(info_block, content_layout[0]);
-
-    let info_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(12), // cover image
-            Constraint::Min(5),     // details
-        ])
-        .split(info_inner);
-
-    // Cover image
-    if let Some(state) = app.image_states.get_mut(&manga.id) {
-        let image_widget = StatefulImage::new().resize(Resize::Fit(None));
-        f.render_stateful_widget(image_widget, info_layout[0], state);
-    } else {
-        let placeholder = Paragraph::new("📚 Loading cover...")
-            .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::DarkGray));
-        f.render_widget(placeholder, info_layout[0]);
-    }
+        );
+    f.render_widget(header, root[0]);
 
-    // Manga details
-    let details = vec![
-        Line::from(vec![
-            Span::styled("Author: ", Style::default().fg(Color::Yellow)),
-            Span::raw(&manga.author),
-        ]),
-        Line::from(vec![
-            Span::styled("Status: ", Style::default().fg(Color::Yellow)),
-            Span::styled(&manga.status, Style::default().fg(Color::Cyan)),
-        ]),
-        Line::from(""),
-        Line::from(Span::styled("Description:", Style::default().fg(Color::Yellow))),
-        Line::from(truncate_text(&manga.description, 35)),
-    ];
-    let details_paragraph = Paragraph::new(details);
-    f.render_widget(details_paragraph, info_layout[1]);
+    // Content: manga info + chapters list
+    let info_width = chapter_grid::info_panel_width(root[1].width, app.config.info_panel_percent);
+    let content_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(info_width), // manga info
+            Constraint::Min(20),    // chapters list
+        ])
+        .split(root[1]);
+
+    draw_manga_info(
+        f,
+        content_layout[0],
+        "Info",
+        manga,
+        app.image_states.get_mut(&manga.id),
+        app.selected_tag,
+        filter,
+    );
 
     // Chapters panel with 2D grid
     let chapters_block = Block::default()
         .borders(Borders::ALL)
-        .title(format!("Chapters ({}) ←↑↓→ to navigate", app.chapters.len()))
+        .title(format!("Chapters ({}, {}) ←↑↓→ to navigate", app.chapters.len(), app.chapter_sort.label()))
         .border_style(Style::default().fg(Color::Yellow));
 
     let chapters_inner = chapters_block.inner(content_layout[1]);
@@ -682,10 +2106,9 @@ fn draw_manga_detail(f: &mut Frame, app: &mut App) {
     } else {
         // Calculate grid dimensions
         const CHAPTER_CARD_WIDTH: u16 = 22;
-        const CHAPTER_CARD_HEIGHT: u16 = 12;
+        let card_height: u16 = if app.show_chapter_thumbnails { 12 } else { 6 };
         
         let cols = (chapters_inner.width / CHAPTER_CARD_WIDTH).max(1) as usize;
-        let rows = (chapters_inner.height / CHAPTER_CARD_HEIGHT).max(1) as usize;
         
         // Store cols for navigation
         app.chapter_grid_cols = cols;
@@ -696,61 +2119,81 @@ fn draw_manga_detail(f: &mut Frame, app: &mut App) {
             app.chapter_selected = max_idx;
         }
         
-        // Calculate which row the selected chapter is in
-        let selected_row = app.chapter_selected / cols;
+        let grid_rows = app.chapter_grid_rows();
+        let row_height = |row: &GridRow| match row {
+            GridRow::Header { .. } => VOLUME_HEADER_HEIGHT,
+            GridRow::Chapters(_) => card_height,
+        };
+        let selected_row = app.selected_grid_row(&grid_rows);
         
         // Adjust scroll to keep selection visible
         if selected_row < app.chapter_scroll_row {
             app.chapter_scroll_row = selected_row;
-        } else if selected_row >= app.chapter_scroll_row + rows {
-            app.chapter_scroll_row = selected_row - rows + 1;
+        }
+        while app.chapter_scroll_row < selected_row
+            && grid_rows[app.chapter_scroll_row..=selected_row].iter().map(row_height).sum::<u16>() > chapters_inner.height
+        {
+            app.chapter_scroll_row += 1;
         }
         
-        // Create row layout
-        let row_constraints: Vec<Constraint> = (0..rows)
-            .map(|_| Constraint::Length(CHAPTER_CARD_HEIGHT))
-            .collect();
-        
-        let row_areas = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(row_constraints)
-            .split(chapters_inner);
-        
-        // Render each row
-        for (row_idx, row_area) in row_areas.iter().enumerate() {
-            let actual_row = app.chapter_scroll_row + row_idx;
-            let start_idx = actual_row * cols;
-            
-            if start_idx >= app.chapters.len() {
+        // Render rows top to bottom until the panel is full
+        let mut y = chapters_inner.y;
+        let bottom = chapters_inner.y + chapters_inner.height;
+        let mut last_drawn_row = app.chapter_scroll_row;
+        for (row_idx, row) in grid_rows.iter().enumerate().skip(app.chapter_scroll_row) {
+            let height = row_height(row);
+            if y + height > bottom && row_idx > app.chapter_scroll_row {
                 break;
             }
+            let row_area = Rect::new(chapters_inner.x, y, chapters_inner.width, height.min(bottom - y));
+            y += height;
+            last_drawn_row = row_idx;
             
-            // Create column layout for this row
-            let col_constraints: Vec<Constraint> = (0..cols)
-                .map(|_| Constraint::Length(CHAPTER_CARD_WIDTH))
-                .collect();
-            
-            let col_areas = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints(col_constraints)
-                .split(*row_area);
-            
-            for (col_idx, col_area) in col_areas.iter().enumerate() {
-                let chapter_idx = start_idx + col_idx;
-                if chapter_idx >= app.chapters.len() {
-                    break;
+            match row {
+                GridRow::Header { volume, count, collapsed } => {
+                    let selected = app.selected_volume_header.as_ref() == Some(volume);
+                    let arrow = if *collapsed { "▶" } else { "▼" };
+                    let style = if selected {
+                        Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                    };
+                    let label = format!("{} {} ({} chapters)", arrow, volume_label(volume), count);
+                    f.render_widget(Paragraph::new(label).style(style), row_area);
+                }
+                GridRow::Chapters(indices) => {
+                    // Create column layout for this row
+                    let col_constraints: Vec<Constraint> = (0..cols)
+                        .map(|_| Constraint::Length(CHAPTER_CARD_WIDTH))
+                        .collect();
+                    
+                    let col_areas = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints(col_constraints)
+                        .split(row_area);
+                    
+                    let selection = app.chapter_selection();
+                    for (col_area, &chapter_idx) in col_areas.iter().zip(indices) {
+                        let chapter = &app.chapters[chapter_idx];
+                        let is_selected = app.selected_volume_header.is_none() && chapter_idx == app.chapter_selected;
+                        let in_range = selection.as_ref().is_some_and(|r| r.contains(&chapter_idx));
+                        
+                        draw_chapter_card(
+                            f,
+                            *col_area,
+                            chapter,
+                            is_selected,
+                            in_range,
+                            app.progress.is_read(&chapter.id),
+                            app.new_chapters.contains(&chapter.id),
+                            app.show_chapter_thumbnails,
+                            chapter.is_from_other_group(&app.config.preferred_groups),
+                            app.config.reading_secs_per_page,
+                            app.chapter_thumbnails.get_mut(&chapter.id),
+                            filter,
+                        );
+                    }
                 }
-                
-                let chapter = &app.chapters[chapter_idx];
-                let is_selected = chapter_idx == app.chapter_selected;
-                
-                draw_chapter_card(
-                    f,
-                    *col_area,
-                    chapter,
-                    is_selected,
-                    app.chapter_thumbnails.get_mut(&chapter.id),
-                );
             }
         }
         
@@ -762,8 +2205,7 @@ fn draw_manga_detail(f: &mut Frame, app: &mut App) {
             f.render_widget(up, Rect::new(chapters_inner.x, chapters_inner.y, chapters_inner.width, 1));
         }
         
-        let total_rows = (app.chapters.len() + cols - 1) / cols;
-        if app.chapter_scroll_row + rows < total_rows {
+        if last_drawn_row + 1 < grid_rows.len() {
             let down = Paragraph::new("▼ more")
                 .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
                 .alignment(Alignment::Center);
@@ -776,11 +2218,108 @@ fn draw_manga_detail(f: &mut Frame, app: &mut App) {
     } else {
         "b: bookmark"
     };
-    draw_footer(f, root[2], &format!("←/→: navigate | Enter: read | {} | Esc: back | q: quit", bookmark_hint));
+    let range_hint = if app.chapter_selection_anchor.is_some() {
+        "m: clear range | D: download range | u: mark range read/unread"
+    } else {
+        "m: mark range | D: download | u: read/unread"
+    };
+    let tag_hint = if app.selected_tag.is_some() {
+        "t: next tag | Enter: search tag"
+    } else {
+        "t: tags"
+    };
+    draw_footer(f, root[2], &format!("←/→: navigate | Enter: read | {} | {} | c: continue | x: random unread | s: sort | {} | g: group by volume | i: thumbnails | z: zoom cover | o: open in browser | Esc: back | q: quit", bookmark_hint, tag_hint, range_hint));
+}
+
+/// The cover, author, status, rating, tags and description of a manga in a bordered panel.
+/// `selected_tag` is highlighted.
+fn draw_manga_info(
+    f: &mut Frame,
+    area: Rect,
+    title: &str,
+    manga: &Manga,
+    cover: Option<&mut StatefulProtocol>,
+    selected_tag: Option<usize>,
+    filter: Option<FilterType>,
+) {
+    let info_block = Block::default()
+        .borders(Borders::ALL)
+        .title(title.to_string())
+        .border_style(Style::default().fg(Color::Yellow));
+
+    let info_inner = info_block.inner(area);
+    f.render_widget(info_block, area);
+
+    let info_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(12), // cover image
+            Constraint::Min(5),     // details
+        ])
+        .split(info_inner);
+
+    // Cover image
+    if let Some(state) = cover {
+        let image_widget = StatefulImage::new().resize(Resize::Fit(filter));
+        f.render_stateful_widget(image_widget, info_layout[0], state);
+    } else {
+        let placeholder = Paragraph::new("📚 Loading cover...")
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(placeholder, info_layout[0]);
+    }
+
+    // Manga details
+    let mut details = Vec::new();
+    if let Some(alt_title) = manga.alt_titles.first() {
+        details.push(Line::from(Span::styled(
+            truncate_text(alt_title, info_layout[1].width as usize),
+            Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+        )));
+    }
+    details.extend([
+        Line::from(vec![
+            Span::styled("Author: ", Style::default().fg(Color::Yellow)),
+            Span::raw(&manga.author),
+        ]),
+        Line::from(vec![
+            Span::styled("Status: ", Style::default().fg(Color::Yellow)),
+            Span::styled(&manga.status, Style::default().fg(Color::Cyan)),
+        ]),
+    ]);
+    if !manga.content_rating.is_empty() {
+        details.push(Line::from(vec![
+            Span::styled("Rating: ", Style::default().fg(Color::Yellow)),
+            Span::raw(&manga.content_rating),
+        ]));
+    }
+    if !manga.tags.is_empty() {
+        let mut spans = vec![Span::styled("Tags: ", Style::default().fg(Color::Yellow))];
+        for (i, tag) in manga.tags.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::raw(", "));
+            }
+            let style = if selected_tag == Some(i) {
+                Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Magenta)
+            };
+            spans.push(Span::styled(tag.name.as_str(), style));
+        }
+        details.push(Line::from(spans));
+    }
+    details.extend([
+        Line::from(""),
+        Line::from(Span::styled("Description:", Style::default().fg(Color::Yellow))),
+    ]);
+    details.extend(markdown_to_text(&manga.description).lines);
+    let details_paragraph = Paragraph::new(details).wrap(Wrap { trim: false });
+    f.render_widget(details_paragraph, info_layout[1]);
 }
 
 fn draw_reader(f: &mut Frame, app: &mut App) {
     let area = f.area();
+    let filter = app.config.resize_filter.filter_type();
 
     let root = Layout::default()
         .direction(Direction::Vertical)
@@ -793,38 +2332,103 @@ fn draw_reader(f: &mut Frame, app: &mut App) {
 
     // Header with chapter info
     let chapter_info = if let Some(chapter) = app.reader.chapters.get(app.reader.current_chapter_idx) {
+        let prefetch_info = app
+            .reader
+            .prefetch
+            .as_ref()
+            .map(|p| match p.failed {
+                0 => format!(" | Prefetching {}/{}", p.done, p.total),
+                failed => format!(" | Prefetching {}/{}, {} failed", p.done, p.total, failed),
+            })
+            .unwrap_or_default();
+        let invert_info = if app.reader.invert { " | Inverted" } else { "" };
+        let auto_scroll_info = if app.reader.auto_scroll.is_some() { " | Auto-scroll" } else { "" };
+        let fit_info = match app.reader.fit {
+            PageFit::Whole => String::new(),
+            fit => format!(" | {}", fit.label()),
+        };
+        // Until the page list arrives, the chapter's listed page count is the best total
+        let page_info = match (app.reader.page_urls.len(), chapter.pages) {
+            (0, 0) => "Loading page list".to_string(),
+            (0, pages) => format!("Page {}/{} (loading page list)", app.reader.current_page + 1, pages),
+            (total, _) => format!("Page {}/{}", app.reader.current_page + 1, total),
+        };
         format!(
-            "Chapter {} - {} | Page {}/{}",
-            chapter.chapter,
+            "{} - {} | {}{}{}{}{}",
+            chapter.long_label(),
             chapter.title,
-            app.reader.current_page + 1,
-            app.reader.page_urls.len().max(1)
+            page_info,
+            fit_info,
+            invert_info,
+            auto_scroll_info,
+            prefetch_info
         )
     } else {
         "Loading...".to_string()
     };
 
+    let mut header_block = Block::default()
+        .borders(Borders::ALL)
+        .title("Reader")
+        .border_style(Style::default().fg(Color::Cyan));
+    if app.config.show_page_cache_status && !app.reader.page_urls.is_empty() {
+        header_block = header_block.title_bottom(page_cache_dots(&app.reader).right_aligned());
+    }
     let header = Paragraph::new(chapter_info)
         .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Reader")
-                .border_style(Style::default().fg(Color::Cyan)),
-        );
+        .block(header_block);
     f.render_widget(header, root[0]);
 
-    // Page content
+    // Page content, with an optional sidebar beside it and thumbnail strip underneath
+    let content_area = if app.reader.show_sidebar {
+        let split = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Min(20),                      // page
+                Constraint::Length(READER_SIDEBAR_WIDTH), // sidebar
+            ])
+            .split(root[1]);
+        draw_reader_sidebar(f, split[1], app);
+        split[0]
+    } else {
+        root[1]
+    };
+    let (page_area, strip_area) = if app.reader.show_thumbnails {
+        let split = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(5),                          // page
+                Constraint::Length(THUMBNAIL_STRIP_HEIGHT), // thumbnails
+            ])
+            .split(content_area);
+        (split[0], Some(split[1]))
+    } else {
+        (content_area, None)
+    };
+
     let content_block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::DarkGray));
 
-    let inner = content_block.inner(root[1]);
-    f.render_widget(content_block, root[1]);
+    let inner = content_block.inner(page_area);
+    f.render_widget(content_block, page_area);
 
     if app.reader.loading {
-        let loading = Paragraph::new("⏳ Loading page...")
+        let current_url = app.reader.page_urls.get(app.reader.current_page);
+        let message = match &app.reader.retry_status {
+            Some((url, retry, retries)) if Some(url) == current_url => {
+                format!("⏳ Loading page (retry {}/{})...", retry, retries)
+            }
+            _ if app.reader.page_urls.is_empty() => {
+                match app.reader.chapters.get(app.reader.current_chapter_idx) {
+                    Some(chapter) => format!("⏳ Loading {}...", chapter.long_label()),
+                    None => "⏳ Loading page...".to_string(),
+                }
+            }
+            _ => "⏳ Loading page...".to_string(),
+        };
+        let loading = Paragraph::new(message)
             .alignment(Alignment::Center)
             .style(Style::default().fg(Color::Yellow));
         f.render_widget(loading, inner);
@@ -833,9 +2437,26 @@ fn draw_reader(f: &mut Frame, app: &mut App) {
             .alignment(Alignment::Center)
             .style(Style::default().fg(Color::Red));
         f.render_widget(error_text, inner);
-    } else if let Some(ref mut state) = app.reader.page_image {
-        let image_widget = StatefulImage::new().resize(Resize::Fit(None));
-        f.render_stateful_widget(image_widget, inner, state);
+    } else if app.reader.chapter_list.is_some()
+        || app.explicit_prompt
+        || app.quit_prompt
+        || app.resume_download_prompt.is_some()
+        || app.command_palette.is_some()
+    {
+        // Graphics protocols can draw over popups, so leave the page blank while one is open
+    } else if app.reader.manga.as_ref().is_some_and(Manga::is_pornographic)
+        && app.explicit_consent != Some(true)
+    {
+        let hidden = Paragraph::new("🔞 Explicit content is hidden for this session")
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hidden, inner);
+    } else if app.reader.page_source.is_some() {
+        app.refresh_page_view(inner);
+        if let Some(ref mut state) = app.reader.page_image {
+            let image_widget = StatefulImage::new().resize(Resize::Fit(filter));
+            f.render_stateful_widget(image_widget, inner, state);
+        }
     } else {
         let error = Paragraph::new("No page to display")
             .alignment(Alignment::Center)
@@ -843,20 +2464,242 @@ fn draw_reader(f: &mut Frame, app: &mut App) {
         f.render_widget(error, inner);
     }
 
-    let footer_hint = if app.reader.error.is_some() {
-        "←/→: page | n: next ch | p: prev ch | r: retry | Esc: back | q: quit"
+    if let Some(strip_area) = strip_area {
+        draw_thumbnail_strip(f, strip_area, &mut app.reader, filter);
+    }
+
+    if app.reader.chapter_list.is_some() {
+        draw_chapter_list_popup(f, root[1], app);
+    }
+
+    let footer_hint = if app.reader.chapter_list.is_some() {
+        "↑/↓: select | Enter: open chapter | Esc: close".to_string()
+    } else if !app.reader.page_jump_input.is_empty() {
+        format!("Go to page: {} | Enter: jump | Esc: cancel", app.reader.page_jump_input)
+    } else if app.reader.error.is_some() {
+        "←/→: page | n: next ch | p: prev ch | t: thumbnails | r: retry | b: report | Esc: back | q: quit".to_string()
+    } else {
+        let keys = "←/→: page | n: next ch | p: prev ch | c: chapters | t: thumbnails | s: sidebar | i: invert | f: fit | ↑/↓: scroll | a: auto-scroll | D: prefetch chapter | 0-9: go to page | o: open in browser | y: copy page URL | Esc: back | q: quit";
+        match chapter_end_hint(app) {
+            Some(hint) => format!("{} | {}", hint, keys),
+            None => keys.to_string(),
+        }
+    };
+    draw_footer(f, root[2], &footer_hint);
+}
+
+const READER_SIDEBAR_WIDTH: u16 = 30;
+
+/// Pages from the current one onward shown in the cache status dots
+const CACHE_STATUS_PAGES: usize = 10;
+
+/// A dot per page from the current one: green when cached, grey when it will need the network.
+fn page_cache_dots(reader: &ReaderState) -> Line<'static> {
+    let mut spans = vec![Span::styled(" Cache ", Style::default().fg(Color::DarkGray))];
+    for url in reader.page_urls.iter().skip(reader.current_page).take(CACHE_STATUS_PAGES) {
+        let color = if reader.cached_pages.contains(url) { Color::Green } else { Color::DarkGray };
+        spans.push(Span::styled("●", Style::default().fg(color)));
+    }
+    spans.push(Span::raw(" "));
+    Line::from(spans)
+}
+
+/// The manga, chapter and position being read, with the chapters either side.
+fn draw_reader_sidebar(f: &mut Frame, area: Rect, app: &App) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Reading")
+        .border_style(Style::default().fg(Color::DarkGray));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let reader = &app.reader;
+    let label = |text: &'static str| Line::from(Span::styled(text, Style::default().fg(Color::Yellow)));
+    let mut lines = Vec::new();
+    if let Some(manga) = &reader.manga {
+        lines.push(label("Manga"));
+        lines.push(Line::from(manga.title.as_str()));
+        lines.push(Line::from(""));
+    }
+    if let Some(chapter) = reader.chapters.get(reader.current_chapter_idx) {
+        lines.push(label("Chapter"));
+        let volume = chapter.volume.as_ref().map(|v| format!("Vol. {} ", v)).unwrap_or_default();
+        lines.push(Line::from(format!("{}{}", volume, chapter.long_label())));
+        if !chapter.title.is_empty() {
+            lines.push(Line::from(Span::styled(chapter.title.as_str(), Style::default().fg(Color::Cyan))));
+        }
+        if !chapter.groups.is_empty() {
+            lines.push(Line::from(Span::styled(chapter.group_names(), Style::default().fg(Color::DarkGray))));
+        }
+        lines.push(Line::from(""));
+    }
+
+    lines.push(label("Page"));
+    let total = reader.page_urls.len();
+    lines.push(Line::from(if total == 0 {
+        "Loading...".to_string()
     } else {
-        "←/→: page | n: next ch | p: prev ch | Esc: back | q: quit"
+        format!("{} of {} ({}%)", reader.current_page + 1, total, (reader.current_page + 1) * 100 / total)
+    }));
+    lines.push(Line::from(""));
+
+    let neighbour = |idx: Option<usize>| {
+        idx.and_then(|i| reader.chapters.get(i))
+            .map_or_else(|| "None".to_string(), Chapter::long_label)
     };
-    draw_footer(f, root[2], footer_hint);
+    lines.push(label("Previous (p)"));
+    lines.push(Line::from(neighbour(reader.current_chapter_idx.checked_sub(1))));
+    lines.push(label("Next (n)"));
+    lines.push(Line::from(neighbour(Some(reader.current_chapter_idx + 1))));
+
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), inner);
+}
+
+/// A heads-up in the last `chapter_end_warning_pages` pages of a chapter.
+fn chapter_end_hint(app: &App) -> Option<String> {
+    let total = app.reader.page_urls.len();
+    let left = total.checked_sub(app.reader.current_page + 1)?;
+    if left >= app.config.chapter_end_warning_pages {
+        return None;
+    }
+    let has_next = app.reader.current_chapter_idx + 1 < app.reader.chapters.len();
+    Some(match (left, has_next) {
+        (0, true) => "Last page, press n for next chapter".to_string(),
+        (0, false) => "Last page of the last chapter".to_string(),
+        (1, _) => "1 page left".to_string(),
+        (left, _) => format!("{} pages left", left),
+    })
+}
+
+fn draw_chapter_list_popup(f: &mut Frame, area: Rect, app: &mut App) {
+    let width = (area.width * 6 / 10).clamp(20.min(area.width), area.width);
+    let height = (area.height * 8 / 10).max(3.min(area.height));
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let current = app.reader.current_chapter_idx;
+    let items: Vec<ListItem> = app
+        .reader
+        .chapters
+        .iter()
+        .enumerate()
+        .map(|(idx, chapter)| {
+            let marker = if idx == current { "▶ " } else { "  " };
+            let read = if app.progress.is_read(&chapter.id) { "✓ " } else { "" };
+            let external = if chapter.external_url.is_some() { " 🔗" } else { "" };
+            let style = if idx == current {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else if !read.is_empty() {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default()
+            };
+            ListItem::new(format!("{}{}{} - {}{}", marker, read, chapter.long_label(), chapter.title, external))
+                .style(style)
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Chapters ({})", app.reader.chapters.len()))
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+
+    f.render_widget(Clear, popup);
+    if let Some(state) = app.reader.chapter_list.as_mut() {
+        f.render_stateful_widget(list, popup, state);
+    }
+}
+
+const THUMBNAIL_STRIP_HEIGHT: u16 = 7;
+const THUMBNAIL_WIDTH: u16 = 7;
+
+fn draw_thumbnail_strip(f: &mut Frame, area: Rect, reader: &mut ReaderState, filter: Option<FilterType>) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Pages")
+        .border_style(Style::default().fg(Color::DarkGray));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let total = reader.page_urls.len();
+    if total == 0 || inner.width < THUMBNAIL_WIDTH {
+        return;
+    }
+
+    // Keep the current page centered in the strip where possible
+    let visible = ((inner.width / THUMBNAIL_WIDTH) as usize).min(total);
+    let start = reader
+        .current_page
+        .saturating_sub(visible / 2)
+        .min(total - visible);
+
+    let cell_constraints: Vec<Constraint> = (0..visible)
+        .map(|_| Constraint::Length(THUMBNAIL_WIDTH))
+        .collect();
+
+    let cells = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(cell_constraints)
+        .split(inner);
+
+    for (i, cell) in cells.iter().enumerate() {
+        let page_idx = start + i;
+        let is_current = page_idx == reader.current_page;
+
+        let cell_block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("{}", page_idx + 1))
+            .border_style(if is_current {
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            });
+
+        let cell_inner = cell_block.inner(*cell);
+        f.render_widget(cell_block, *cell);
+
+        let thumbnail = reader
+            .page_urls
+            .get(page_idx)
+            .and_then(|url| reader.page_thumbnails.get_mut(url));
+
+        if let Some(state) = thumbnail {
+            let image_widget = StatefulImage::new().resize(Resize::Fit(filter));
+            f.render_stateful_widget(image_widget, cell_inner, state);
+        } else {
+            let placeholder = Paragraph::new("·")
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::DarkGray));
+            f.render_widget(placeholder, cell_inner);
+        }
+    }
 }
 
 fn draw_header(f: &mut Frame, area: Rect, app: &App) {
-    let titles = vec!["Home", "Bookmarks", "Search"];
+    let updates = app.bookmarks.updated_ids.len();
+    let bookmarks_title = if updates > 0 {
+        Line::from(vec![
+            Span::raw("Bookmarks "),
+            Span::styled(format!("●{}", updates), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+        ])
+    } else {
+        Line::from("Bookmarks")
+    };
+    let titles = vec![Line::from("Home"), bookmarks_title, Line::from("Search"), Line::from("Local")];
     let selected = match app.tab {
         Tab::Home => 0,
         Tab::Bookmarks => 1,
         Tab::Search => 2,
+        Tab::Local => 3,
     };
 
     let header_style = if app.focus == Focus::Header {
@@ -867,11 +2710,12 @@ fn draw_header(f: &mut Frame, area: Rect, app: &App) {
         Style::default().fg(Color::White)
     };
 
+    let title = if app.refreshing { "Manga Reader ⟳ Refreshing..." } else { "Manga Reader" };
     let tabs = Tabs::new(titles)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Manga Reader")
+                .title(title)
                 .border_style(Style::default().fg(Color::Cyan)),
         )
         .select(selected)
@@ -881,6 +2725,7 @@ fn draw_header(f: &mut Frame, area: Rect, app: &App) {
     f.render_widget(tabs, area);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn draw_manga_section(
     f: &mut Frame,
     area: Rect,
@@ -888,7 +2733,11 @@ fn draw_manga_section(
     mangas: &[Manga],
     offset: &mut usize,
     focused: bool,
-    image_states: &mut HashMap<String, StatefulProtocol>,
+    image_states: &mut LruMap<StatefulProtocol>,
+    progress: &ProgressStore,
+    bookmarks: &Bookmarks,
+    filter: Option<FilterType>,
+    cover_rows: u16,
 ) {
     let block = Block::default()
         .borders(Borders::ALL)
@@ -943,7 +2792,12 @@ fn draw_manga_section(
             *card_area,
             manga,
             focused && i == 0,
+            progress.is_completed(&manga.id),
+            false,
+            bookmarks.status(&manga.id),
             image_states.get_mut(&manga.id),
+            filter,
+            cover_rows,
         );
     }
 
@@ -974,12 +2828,18 @@ fn draw_manga_section(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn draw_manga_card(
     f: &mut Frame,
     area: Rect,
     manga: &Manga,
     selected: bool,
+    completed: bool,
+    pinned: bool,
+    status: Option<ReadingStatus>,
     image_state: Option<&mut StatefulProtocol>,
+    filter: Option<FilterType>,
+    cover_rows: u16,
 ) {
     let border_style = if selected {
         Style::default()
@@ -1000,11 +2860,14 @@ fn draw_manga_card(
         return;
     }
 
+    // Leave room for the title, two lines of description and the status line
+    let cover_rows = cover_rows.clamp(3, inner.height.saturating_sub(5).max(3));
+
     // Layout: image, title, description, rating
     let card_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(8), // image (larger for cover)
+            Constraint::Length(cover_rows), // image
             Constraint::Length(2), // title
             Constraint::Min(2),    // description
             Constraint::Length(1), // rating/status
@@ -1013,7 +2876,7 @@ fn draw_manga_card(
 
     // Render cover image or placeholder
     if let Some(state) = image_state {
-        let image_widget = StatefulImage::new().resize(Resize::Scale(None));
+        let image_widget = StatefulImage::new().resize(Resize::Scale(filter));
         f.render_stateful_widget(image_widget, card_layout[0], state);
     } else {
         // Placeholder when image not loaded
@@ -1036,7 +2899,12 @@ fn draw_manga_card(
     }
 
     // Title (truncated)
-    let title = truncate_text(&manga.title, (inner.width.saturating_sub(2)) as usize);
+    let title = if pinned {
+        format!("📌 {}", manga.title)
+    } else {
+        manga.title.clone()
+    };
+    let title = truncate_text(&title, (inner.width.saturating_sub(2)) as usize);
     let title_paragraph = Paragraph::new(title)
         .style(
             Style::default()
@@ -1049,31 +2917,71 @@ fn draw_manga_card(
     // Description (truncated, multi-line)
     let desc_width = inner.width.saturating_sub(1) as usize;
     let max_desc_lines = card_layout[2].height.saturating_sub(0) as usize;
-    let desc_lines = wrap_text(&manga.description, desc_width, max_desc_lines.max(1));
+    let desc_lines = wrap_text(&markdown_to_plain(&manga.description), desc_width, max_desc_lines.max(1));
     let desc_paragraph =
         Paragraph::new(desc_lines.join("\n")).style(Style::default().fg(Color::DarkGray));
     f.render_widget(desc_paragraph, card_layout[2]);
 
     // Rating/Status line
-    let rating_line = Line::from(vec![
+    let mut rating_spans = vec![
         Span::styled("★ ", Style::default().fg(Color::Yellow)),
         Span::styled(&manga.status, Style::default().fg(Color::Cyan)),
-    ]);
+    ];
+    if completed {
+        rating_spans.push(Span::styled(
+            "  Completed ✓",
+            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+        ));
+    }
+    let rating_line = Line::from(rating_spans);
     let rating_paragraph = Paragraph::new(rating_line);
     f.render_widget(rating_paragraph, card_layout[3]);
+
+    // Reading status tag on the top border, clear of the NEW badge on the right
+    if let Some(status) = status {
+        let tag = format!(" {} ", status.label());
+        let width = (tag.width() as u16).min(area.width.saturating_sub(2));
+        let tag_area = Rect::new(area.x + 1, area.y, width, 1);
+        let label = Paragraph::new(tag).style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(reading_status_color(status))
+                .add_modifier(Modifier::BOLD),
+        );
+        f.render_widget(label, tag_area);
+    }
+}
+
+fn reading_status_color(status: ReadingStatus) -> Color {
+    match status {
+        ReadingStatus::Reading => Color::Cyan,
+        ReadingStatus::PlanToRead => Color::Yellow,
+        ReadingStatus::Completed => Color::Green,
+        ReadingStatus::Dropped => Color::Red,
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn draw_chapter_card(
     f: &mut Frame,
     area: Rect,
     chapter: &Chapter,
     selected: bool,
+    in_range: bool,
+    read: bool,
+    new: bool,
+    show_thumbnail: bool,
+    other_group: bool,
+    secs_per_page: u64,
     image_state: Option<&mut StatefulProtocol>,
+    filter: Option<FilterType>,
 ) {
     let border_style = if selected {
         Style::default()
             .fg(Color::Cyan)
             .add_modifier(Modifier::BOLD)
+    } else if in_range {
+        Style::default().fg(Color::Magenta)
     } else {
         Style::default().fg(Color::DarkGray)
     };
@@ -1089,42 +2997,57 @@ fn draw_chapter_card(
         return;
     }
 
-    // Layout: image, chapter number, title, pages
+    // Layout: image, chapter number, title, pages (text-only cards drop the image)
+    let image_height = if show_thumbnail { 6 } else { 0 };
     let card_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Min(6),    // image
-            Constraint::Length(1), // chapter number
-            Constraint::Length(2), // title
-            Constraint::Length(1), // pages
+            Constraint::Min(image_height), // image
+            Constraint::Length(1),         // chapter number
+            Constraint::Length(2),         // title
+            Constraint::Length(1),         // pages
         ])
         .split(inner);
 
     // Render cover image or placeholder
-    if let Some(state) = image_state {
-        let image_widget = StatefulImage::new().resize(Resize::Fit(None));
-        f.render_stateful_widget(image_widget, card_layout[0], state);
-    } else if chapter.external_url.is_some() {
-        let placeholder = Paragraph::new("🔗\nExternal")
-            .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::Magenta));
-        f.render_widget(placeholder, card_layout[0]);
-    } else {
-        let placeholder = Paragraph::new("📖\nLoading...")
-            .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::DarkGray));
-        f.render_widget(placeholder, card_layout[0]);
+    if show_thumbnail {
+        if let Some(state) = image_state {
+            let image_widget = StatefulImage::new().resize(Resize::Fit(filter));
+            f.render_stateful_widget(image_widget, card_layout[0], state);
+        } else if chapter.external_url.is_some() {
+            let placeholder = Paragraph::new("🔗\nExternal")
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::Magenta));
+            f.render_widget(placeholder, card_layout[0]);
+        } else {
+            let placeholder = Paragraph::new("📖\nLoading...")
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::DarkGray));
+            f.render_widget(placeholder, card_layout[0]);
+        }
     }
 
     // Chapter number
     let vol = chapter.volume.as_ref().map(|v| format!("V{} ", v)).unwrap_or_default();
-    let chapter_num = format!("{}Ch.{}", vol, chapter.chapter);
+    let read_marker = if read {
+        "✓ "
+    } else if new {
+        "NEW "
+    } else {
+        ""
+    };
+    let chapter_num = format!("{}{}{}", read_marker, vol, chapter.short_label());
+    let number_color = if chapter.other_language {
+        Color::DarkGray
+    } else if read {
+        Color::Green
+    } else if new {
+        Color::LightMagenta
+    } else {
+        Color::Yellow
+    };
     let chapter_paragraph = Paragraph::new(truncate_text(&chapter_num, inner.width as usize))
-        .style(
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )
+        .style(Style::default().fg(number_color).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center);
     f.render_widget(chapter_paragraph, card_layout[1]);
 
@@ -1135,68 +3058,30 @@ fn draw_chapter_card(
         chapter.title.clone()
     };
     let title_lines = wrap_text(&title, inner.width as usize, 2);
+    let title_color = if chapter.other_language { Color::DarkGray } else { Color::White };
     let title_paragraph = Paragraph::new(title_lines.join("\n"))
-        .style(Style::default().fg(Color::White))
+        .style(Style::default().fg(title_color))
         .alignment(Alignment::Center);
     f.render_widget(title_paragraph, card_layout[2]);
 
-    // Pages
-    let pages_text = format!("{} pages", chapter.pages);
-    let pages_paragraph = Paragraph::new(pages_text)
-        .style(Style::default().fg(Color::DarkGray))
+    // Pages, or a note when only a non-preferred group or language has this chapter
+    let (pages_text, pages_color) = if chapter.other_language {
+        (format!("[{}] {} pages", chapter.language, chapter.pages), Color::DarkGray)
+    } else if other_group {
+        (format!("⚠ {}", chapter.group_names()), Color::LightRed)
+    } else if secs_per_page > 0 && chapter.pages > 0 {
+        let secs = chapter.pages as u64 * secs_per_page;
+        // Rounded up, so short chapters don't claim to take no time at all
+        (format!("{} pages · ~{}", chapter.pages, format_duration(secs.div_ceil(60) * 60)), Color::DarkGray)
+    } else {
+        (format!("{} pages", chapter.pages), Color::DarkGray)
+    };
+    let pages_paragraph = Paragraph::new(truncate_text(&pages_text, inner.width as usize))
+        .style(Style::default().fg(pages_color))
         .alignment(Alignment::Center);
     f.render_widget(pages_paragraph, card_layout[3]);
 }
 
-fn truncate_text(text: &str, max_len: usize) -> String {
-    if text.chars().count() <= max_len {
-        text.to_string()
-    } else {
-        format!(
-            "{}...",
-            text.chars()
-                .take(max_len.saturating_sub(3))
-                .collect::<String>()
-        )
-    }
-}
-
-fn wrap_text(text: &str, width: usize, max_lines: usize) -> Vec<String> {
-    if width == 0 || max_lines == 0 {
-        return vec![];
-    }
-
-    let mut lines = Vec::new();
-    let mut current_line = String::new();
-
-    for word in text.split_whitespace() {
-        if current_line.is_empty() {
-            current_line = word.to_string();
-        } else if current_line.chars().count() + 1 + word.chars().count() <= width {
-            current_line.push(' ');
-            current_line.push_str(word);
-        } else {
-            lines.push(current_line);
-            if lines.len() >= max_lines {
-                if let Some(last) = lines.last_mut() {
-                    let char_count = last.chars().count();
-                    if char_count > 3 {
-                        *last = last.chars().take(char_count - 3).collect::<String>() + "...";
-                    }
-                }
-                return lines;
-            }
-            current_line = word.to_string();
-        }
-    }
-
-    if !current_line.is_empty() && lines.len() < max_lines {
-        lines.push(current_line);
-    }
-
-    lines
-}
-
 fn draw_footer(f: &mut Frame, area: Rect, help_text: &str) {
     let spans: Vec<Span> = help_text
         .split(" | ")
@@ -1217,12 +3102,34 @@ fn draw_footer(f: &mut Frame, area: Rect, help_text: &str) {
 
     let text = Line::from(spans);
 
+    let usage = Line::from(Span::styled(
+        format!(" ↓ {} this session ", format_bytes(bytes_downloaded())),
+        Style::default().fg(Color::DarkGray),
+    ))
+    .right_aligned();
+
     let p = Paragraph::new(text)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan)),
+                .border_style(Style::default().fg(Color::Cyan))
+                .title_bottom(usage),
         )
         .alignment(Alignment::Center);
     f.render_widget(p, area);
 }
+
+pub fn format_bytes(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    const GB: f64 = MB * 1024.0;
+
+    let bytes = bytes as f64;
+    if bytes >= GB {
+        format!("{:.2} GB", bytes / GB)
+    } else if bytes >= MB {
+        format!("{:.1} MB", bytes / MB)
+    } else {
+        format!("{:.0} KB", bytes / KB)
+    }
+}